@@ -0,0 +1,270 @@
+use crate::tree::BinaryTree;
+use core::borrow::Borrow;
+use core::ops::Deref;
+
+/// *English*: Balance factor of the tree inside bounded tree
+///
+/// *Russian*: Коэффициент балансировки дерева внутри ограниченного дерева
+
+const BOUNDED_BALANCE_FACTOR: f64 = 2.0;
+
+/// *English*: **Evict** tells **BoundedTree**, which element must be removed,
+/// when there are more elements than capacity.
+///
+/// *Russian*: **Evict** говорит **BoundedTree**, какой элемент нужно удалить,
+/// когда элементов становится больше, чем вместимость.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Evict {
+	/// *English*: Remove the smallest element, so tree keeps *top-K* largest ones
+	///
+	/// *Russian*: Удалять наименьший элемент, так что дерево хранит *K* наибольших
+	Min,
+	
+	/// *English*: Remove the largest element, so tree keeps *top-K* smallest ones
+	///
+	/// *Russian*: Удалять наибольший элемент, так что дерево хранит *K* наименьших
+	Max,
+}
+
+/// *English*: **BoundedTree** is a tree with fixed *capacity*.
+/// When new element doesn't fit, the smallest or the largest element
+/// (depending on **Evict**) is removed and returned by **insert()**.
+/// All query and iteration methods of **BinaryTree** are available through **Deref**.
+/// Inner tree rebalances itself, so **insert()** takes O(log n) (amortized).
+///
+/// *Russian*: **BoundedTree** - дерево с фиксированной *вместимостью*.
+/// Когда новый элемент не помещается, наименьший или наибольший элемент
+/// (в зависимости от **Evict**) удаляется и возвращается из **insert()**.
+/// Все методы запросов и итерации **BinaryTree** доступны через **Deref**.
+/// Дерево внутри само себя балансирует, так что **insert()** занимает O(log n) (амортизированно).
+///
+/// # Example
+///
+/// ```
+/// use binartree::bounded::{BoundedTree, Evict};
+///
+/// let mut best = BoundedTree::new(3, Evict::Min);
+/// assert_eq!(best.insert(&10), None);
+/// assert_eq!(best.insert(&30), None);
+/// assert_eq!(best.insert(&20), None);
+/// assert_eq!(best.insert(&40), Some(10));
+/// assert_eq!(best.insert(&5), Some(5));
+///
+/// assert_eq!(best.to_vec(), vec![20, 30, 40]);
+/// assert_eq!(*best.first(), 20);
+/// assert!(best.contains(&30));
+/// ```
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundedTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	tree: BinaryTree<T>,
+	capacity: usize,
+	evict: Evict,
+}
+
+impl<T> BoundedTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	/// *English*: Method **new()** creates *empty* tree with given capacity and eviction side
+	///
+	/// *Russian*: Метод **new()** создаёт *пустое* дерево с данной вместимостью и стороной удаления
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::bounded::{BoundedTree, Evict};
+	///
+	/// let tree = BoundedTree::<i32>::new(10, Evict::Max);
+	/// assert_eq!(tree.capacity(), 10);
+	/// assert_eq!(tree.evict(), Evict::Max);
+	/// assert!(tree.is_empty());
+	/// ```
+	
+	pub fn new(capacity: usize, evict: Evict) -> Self {
+		BoundedTree {
+			tree: BinaryTree::with_auto_rebalance(BOUNDED_BALANCE_FACTOR),
+			capacity,
+			evict,
+		}
+	}
+	
+	/// *English*: Method **capacity()** returns max number of elements
+	///
+	/// *Russian*: Метод **capacity()** возвращает наибольшее количество элементов
+	
+	#[inline]
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+	
+	/// *English*: Method **evict()** returns eviction side
+	///
+	/// *Russian*: Метод **evict()** возвращает сторону удаления
+	
+	#[inline]
+	pub fn evict(&self) -> Evict {
+		self.evict
+	}
+	
+	/// *English*: Method **insert()** adds value to tree. If tree is full,
+	/// the evicted element is returned: it may be the new value itself,
+	/// if it's beyond all the others on the eviction side.
+	///
+	/// *Russian*: Метод **insert()** добавляет значение в дерево. Если дерево заполнено,
+	/// возвращается удалённый элемент: им может оказаться и само новое значение,
+	/// если оно дальше всех остальных со стороны удаления.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::bounded::{BoundedTree, Evict};
+	///
+	/// let mut tree = BoundedTree::new(2, Evict::Max);
+	/// assert_eq!(tree.insert(&3), None);
+	/// assert_eq!(tree.insert(&2), None);
+	/// assert_eq!(tree.insert(&1), Some(3));
+	/// assert_eq!(tree.insert(&4), Some(4));
+	/// assert_eq!(tree.to_vec(), vec![1, 2]);
+	/// ```
+	
+	pub fn insert(&mut self, val: &T) -> Option<T> {
+		if self.tree.len() < self.capacity {
+			self.tree.insert(val);
+			return None;
+		}
+		
+		match self.evict {
+			Evict::Min => match self.tree.try_first() {
+				Ok(min) if min < val => {
+					self.tree.insert(val);
+					self.tree.pop_first()
+				}
+				_ => Some(*val),
+			},
+			Evict::Max => match self.tree.try_last() {
+				Ok(max) if max > val => {
+					self.tree.insert(val);
+					self.tree.pop_last()
+				}
+				_ => Some(*val),
+			},
+		}
+	}
+	
+	/// *English*: Method **remove()** removes value from tree, if it's there
+	///
+	/// *Russian*: Метод **remove()** удаляет значение из дерева, если оно там есть
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::bounded::{BoundedTree, Evict};
+	///
+	/// let mut tree = BoundedTree::new(3, Evict::Min);
+	/// tree.extend(vec![1, 2, 3]);
+	/// tree.remove(&2);
+	/// assert_eq!(tree.to_vec(), vec![1, 3]);
+	/// ```
+	
+	#[inline]
+	pub fn remove<Q>(&mut self, val: &Q)
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.tree.remove(val);
+	}
+	
+	/// *English*: Method **pop_first()** removes and returns min value or *None*, if tree is empty
+	///
+	/// *Russian*: Метод **pop_first()** удаляет и возвращает наименьшее значение
+	/// или *None*, если дерево пустое
+	
+	#[inline]
+	pub fn pop_first(&mut self) -> Option<T> {
+		self.tree.pop_first()
+	}
+	
+	/// *English*: Method **pop_last()** removes and returns max value or *None*, if tree is empty
+	///
+	/// *Russian*: Метод **pop_last()** удаляет и возвращает наибольшее значение
+	/// или *None*, если дерево пустое
+	
+	#[inline]
+	pub fn pop_last(&mut self) -> Option<T> {
+		self.tree.pop_last()
+	}
+	
+	/// *English*: Method **clear()** removes all elements, capacity stays the same
+	///
+	/// *Russian*: Метод **clear()** удаляет все элементы, вместимость остаётся прежней
+	
+	#[inline]
+	pub fn clear(&mut self) {
+		self.tree.clear();
+	}
+	
+	/// *English*: Method **into_tree()** returns inner **BinaryTree**
+	///
+	/// *Russian*: Метод **into_tree()** возвращает внутреннее **BinaryTree**
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::bounded::{BoundedTree, Evict};
+	///
+	/// let mut tree = BoundedTree::new(2, Evict::Min);
+	/// tree.extend(1..10);
+	///
+	/// let mut inner = tree.into_tree();
+	/// inner.insert(&1);
+	/// assert_eq!(inner.to_vec(), vec![1, 8, 9]);
+	/// ```
+	
+	#[inline]
+	pub fn into_tree(self) -> BinaryTree<T> {
+		self.tree
+	}
+}
+
+/// *English*: **Deref** gives access to all *immutable* methods of **BinaryTree**
+///
+/// *Russian*: **Deref** даёт доступ ко всем *неизменяющим* методам **BinaryTree**
+///
+/// # Example
+///
+/// ```
+/// use binartree::bounded::{BoundedTree, Evict};
+///
+/// let mut tree = BoundedTree::new(4, Evict::Max);
+/// tree.extend((0..10).rev());
+///
+/// assert_eq!(tree.len(), 4);
+/// assert_eq!(*tree.last(), 3);
+/// assert_eq!(tree.iter().collect::<Vec<i32>>(), vec![0, 1, 2, 3]);
+/// ```
+
+impl<T> Deref for BoundedTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Target = BinaryTree<T>;
+	
+	fn deref(&self) -> &BinaryTree<T> {
+		&self.tree
+	}
+}
+
+/// *English*: **Extend<T>** trait for bounded tree. Evicted elements are dropped.
+///
+/// *Russian*: Трейт **Extend<T>** для ограниченного дерева. Удалённые элементы теряются.
+
+impl<T> Extend<T> for BoundedTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+		for it in iter {
+			self.insert(&it);
+		}
+	}
+}
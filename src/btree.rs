@@ -0,0 +1,1625 @@
+use crate::iter::TreeIter;
+use crate::seq::SortedSeq;
+use crate::tree::{DuplicatePolicy, filter_present, set_result};
+use crate::error::{Error, InvariantError};
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::mem;
+use core::ops::{BitAnd, BitOr, BitXor, Bound, RangeBounds};
+
+/// *English*: Default max number of children of one node.
+/// 32 children (31 keys) of *u64* take about 4 cache lines.
+///
+/// *Russian*: Наибольшее количество потомков одного узла по-умолчанию.
+/// 32 потомка (31 ключ) типа *u64* занимают около 4 кэш-линий.
+
+pub const DEFAULT_FANOUT: usize = 32;
+
+/// *English*: Node of multiway tree. Node is a *leaf*, if it has no children,
+/// otherwise it has exactly one child more than keys. All keys of child *i*
+/// lie between keys *i - 1* and *i* (equal keys may be on both sides).
+///
+/// *Russian*: Узел многопутевого дерева. Узел - *лист*, если у него нет потомков,
+/// иначе потомков ровно на один больше, чем ключей. Все ключи потомка *i*
+/// лежат между ключами *i - 1* и *i* (равные ключи могут быть с обеих сторон).
+
+#[derive(Debug, Clone)]
+struct BNode<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	keys: Vec<T>,
+	children: Vec<BNode<T>>,
+}
+
+impl<T> BNode<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn leaf(keys: Vec<T>) -> Self {
+		BNode { keys, children: vec![] }
+	}
+	
+	#[inline]
+	fn is_leaf(&self) -> bool {
+		self.children.is_empty()
+	}
+	
+	/// *English*: Builds subtree of given *height* from sorted slice.
+	/// Keys are spread evenly, so every node is at least half full.
+	///
+	/// *Russian*: Строит поддерево данной *высоты* из отсортированного среза.
+	/// Ключи распределяются равномерно, так что каждый узел заполнен хотя бы наполовину.
+	
+	fn build(src: &[T], height: usize, degree: usize) -> Self {
+		if height <= 1 {
+			return BNode::leaf(src.to_vec());
+		}
+		
+		// subtree of height h - 1 has at least degree^(h - 1) - 1 keys
+		let min_child = degree.saturating_pow(height as u32 - 1);
+		let count = ((src.len() + 1) / min_child).clamp(2, 2 * degree);
+		let per_child = (src.len() + 1 - count) / count;
+		let extra = (src.len() + 1 - count) % count;
+		
+		let mut node = BNode { keys: Vec::with_capacity(count - 1), children: Vec::with_capacity(count) };
+		let mut start = 0;
+		for i in 0..count {
+			let len = per_child + usize::from(i < extra);
+			node.children.push(BNode::build(&src[start..start + len], height - 1, degree));
+			start += len;
+			if i + 1 < count {
+				node.keys.push(src[start]);
+				start += 1;
+			}
+		}
+		node
+	}
+	
+	/// *English*: In-order keys of subtree, which are in range of comparator
+	///
+	/// *Russian*: Ключи поддерева по порядку, которые попадают в диапазон компаратора
+	
+	fn range_by<F>(&self, cmp: &mut F, result: &mut VecDeque<T>)
+		where F: FnMut(&T) -> Ordering
+	{
+		let start = self.keys.partition_point(|key| cmp(key) == Ordering::Less);
+		let end = start + self.keys[start..].partition_point(|key| cmp(key) != Ordering::Greater);
+		
+		for i in start..=end {
+			if !self.is_leaf() {
+				self.children[i].range_by(cmp, result);
+			}
+			if i < end {
+				result.push_back(self.keys[i]);
+			}
+		}
+	}
+	
+	/// *English*: The smallest key, for which predicate is *false*
+	///
+	/// *Russian*: Наименьший ключ, для которого предикат *ложен*
+	
+	fn partition_point<P>(&self, pred: &mut P) -> Option<&T>
+		where P: FnMut(&T) -> bool
+	{
+		let index = self.keys.partition_point(|key| pred(key));
+		let found = self.keys.get(index);
+		if self.is_leaf() {
+			return found;
+		}
+		self.children[index].partition_point(pred).or(found)
+	}
+	
+	/// *English*: The biggest key, for which predicate is *true*
+	///
+	/// *Russian*: Наибольший ключ, для которого предикат *истинен*
+	
+	fn last_before<P>(&self, pred: &mut P) -> Option<&T>
+		where P: FnMut(&T) -> bool
+	{
+		let index = self.keys.partition_point(|key| pred(key));
+		let found = index.checked_sub(1).map(|index| &self.keys[index]);
+		if self.is_leaf() {
+			return found;
+		}
+		self.children[index].last_before(pred).or(found)
+	}
+	
+	/// *English*: Any key, for which comparator returns *Equal*
+	///
+	/// *Russian*: Любой ключ, для которого компаратор возвращает *Equal*
+	
+	fn find_by<F>(&self, cmp: &mut F) -> Option<&T>
+		where F: FnMut(&T) -> Ordering
+	{
+		match self.keys.binary_search_by(&mut *cmp) {
+			Ok(index) => Some(&self.keys[index]),
+			Err(_) if self.is_leaf() => None,
+			Err(index) => self.children[index].find_by(cmp),
+		}
+	}
+	
+	/// *English*: Mutable reference to any key, which is equal to value
+	///
+	/// *Russian*: Изменяемая ссылка на любой ключ, равный значению
+	
+	fn find_mut(&mut self, val: &T) -> Option<&mut T> {
+		match self.keys.binary_search(val) {
+			Ok(index) => Some(&mut self.keys[index]),
+			Err(_) if self.is_leaf() => None,
+			Err(index) => self.children[index].find_mut(val),
+		}
+	}
+	
+	/// *English*: Splits full child *i* in halves, middle key goes up to this node
+	///
+	/// *Russian*: Делит заполненного потомка *i* пополам, средний ключ поднимается в этот узел
+	
+	fn split_child(&mut self, i: usize, degree: usize) {
+		let child = &mut self.children[i];
+		let right_keys = child.keys.split_off(degree);
+		let middle = child.keys.pop().unwrap();
+		let right_children = if child.is_leaf() { vec![] } else { child.children.split_off(degree) };
+		
+		self.keys.insert(i, middle);
+		self.children.insert(i + 1, BNode { keys: right_keys, children: right_children });
+	}
+	
+	/// *English*: Inserts value into node, which isn't full.
+	/// Full children are split on the way down, so there is no way back up.
+	/// Equal values are placed after the old ones.
+	///
+	/// *Russian*: Вставляет значение в незаполненный узел.
+	/// Заполненные потомки делятся по пути вниз, так что подниматься обратно не нужно.
+	/// Равные значения ставятся после старых.
+	
+	fn insert_nonfull(&mut self, val: &T, degree: usize) {
+		let mut node = self;
+		loop {
+			let mut index = node.keys.partition_point(|key| key <= val);
+			if node.is_leaf() {
+				node.keys.insert(index, *val);
+				return;
+			}
+			
+			if node.children[index].keys.len() == 2 * degree - 1 {
+				node.split_child(index, degree);
+				if node.keys[index] <= *val {
+					index += 1;
+				}
+			}
+			node = &mut node.children[index];
+		}
+	}
+	
+	/// *English*: Makes sure, that child *i* has at least *degree* keys,
+	/// so one key can be removed from it. Key is borrowed from a sibling
+	/// or child is merged with a sibling. Returns new index of the child.
+	///
+	/// *Russian*: Гарантирует, что у потомка *i* хотя бы *degree* ключей,
+	/// так что из него можно удалить один ключ. Ключ берётся у соседа
+	/// или потомок сливается с соседом. Возвращает новый индекс потомка.
+	
+	fn fill(&mut self, i: usize, degree: usize) -> usize {
+		if self.children[i].keys.len() >= degree {
+			return i;
+		}
+		
+		if i > 0 && self.children[i - 1].keys.len() >= degree {
+			let (left, right) = self.children.split_at_mut(i);
+			let (left, child) = (&mut left[i - 1], &mut right[0]);
+			
+			let key = mem::replace(&mut self.keys[i - 1], left.keys.pop().unwrap());
+			child.keys.insert(0, key);
+			if let Some(last) = left.children.pop() {
+				child.children.insert(0, last);
+			}
+			return i;
+		}
+		
+		if i + 1 < self.children.len() && self.children[i + 1].keys.len() >= degree {
+			let (left, right) = self.children.split_at_mut(i + 1);
+			let (child, right) = (&mut left[i], &mut right[0]);
+			
+			let key = mem::replace(&mut self.keys[i], right.keys.remove(0));
+			child.keys.push(key);
+			if !right.is_leaf() {
+				child.children.push(right.children.remove(0));
+			}
+			return i;
+		}
+		
+		if i + 1 < self.children.len() {
+			self.merge(i);
+			i
+		} else {
+			self.merge(i - 1);
+			i - 1
+		}
+	}
+	
+	/// *English*: Merges children *i* and *i + 1* with key *i* between them
+	///
+	/// *Russian*: Сливает потомков *i* и *i + 1* с ключом *i* между ними
+	
+	fn merge(&mut self, i: usize) {
+		let right = self.children.remove(i + 1);
+		let key = self.keys.remove(i);
+		let left = &mut self.children[i];
+		left.keys.push(key);
+		left.keys.extend(right.keys);
+		left.children.extend(right.children);
+	}
+	
+	/// *English*: Removes min (or max, if *last* is *true*) key of subtree.
+	/// Node must have at least *degree* keys, unless it's the root.
+	///
+	/// *Russian*: Удаляет наименьший (или наибольший, если *last* - *true*) ключ поддерева.
+	/// В узле должно быть хотя бы *degree* ключей, если это не корень.
+	
+	fn pop_edge(&mut self, last: bool, degree: usize) -> Option<T> {
+		if self.is_leaf() {
+			return if last { self.keys.pop() } else if self.keys.is_empty() { None } else { Some(self.keys.remove(0)) };
+		}
+		
+		let index = if last { self.children.len() - 1 } else { 0 };
+		let index = self.fill(index, degree);
+		self.children[index].pop_edge(last, degree)
+	}
+	
+	/// *English*: Removes one key, which is equal to value.
+	/// Node must have at least *degree* keys, unless it's the root.
+	///
+	/// *Russian*: Удаляет один ключ, равный значению.
+	/// В узле должно быть хотя бы *degree* ключей, если это не корень.
+	
+	fn remove<Q>(&mut self, val: &Q, degree: usize) -> Option<T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		let index = self.keys.partition_point(|key| key.borrow() < val);
+		let here = index < self.keys.len() && self.keys[index].borrow() == val;
+		
+		if self.is_leaf() {
+			return if here { Some(self.keys.remove(index)) } else { None };
+		}
+		
+		if here {
+			if self.children[index].keys.len() >= degree {
+				let pred = self.children[index].pop_edge(true, degree).unwrap();
+				return Some(mem::replace(&mut self.keys[index], pred));
+			}
+			if self.children[index + 1].keys.len() >= degree {
+				let succ = self.children[index + 1].pop_edge(false, degree).unwrap();
+				return Some(mem::replace(&mut self.keys[index], succ));
+			}
+			self.merge(index);
+			return self.children[index].remove(val, degree);
+		}
+		
+		let index = self.fill(index, degree);
+		self.children[index].remove(val, degree)
+	}
+	
+	/// *English*: Checks order, shape and policy of subtree.
+	/// *count* is the number of keys before subtree, *last* is the key before it.
+	///
+	/// *Russian*: Проверяет порядок, форму и политику поддерева.
+	/// *count* - количество ключей перед поддеревом, *last* - ключ перед ним.
+	
+	fn validate(&self, depth: usize, height: usize, degree: usize, unique: bool, count: &mut usize, last: &mut Option<T>) -> Result<(), InvariantError> {
+		let too_few = depth > 0 && self.keys.len() < degree - 1;
+		let too_many = self.keys.len() > 2 * degree - 1;
+		let wrong_children = !self.is_leaf() && self.children.len() != self.keys.len() + 1;
+		let wrong_level = self.is_leaf() != (depth + 1 == height);
+		if too_few || too_many || wrong_children || wrong_level {
+			return Err(InvariantError::Shape { depth });
+		}
+		
+		for i in 0..=self.keys.len() {
+			if !self.is_leaf() {
+				self.children[i].validate(depth + 1, height, degree, unique, count, last)?;
+			}
+			if let Some(key) = self.keys.get(i) {
+				match last.map(|last| last.cmp(key)) {
+					Some(Ordering::Greater) => return Err(InvariantError::Order { index: *count }),
+					Some(Ordering::Equal) if unique => return Err(InvariantError::Duplicate { index: *count }),
+					_ => (),
+				}
+				*last = Some(*key);
+				*count += 1;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// **Multiway Tree**
+/// --------------------
+///
+/// *English*: **BTree** is a *B-tree*: every node keeps up to *fanout - 1* sorted keys
+/// and up to *fanout* children, all leaves are on the same level. Keys of one node
+/// lie together in memory, so search touches only O(log_fanout n) nodes instead of
+/// O(log n) boxes of **BinaryTree** - it's much friendlier to cache for big trees.
+/// Tree is always balanced. It has the same API as **BinaryTree** (policies, limits,
+/// bounds, set algebra, iterators), so you can switch with a type change.
+///
+/// *Russian*: **BTree** - это *B-дерево*: каждый узел хранит до *fanout - 1* отсортированных
+/// ключей и до *fanout* потомков, все листья на одном уровне. Ключи одного узла
+/// лежат в памяти рядом, так что поиск затрагивает лишь O(log_fanout n) узлов вместо
+/// O(log n) ячеек **BinaryTree** - для больших деревьев это гораздо лучше для кэша.
+/// Дерево всегда сбалансировано. У него такой же API, как у **BinaryTree** (политики,
+/// пределы, границы, операции над множествами, итераторы), так что можно перейти,
+/// просто поменяв тип.
+///
+/// # Example
+///
+/// ```
+/// use binartree::btree::BTree;
+/// use std::iter::FromIterator;
+///
+/// let mut tree = BTree::with_fanout(4);
+/// tree.extend(0..1000u64);
+/// tree.remove(&500);
+///
+/// assert_eq!(tree.len(), 999);
+/// assert!(!tree.contains(&500));
+/// assert_eq!(tree.range(498..503).collect::<Vec<u64>>(), vec![498, 499, 501, 502]);
+/// assert!(tree.height() <= 9);
+/// ```
+
+#[derive(Debug, Clone)]
+pub struct BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	root: BNode<T>,
+	size: usize,
+	degree: usize,
+	limit: Option<usize>,
+	policy: DuplicatePolicy,
+}
+
+/// *English*: **Default** multiway tree is *empty* and has **DEFAULT_FANOUT**
+///
+/// *Russian*: По-умолчанию многопутевое дерево *пусто* и имеет **DEFAULT_FANOUT**
+
+impl<T> Default for BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn default() -> Self {
+		BTree {
+			root: BNode::leaf(vec![]),
+			size: 0,
+			degree: DEFAULT_FANOUT / 2,
+			limit: None,
+			policy: DuplicatePolicy::KeepAll,
+		}
+	}
+}
+
+/// *English*: Multiway trees are *equal*, if they have the same sorted elements.
+/// Fanout, shape, limit and policy aren't compared.
+///
+/// *Russian*: Многопутевые деревья *равны*, если у них одинаковые отсортированные элементы.
+/// Ширина узлов, форма, предел и политика не сравниваются.
+
+impl<T> PartialEq for BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn eq(&self, other: &Self) -> bool {
+		self.size == other.size && self.iter().eq(other.iter())
+	}
+}
+
+impl<T> Eq for BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{}
+
+/// *English*: Hash is built from size and sorted elements, like hash of **BinaryTree**
+///
+/// *Russian*: Хэш строится из размера и отсортированных элементов, как хэш **BinaryTree**
+
+impl<T> Hash for BTree<T>
+	where T: Copy + Clone + Ord + Eq + Hash
+{
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.size.hash(state);
+		for elem in self.iter() {
+			elem.hash(state);
+		}
+	}
+}
+
+/// *English*: Multiway trees are compared *lexicographically* by their sorted elements
+///
+/// *Russian*: Многопутевые деревья сравниваются *лексикографически* по их отсортированным элементам
+
+impl<T> PartialOrd for BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<T> Ord for BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.iter().cmp(other.iter())
+	}
+}
+
+impl<T> BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	/// *English*: Method **new()** creates *empty* tree with **DEFAULT_FANOUT**
+	///
+	/// *Russian*: Метод **new()** создаёт *пустое* дерево с **DEFAULT_FANOUT**
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::{BTree, DEFAULT_FANOUT};
+	///
+	/// let tree = BTree::<u64>::new();
+	/// assert!(tree.is_empty());
+	/// assert_eq!(tree.fanout(), DEFAULT_FANOUT);
+	/// ```
+	
+	#[inline]
+	pub fn new() -> Self {
+		BTree::default()
+	}
+	
+	/// *English*: Method **with_fanout()** creates *empty* tree, which nodes
+	/// have up to *fanout* children. Odd fanout is rounded down.
+	/// If fanout is less than 4, it'll panic.
+	///
+	/// *Russian*: Метод **with_fanout()** создаёт *пустое* дерево, узлы которого
+	/// имеют до *fanout* потомков. Нечётная ширина округляется вниз.
+	/// Если ширина меньше 4, то будет вызвана *паника*.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::BTree;
+	///
+	/// let tree = BTree::<u64>::with_fanout(9);
+	/// assert_eq!(tree.fanout(), 8);
+	/// ```
+	
+	#[inline]
+	pub fn with_fanout(fanout: usize) -> Self {
+		let mut tree = BTree::new();
+		tree.set_fanout(fanout);
+		tree
+	}
+	
+	/// *English*: Method **set_fanout()** changes max number of children of node.
+	/// Not empty tree is rebuilt in O(n). Odd fanout is rounded down.
+	/// If fanout is less than 4, it'll panic.
+	///
+	/// *Russian*: Метод **set_fanout()** меняет наибольшее количество потомков узла.
+	/// Непустое дерево перестраивается за O(n). Нечётная ширина округляется вниз.
+	/// Если ширина меньше 4, то будет вызвана *паника*.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::BTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = BTree::from_iter(0..1000);
+	/// tree.set_fanout(4);
+	///
+	/// assert_eq!(tree.fanout(), 4);
+	/// assert_eq!(tree.validate(), Ok(()));
+	/// assert_eq!(tree.to_vec(), (0..1000).collect::<Vec<i32>>());
+	/// ```
+	
+	pub fn set_fanout(&mut self, fanout: usize) {
+		assert!(fanout >= 4, "Fanout must be at least 4");
+		if self.degree == fanout / 2 {
+			return;
+		}
+		
+		let keys = self.to_vec();
+		self.degree = fanout / 2;
+		self.rebuild(&keys);
+	}
+	
+	/// *English*: Method **fanout()** returns max number of children of node
+	///
+	/// *Russian*: Метод **fanout()** возвращает наибольшее количество потомков узла
+	
+	#[inline]
+	pub fn fanout(&self) -> usize {
+		self.degree * 2
+	}
+	
+	/// *English*: Method **with_limit()** creates *empty* tree,
+	/// which can't hold more than *limit* elements (like **BinaryTree::with_limit()**)
+	///
+	/// *Russian*: Метод **with_limit()** создаёт *пустое* дерево,
+	/// которое не может хранить больше *limit* элементов (как **BinaryTree::with_limit()**)
+	
+	#[inline]
+	pub fn with_limit(limit: usize) -> Self {
+		let mut tree = BTree::new();
+		tree.limit = Some(limit);
+		tree
+	}
+	
+	/// *English*: Method **with_policy()** creates *empty* tree
+	/// with given policy for duplicates (like **BinaryTree::with_policy()**)
+	///
+	/// *Russian*: Метод **with_policy()** создаёт *пустое* дерево
+	/// с данной политикой для повторов (как **BinaryTree::with_policy()**)
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::BTree;
+	/// use binartree::tree::DuplicatePolicy;
+	///
+	/// let mut tree = BTree::with_policy(DuplicatePolicy::Reject);
+	/// assert!(tree.insert(&1));
+	/// assert!(!tree.insert(&1));
+	/// assert_eq!(tree.len(), 1);
+	/// ```
+	
+	#[inline]
+	pub fn with_policy(policy: DuplicatePolicy) -> Self {
+		let mut tree = BTree::new();
+		tree.policy = policy;
+		tree
+	}
+	
+	/// *English*: Method **policy()** returns policy for duplicates
+	///
+	/// *Russian*: Метод **policy()** возвращает политику для повторов
+	
+	#[inline]
+	pub fn policy(&self) -> DuplicatePolicy {
+		self.policy
+	}
+	
+	/// *English*: Method **limit()** returns max number of elements or *None*, if tree is unbounded
+	///
+	/// *Russian*: Метод **limit()** возвращает наибольшее количество элементов
+	/// или *None*, если дерево неограничено
+	
+	#[inline]
+	pub fn limit(&self) -> Option<usize> {
+		self.limit
+	}
+	
+	/// *English*: Method **len()** returns number of elements
+	///
+	/// *Russian*: Метод **len()** возвращает количество элементов
+	
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.size
+	}
+	
+	/// *English*: Method **is_empty()** checks that tree has no elements
+	///
+	/// *Russian*: Метод **is_empty()** проверяет, что в дереве нет элементов
+	
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.size == 0
+	}
+	
+	/// *English*: Method **height()** returns *number of levels*. Empty tree has height 0.
+	///
+	/// *Russian*: Метод **height()** возвращает *количество уровней*. Высота пустого дерева - 0.
+	
+	pub fn height(&self) -> usize {
+		if self.size == 0 {
+			return 0;
+		}
+		
+		let mut height = 1;
+		let mut node = &self.root;
+		while !node.is_leaf() {
+			node = &node.children[0];
+			height += 1;
+		}
+		height
+	}
+	
+	/// *English*: Replaces all nodes with balanced ones built from sorted keys
+	///
+	/// *Russian*: Заменяет все узлы на сбалансированные, построенные из отсортированных ключей
+	
+	fn rebuild(&mut self, keys: &[T]) {
+		let mut height = 1;
+		let mut capacity = 2 * self.degree - 1;
+		while capacity < keys.len() {
+			capacity = capacity.saturating_mul(2 * self.degree).saturating_add(2 * self.degree - 1);
+			height += 1;
+		}
+		
+		self.root = BNode::build(keys, height, self.degree);
+		self.size = keys.len();
+		self.check();
+	}
+	
+	/// *English*: Method **to_vec()** returns sorted vector of elements
+	///
+	/// *Russian*: Метод **to_vec()** возвращает отсортированный вектор элементов
+	
+	#[inline]
+	pub fn to_vec(&self) -> Vec<T> {
+		self.iter().collect()
+	}
+	
+	/// *English*: Method **to_deque()** returns sorted deque of elements
+	///
+	/// *Russian*: Метод **to_deque()** возвращает отсортированный дек элементов
+	
+	#[inline]
+	pub fn to_deque(&self) -> VecDeque<T> {
+		self.iter().collect()
+	}
+	
+	/// *English*: Method **insert()** adds value like **BinaryTree::insert()**:
+	/// equal values are placed after the old ones, policy decides what to do
+	/// with duplicates and *panic* is called, if bounded tree is full.
+	/// Returns *true*, if tree was changed. Takes O(fanout * log n).
+	///
+	/// *Russian*: Метод **insert()** добавляет значение как **BinaryTree::insert()**:
+	/// равные значения ставятся после старых, политика решает, что делать
+	/// с повторами, а если ограниченное дерево заполнено, то вызывается *паника*.
+	/// Возвращает *true*, если дерево изменилось. Занимает O(fanout * log n).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::BTree;
+	///
+	/// let mut tree = BTree::new();
+	/// tree.insert(&3);
+	/// tree.insert(&1);
+	/// tree.insert(&3);
+	///
+	/// assert_eq!(tree.to_vec(), vec![1, 3, 3]);
+	/// ```
+	
+	#[inline]
+	pub fn insert(&mut self, val: &T) -> bool {
+		match self.try_insert(val) {
+			Ok(changed) => changed,
+			Err(error) => panic!("{}", error),
+		}
+	}
+	
+	/// *English*: Method **try_insert()** adds value like **insert()**,
+	/// but returns *Error::Full* instead of panic, if bounded tree is full.
+	///
+	/// *Russian*: Метод **try_insert()** добавляет значение как **insert()**,
+	/// но вместо паники возвращает *Error::Full*, если ограниченное дерево заполнено.
+	
+	pub fn try_insert(&mut self, val: &T) -> Result<bool, Error> {
+		match self.policy {
+			DuplicatePolicy::KeepAll => (),
+			DuplicatePolicy::Reject => if self.contains(val) {
+				return Ok(false);
+			},
+			DuplicatePolicy::Replace => if let Some(key) = self.root.find_mut(val) {
+				*key = *val;
+				return Ok(true);
+			},
+		}
+		
+		if matches!(self.limit, Some(limit) if self.size >= limit) {
+			return Err(Error::Full);
+		}
+		
+		if self.root.keys.len() == 2 * self.degree - 1 {
+			let old = mem::replace(&mut self.root, BNode::leaf(vec![]));
+			self.root.children.push(old);
+			self.root.split_child(0, self.degree);
+		}
+		
+		self.root.insert_nonfull(val, self.degree);
+		self.size += 1;
+		self.check();
+		Ok(true)
+	}
+	
+	/// *English*: Method **contains()** checks that value is in the tree.
+	/// Value may be any borrowed form of key.
+	///
+	/// *Russian*: Метод **contains()** проверяет наличие значения в дереве.
+	/// Значение может быть любой заимствованной формой ключа.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::BTree;
+	///
+	/// let names = BTree::from(vec!["Alice", "Bob"]);
+	/// let name = String::from("Bob");
+	///
+	/// assert!(names.contains(name.as_str()));
+	/// assert!(!names.contains("Eve"));
+	/// ```
+	
+	#[inline]
+	pub fn contains<Q>(&self, val: &Q) -> bool
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.get(val).is_some()
+	}
+	
+	/// *English*: Method **get()** returns key, which is equal to value, or *None*
+	///
+	/// *Russian*: Метод **get()** возвращает ключ, равный значению, или *None*
+	
+	#[inline]
+	pub fn get<Q>(&self, val: &Q) -> Option<&T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.find_by(|key| key.borrow().cmp(val))
+	}
+	
+	/// *English*: Method **range()** returns *sorted* iterator over keys from range.
+	/// It visits only nodes that may contain such keys.
+	///
+	/// *Russian*: Метод **range()** возвращает *отсортированный* итератор по ключам из диапазона.
+	/// Он посещает только узлы, в которых могут быть такие ключи.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::BTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let tree = BTree::from_iter(vec![5, 1, 4, 2, 3, 3]);
+	///
+	/// assert_eq!(tree.range(2..4).collect::<Vec<i32>>(), vec![2, 3, 3]);
+	/// assert_eq!(tree.range(4..).collect::<Vec<i32>>(), vec![4, 5]);
+	/// ```
+	
+	pub fn range<Q, R>(&self, range: R) -> TreeIter<T>
+		where T: Borrow<Q>, Q: Ord + ?Sized, R: RangeBounds<Q>
+	{
+		self.range_by(|key| {
+			let key = key.borrow();
+			let before = match range.start_bound() {
+				Bound::Included(start) => key < start,
+				Bound::Excluded(start) => key <= start,
+				Bound::Unbounded => false,
+			};
+			let after = match range.end_bound() {
+				Bound::Included(end) => key > end,
+				Bound::Excluded(end) => key >= end,
+				Bound::Unbounded => false,
+			};
+			
+			match (before, after) {
+				(true, _) => Ordering::Less,
+				(_, true) => Ordering::Greater,
+				_ => Ordering::Equal,
+			}
+		})
+	}
+	
+	/// *English*: Method **range_by()** returns *sorted* iterator over keys,
+	/// for which comparator returns *Equal* (like **BinaryTree::range_by()**)
+	///
+	/// *Russian*: Метод **range_by()** возвращает *отсортированный* итератор по ключам,
+	/// для которых компаратор возвращает *Equal* (как **BinaryTree::range_by()**)
+	
+	pub fn range_by<F>(&self, mut cmp: F) -> TreeIter<T>
+		where F: FnMut(&T) -> Ordering
+	{
+		let mut iter = VecDeque::new();
+		self.root.range_by(&mut cmp, &mut iter);
+		TreeIter { iter }
+	}
+	
+	/// *English*: Method **find_by()** searches key with comparator
+	/// (like **BinaryTree::find_by()**). Returns *None*, if there is no such key.
+	///
+	/// *Russian*: Метод **find_by()** ищет ключ с компаратором
+	/// (как **BinaryTree::find_by()**). Возвращает *None*, если такого ключа нет.
+	
+	#[inline]
+	pub fn find_by<F>(&self, mut cmp: F) -> Option<&T>
+		where F: FnMut(&T) -> Ordering
+	{
+		self.root.find_by(&mut cmp)
+	}
+	
+	/// *English*: Method **partition_point()** returns the smallest key, for which
+	/// predicate is *false*, or *None*, if it's *true* for all keys
+	/// (like **BinaryTree::partition_point()**)
+	///
+	/// *Russian*: Метод **partition_point()** возвращает наименьший ключ, для которого
+	/// предикат *ложен*, или *None*, если он *истинен* для всех ключей
+	/// (как **BinaryTree::partition_point()**)
+	
+	#[inline]
+	pub fn partition_point<P>(&self, mut pred: P) -> Option<&T>
+		where P: FnMut(&T) -> bool
+	{
+		self.root.partition_point(&mut pred)
+	}
+	
+	/// *English*: Method **lower_bound()** returns the smallest key *above* the bound
+	/// or *None*, if there is no such key (like **BinaryTree::lower_bound()**)
+	///
+	/// *Russian*: Метод **lower_bound()** возвращает наименьший ключ *выше* границы
+	/// или *None*, если такого ключа нет (как **BinaryTree::lower_bound()**)
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::BTree;
+	/// use std::iter::FromIterator;
+	/// use std::ops::Bound;
+	///
+	/// let tree = BTree::from_iter(vec![10, 20, 30]);
+	///
+	/// assert_eq!(tree.lower_bound(Bound::Excluded(&20)), Some(&30));
+	/// assert_eq!(tree.upper_bound(Bound::Excluded(&20)), Some(&10));
+	/// ```
+	
+	pub fn lower_bound<Q>(&self, bound: Bound<&Q>) -> Option<&T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.partition_point(|key| match bound {
+			Bound::Included(val) => key.borrow() < val,
+			Bound::Excluded(val) => key.borrow() <= val,
+			Bound::Unbounded => false,
+		})
+	}
+	
+	/// *English*: Method **upper_bound()** returns the biggest key *below* the bound
+	/// or *None*, if there is no such key (like **BinaryTree::upper_bound()**)
+	///
+	/// *Russian*: Метод **upper_bound()** возвращает наибольший ключ *ниже* границы
+	/// или *None*, если такого ключа нет (как **BinaryTree::upper_bound()**)
+	
+	pub fn upper_bound<Q>(&self, bound: Bound<&Q>) -> Option<&T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.root.last_before(&mut |key: &T| match bound {
+			Bound::Included(val) => key.borrow() <= val,
+			Bound::Excluded(val) => key.borrow() < val,
+			Bound::Unbounded => true,
+		})
+	}
+	
+	/// *English*: Method **first()** returns *minimum value*.
+	/// If tree is empty, it'll panic.
+	///
+	/// *Russian*: Метод **first()** возвращает *минимальное значение*.
+	/// Если дерево пустое, то будет вызвана *паника*.
+	
+	#[inline]
+	pub fn first(&self) -> &T {
+		match self.try_first() {
+			Ok(key) => key,
+			Err(error) => panic!("{}", error),
+		}
+	}
+	
+	/// *English*: Method **try_first()** returns *minimum value* or *Error::Empty*
+	///
+	/// *Russian*: Метод **try_first()** возвращает *минимальное значение* или *Error::Empty*
+	
+	pub fn try_first(&self) -> Result<&T, Error> {
+		let mut node = &self.root;
+		while !node.is_leaf() {
+			node = &node.children[0];
+		}
+		node.keys.first().ok_or(Error::Empty)
+	}
+	
+	/// *English*: Method **last()** returns *maximum value*.
+	/// If tree is empty, it'll panic.
+	///
+	/// *Russian*: Метод **last()** возвращает *максимальное значение*.
+	/// Если дерево пустое, то будет вызвана *паника*.
+	
+	#[inline]
+	pub fn last(&self) -> &T {
+		match self.try_last() {
+			Ok(key) => key,
+			Err(error) => panic!("{}", error),
+		}
+	}
+	
+	/// *English*: Method **try_last()** returns *maximum value* or *Error::Empty*
+	///
+	/// *Russian*: Метод **try_last()** возвращает *максимальное значение* или *Error::Empty*
+	
+	pub fn try_last(&self) -> Result<&T, Error> {
+		let mut node = &self.root;
+		while !node.is_leaf() {
+			node = node.children.last().unwrap();
+		}
+		node.keys.last().ok_or(Error::Empty)
+	}
+	
+	/// *English*: Method **iter()** returns *lazy sorted* iterator over keys.
+	/// It doesn't copy the tree: every step takes O(1) amortized.
+	///
+	/// *Russian*: Метод **iter()** возвращает *ленивый отсортированный* итератор по ключам.
+	/// Он не копирует дерево: каждый шаг занимает O(1) амортизированно.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::BTree;
+	///
+	/// let tree = BTree::from(vec![3, 1, 2]);
+	/// assert_eq!(tree.iter().collect::<Vec<i32>>(), vec![1, 2, 3]);
+	/// ```
+	
+	pub fn iter(&self) -> Iter<'_, T> {
+		let mut iter = Iter {
+			stack: vec![],
+			left: self.size,
+		};
+		iter.descend(&self.root);
+		iter
+	}
+	
+	/// *English*: Method **append()** adds all elements of other tree.
+	/// If bounded tree can't take all of them, it'll *panic* before adding anything.
+	///
+	/// *Russian*: Метод **append()** добавляет все элементы другого дерева.
+	/// Если ограниченное дерево не может принять их все, то до добавления будет вызвана *паника*.
+	
+	#[inline]
+	pub fn append(&mut self, src: &Self) {
+		if let Err(error) = self.try_append(src) {
+			panic!("{}", error);
+		}
+	}
+	
+	/// *English*: Method **try_append()** adds all elements of other tree like **append()**,
+	/// but if bounded tree can't take all of them, it returns *Error::Full* and *adds nothing*.
+	/// Duplicates, which policy rejects or replaces, don't take place.
+	///
+	/// *Russian*: Метод **try_append()** добавляет все элементы другого дерева как **append()**,
+	/// но если ограниченное дерево не может принять их все, то возвращает *Error::Full*
+	/// и *ничего не добавляет*. Повторы, которые политика отклоняет или заменяет, места не занимают.
+	
+	pub fn try_append(&mut self, src: &Self) -> Result<(), Error> {
+		if matches!(self.limit, Some(limit) if self.size + self.append_growth(src) > limit) {
+			return Err(Error::Full);
+		}
+		
+		for elem in src.iter() {
+			self.insert(&elem);
+		}
+		Ok(())
+	}
+	
+	/// *English*: Number of elements, which append of *src* adds under tree's policy
+	///
+	/// *Russian*: Количество элементов, которое добавит присоединение *src* при политике дерева
+	
+	fn append_growth(&self, src: &Self) -> usize {
+		match self.policy {
+			DuplicatePolicy::KeepAll => src.size,
+			DuplicatePolicy::Reject | DuplicatePolicy::Replace => {
+				let mut keys = src.to_vec();
+				keys.dedup();
+				filter_present(&keys, &self.to_vec(), false).len()
+			}
+		}
+	}
+	
+	/// *English*: Method **clear()** removes all elements, fanout stays the same
+	///
+	/// *Russian*: Метод **clear()** удаляет все элементы, ширина узлов остаётся прежней
+	
+	#[inline]
+	pub fn clear(&mut self) {
+		self.root = BNode::leaf(vec![]);
+		self.size = 0;
+	}
+	
+	/// *English*: Method **remove()** removes *one occurrence* of value, if it's there.
+	/// Takes O(fanout * log n).
+	///
+	/// *Russian*: Метод **remove()** удаляет *одно вхождение* значения, если оно есть.
+	/// Занимает O(fanout * log n).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::BTree;
+	///
+	/// let mut tree = BTree::from(vec![1, 2, 2, 3]);
+	/// tree.remove(&2);
+	/// tree.remove(&5);
+	/// assert_eq!(tree.to_vec(), vec![1, 2, 3]);
+	/// ```
+	
+	#[inline]
+	pub fn remove<Q>(&mut self, val: &Q)
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		let _ = self.try_remove(val);
+	}
+	
+	/// *English*: Method **try_remove()** removes value like **remove()**,
+	/// but returns *Error::NotFound*, if there is no such value.
+	///
+	/// *Russian*: Метод **try_remove()** удаляет значение как **remove()**,
+	/// но возвращает *Error::NotFound*, если такого значения нет.
+	
+	pub fn try_remove<Q>(&mut self, val: &Q) -> Result<(), Error>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		let removed = self.root.remove(val, self.degree);
+		self.shrink_root();
+		removed.ok_or(Error::NotFound)?;
+		self.size -= 1;
+		self.check();
+		Ok(())
+	}
+	
+	/// *English*: Empty root with one child is replaced by the child
+	///
+	/// *Russian*: Пустой корень с одним потомком заменяется этим потомком
+	
+	fn shrink_root(&mut self) {
+		if self.root.keys.is_empty() && !self.root.is_leaf() {
+			self.root = self.root.children.pop().unwrap();
+		}
+	}
+	
+	/// *English*: Method **difference()** returns *sorted* elements,
+	/// that are *in 1-st tree, but not in 2-nd*. Takes O(n + m).
+	///
+	/// *Russian*: Метод **difference()** возвращает *отсортированные* элементы,
+	/// *которые есть в 1 дереве, но которых нет во 2*. Занимает O(n + m).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::BTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let tree1 = BTree::from_iter(1..6);
+	/// let tree2 = BTree::from_iter(3..9);
+	///
+	/// assert_eq!(tree1.difference(&tree2).to_vec(), vec![1, 2]);
+	/// assert_eq!(tree1.intersection(&tree2).to_vec(), vec![3, 4, 5]);
+	/// assert_eq!(tree1.symmetric_difference(&tree2).to_vec(), vec![1, 2, 6, 7, 8]);
+	/// assert_eq!(tree1.union(&tree2).to_vec(), (1..9).collect::<Vec<i32>>());
+	/// ```
+	
+	pub fn difference(&self, other: &Self) -> SortedSeq<T> {
+		set_result(self.policy, filter_present(&self.to_vec(), &other.to_vec(), false))
+	}
+	
+	/// *English*: Method **intersection()** returns *sorted* elements,
+	/// that are *in 1-st and 2-nd tree*. Takes O(n + m).
+	///
+	/// *Russian*: Метод **intersection()** возвращает *отсортированные* элементы,
+	/// *которые есть и в 1, и во 2 дереве*. Занимает O(n + m).
+	
+	pub fn intersection(&self, other: &Self) -> SortedSeq<T> {
+		set_result(self.policy, filter_present(&self.to_vec(), &other.to_vec(), true))
+	}
+	
+	/// *English*: Method **symmetric_difference()** returns *sorted* elements,
+	/// that are *only in 1-st or 2-nd tree*. Takes O(n + m).
+	///
+	/// *Russian*: Метод **symmetric_difference()** возвращает *отсортированные* элементы,
+	/// которые есть *либо только в 1 дереве, либо только во 2*. Занимает O(n + m).
+	
+	pub fn symmetric_difference(&self, other: &Self) -> SortedSeq<T> {
+		let first = self.to_vec();
+		let second = other.to_vec();
+		
+		let mut seq = SortedSeq::from_sorted(filter_present(&first, &second, false));
+		seq.merge(SortedSeq::from_sorted(filter_present(&second, &first, false)));
+		set_result(self.policy, seq.to_deque())
+	}
+	
+	/// *English*: Method **union()** returns *sorted* elements of 1-st tree
+	/// and elements of 2-nd tree, which are not in 1-st. Takes O(n + m).
+	///
+	/// *Russian*: Метод **union()** возвращает *отсортированные* элементы 1 дерева
+	/// и элементы 2 дерева, которых нет в 1. Занимает O(n + m).
+	
+	pub fn union(&self, other: &Self) -> SortedSeq<T> {
+		let first = self.to_vec();
+		let second = other.to_vec();
+		
+		let mut seq = SortedSeq::from_sorted(filter_present(&second, &first, false));
+		seq.merge(SortedSeq::from_sorted(VecDeque::from(first)));
+		set_result(self.policy, seq.to_deque())
+	}
+	
+	/// *English*: Method **is_disjoint()** checks, that trees have no elements in common
+	///
+	/// *Russian*: Метод **is_disjoint()** проверяет, что у деревьев нет общих элементов
+	
+	#[inline]
+	pub fn is_disjoint(&self, other: &Self) -> bool {
+		self.intersection(other).is_empty()
+	}
+	
+	/// *English*: Method **drain_filter()** removes all values, which match the function,
+	/// and returns *iterator with removed values*. Survivors are rebuilt in O(n).
+	///
+	/// *Russian*: Метод **drain_filter()** удаляет все значения, которые соответствуют функции,
+	/// и возвращает *итератор с удалёнными значениями*. Оставшиеся перестраиваются за O(n).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::BTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = BTree::from_iter(1..11);
+	/// let evens = tree.drain_filter(|x| x % 2 == 0);
+	///
+	/// assert_eq!(tree.to_vec(), vec![1, 3, 5, 7, 9]);
+	/// assert_eq!(evens.collect::<Vec<i32>>(), vec![2, 4, 6, 8, 10]);
+	/// ```
+	
+	pub fn drain_filter<F: FnMut(&T) -> bool>(&mut self, mut fun: F) -> TreeIter<T> {
+		let mut removed = VecDeque::new();
+		let mut kept = Vec::with_capacity(self.size);
+		
+		for key in self.iter() {
+			if fun(&key) {
+				removed.push_back(key);
+			} else {
+				kept.push(key);
+			}
+		}
+		
+		if !removed.is_empty() {
+			self.rebuild(&kept);
+		}
+		TreeIter { iter: removed }
+	}
+	
+	/// *English*: Method **retain()** keeps only keys, for which predicate returns *true*.
+	/// Keys are visited in *ascending* order. Takes O(n).
+	///
+	/// *Russian*: Метод **retain()** оставляет только ключи, для которых предикат
+	/// возвращает *true*. Ключи обходятся *по возрастанию*. Занимает O(n).
+	
+	#[inline]
+	pub fn retain<F>(&mut self, mut pred: F)
+		where F: FnMut(&T) -> bool
+	{
+		self.drain_filter(|key| !pred(key));
+	}
+	
+	/// *English*: Method **pop_first()** removes and returns min value or *None*, if tree is empty
+	///
+	/// *Russian*: Метод **pop_first()** удаляет и возвращает наименьшее значение
+	/// или *None*, если дерево пустое
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::BTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = BTree::from_iter(1..4);
+	/// assert_eq!(tree.pop_first(), Some(1));
+	/// assert_eq!(tree.pop_last(), Some(3));
+	/// assert_eq!(tree.to_vec(), vec![2]);
+	/// ```
+	
+	pub fn pop_first(&mut self) -> Option<T> {
+		let key = self.root.pop_edge(false, self.degree);
+		self.shrink_root();
+		if key.is_some() {
+			self.size -= 1;
+			self.check();
+		}
+		key
+	}
+	
+	/// *English*: Method **try_pop_first()** removes and returns min value or *Error::Empty*
+	///
+	/// *Russian*: Метод **try_pop_first()** удаляет и возвращает наименьшее значение или *Error::Empty*
+	
+	#[inline]
+	pub fn try_pop_first(&mut self) -> Result<T, Error> {
+		self.pop_first().ok_or(Error::Empty)
+	}
+	
+	/// *English*: Method **pop_last()** removes and returns max value or *None*, if tree is empty
+	///
+	/// *Russian*: Метод **pop_last()** удаляет и возвращает наибольшее значение
+	/// или *None*, если дерево пустое
+	
+	pub fn pop_last(&mut self) -> Option<T> {
+		let key = self.root.pop_edge(true, self.degree);
+		self.shrink_root();
+		if key.is_some() {
+			self.size -= 1;
+			self.check();
+		}
+		key
+	}
+	
+	/// *English*: Method **try_pop_last()** removes and returns max value or *Error::Empty*
+	///
+	/// *Russian*: Метод **try_pop_last()** удаляет и возвращает наибольшее значение или *Error::Empty*
+	
+	#[inline]
+	pub fn try_pop_last(&mut self) -> Result<T, Error> {
+		self.pop_last().ok_or(Error::Empty)
+	}
+	
+	/// *English*: Method **replace_val()** changes all keys with
+	/// *some* value to *another* value
+	///
+	/// *Russian*: Метод **replace_val()** заменяет все ключи
+	/// с *одним* значением на ключи с *другим* значением
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::BTree;
+	///
+	/// let mut tree = BTree::from(vec![1, 1, 2]);
+	/// tree.replace_val(&1, &3);
+	/// assert_eq!(tree.to_vec(), vec![2, 3, 3]);
+	/// ```
+	
+	pub fn replace_val(&mut self, old_val: &T, new_val: &T) {
+		if old_val == new_val {
+			return;
+		}
+		
+		let count = self.range(old_val..=old_val).len();
+		for _ in 0..count {
+			self.remove(old_val);
+		}
+		for _ in 0..count {
+			self.insert(new_val);
+		}
+	}
+	
+	/// *English*: Method **try_replace_val()** works like **replace_val()**,
+	/// but returns *Error::NotFound*, if there are no keys with old value.
+	///
+	/// *Russian*: Метод **try_replace_val()** работает как **replace_val()**,
+	/// но возвращает *Error::NotFound*, если ключей со старым значением нет.
+	
+	pub fn try_replace_val(&mut self, old_val: &T, new_val: &T) -> Result<(), Error> {
+		if !self.contains(old_val) {
+			return Err(Error::NotFound);
+		}
+		self.replace_val(old_val, new_val);
+		Ok(())
+	}
+	
+	/// *English*: Method **multi_remove()** removes one occurrence of every value
+	/// from *src* in one pass and rebuilds the tree. Takes O(n + m log m).
+	///
+	/// *Russian*: Метод **multi_remove()** удаляет по одному вхождению каждого значения
+	/// из *src* за один проход и перестраивает дерево. Занимает O(n + m log m).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::btree::BTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = BTree::from_iter(vec![1, 2, 2, 3, 4]);
+	/// tree.multi_remove(vec![4, 2, 7]);
+	/// assert_eq!(tree.to_vec(), vec![1, 2, 3]);
+	/// ```
+	
+	pub fn multi_remove(&mut self, mut src: Vec<T>) {
+		src.sort();
+		let mut i = 0;
+		
+		self.retain(|key| {
+			while i < src.len() && src[i] < *key {
+				i += 1;
+			}
+			if i < src.len() && src[i] == *key {
+				i += 1;
+				return false;
+			}
+			true
+		});
+	}
+	
+	/// *English*: Method **validate()** checks *structure of the tree*: keys are sorted,
+	/// there are no duplicates if policy forbids them, every node except the root
+	/// is at least half full, all leaves are on the same level and length is right.
+	/// Takes O(n). If crate is built with *validate* feature,
+	/// tree calls it after every mutation in debug builds.
+	///
+	/// *Russian*: Метод **validate()** проверяет *структуру дерева*: ключи отсортированы,
+	/// нет повторов, если политика их запрещает, каждый узел, кроме корня,
+	/// заполнен хотя бы наполовину, все листья на одном уровне и длина верна.
+	/// Занимает O(n). Если крейт собран с фичей *validate*,
+	/// дерево вызывает его после каждого изменения в отладочных сборках.
+	
+	pub fn validate(&self) -> Result<(), InvariantError> {
+		let mut count = 0;
+		let height = self.height().max(1);
+		let unique = self.policy != DuplicatePolicy::KeepAll;
+		self.root.validate(0, height, self.degree, unique, &mut count, &mut None)?;
+		
+		if count != self.size {
+			return Err(InvariantError::Size { expected: self.size, actual: count });
+		}
+		Ok(())
+	}
+	
+	/// *English*: Panics if tree is broken. Works only in debug builds
+	/// with *validate* feature, otherwise does nothing.
+	///
+	/// *Russian*: Паникует, если дерево сломано. Работает только в отладочных
+	/// сборках с фичей *validate*, иначе ничего не делает.
+	
+	#[inline]
+	fn check(&self) {
+		if cfg!(feature = "validate") {
+			debug_assert_eq!(self.validate(), Ok(()));
+		}
+	}
+}
+
+/// *English*: Lazy in-order iterator of **BTree**. Keeps the path from the root
+/// to the current key, so it takes O(height) memory.
+///
+/// *Russian*: Ленивый итератор по порядку для **BTree**. Хранит путь от корня
+/// до текущего ключа, так что занимает O(высоты) памяти.
+
+pub struct Iter<'a, T>
+	where T: Copy + Clone + Ord + Eq
+{
+	stack: Vec<(&'a BNode<T>, usize)>,
+	left: usize,
+}
+
+impl<'a, T> Iter<'a, T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn descend(&mut self, mut node: &'a BNode<T>) {
+		loop {
+			self.stack.push((node, 0));
+			if node.is_leaf() {
+				return;
+			}
+			node = &node.children[0];
+		}
+	}
+}
+
+impl<T> Iterator for Iter<'_, T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Item = T;
+	
+	fn next(&mut self) -> Option<T> {
+		loop {
+			let (node, index) = self.stack.last_mut()?;
+			let node = *node;
+			
+			if *index < node.keys.len() {
+				let key = node.keys[*index];
+				*index += 1;
+				if !node.is_leaf() {
+					let child = &node.children[*index];
+					self.descend(child);
+				}
+				self.left -= 1;
+				return Some(key);
+			}
+			self.stack.pop();
+		}
+	}
+	
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.left, Some(self.left))
+	}
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T>
+	where T: Copy + Clone + Ord + Eq
+{}
+
+/// *English*: Owning iterator over sorted keys (like **BinaryTree**)
+///
+/// *Russian*: Владеющий итератор по отсортированным ключам (как у **BinaryTree**)
+
+impl<T> IntoIterator for BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Item = T;
+	type IntoIter = TreeIter<T>;
+	
+	#[inline]
+	fn into_iter(self) -> TreeIter<T> {
+		TreeIter { iter: self.to_deque() }
+	}
+}
+
+impl<'a, T> IntoIterator for &'a BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Item = T;
+	type IntoIter = Iter<'a, T>;
+	
+	#[inline]
+	fn into_iter(self) -> Iter<'a, T> {
+		self.iter()
+	}
+}
+
+/// *English*: **Extend<T>** trait for multiway tree
+///
+/// *Russian*: Трейт **Extend<T>** для многопутевого дерева
+
+impl<T> Extend<T> for BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+		for it in iter {
+			self.insert(&it);
+		}
+	}
+}
+
+/// *English*: **FromIterator<T>** trait for multiway tree. Elements are collected,
+/// sorted and packed into nodes at once, so it takes O(n log n).
+///
+/// *Russian*: Трейт **FromIterator<T>** для многопутевого дерева. Элементы собираются,
+/// сортируются и укладываются в узлы за раз, так что это занимает O(n log n).
+
+impl<T> FromIterator<T> for BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+		BTree::from(iter.into_iter().collect::<Vec<T>>())
+	}
+}
+
+/// *English*: Converts vector to multiway tree with **DEFAULT_FANOUT**
+///
+/// *Russian*: Конвертирует вектор в многопутевое дерево с **DEFAULT_FANOUT**
+
+impl<T> From<Vec<T>> for BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn from(mut keys: Vec<T>) -> Self {
+		keys.sort();
+		let mut tree = BTree::new();
+		tree.rebuild(&keys);
+		tree
+	}
+}
+
+/// *English*: Converts deque to multiway tree
+///
+/// *Russian*: Конвертирует дек в многопутевое дерево
+
+impl<T> From<VecDeque<T>> for BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn from(s: VecDeque<T>) -> Self {
+		BTree::from(Vec::from(s))
+	}
+}
+
+/// *English*: Converts slice to multiway tree
+///
+/// *Russian*: Конвертирует срез в многопутевое дерево
+
+impl<T> From<&mut [T]> for BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn from(s: &mut [T]) -> Self {
+		BTree::from(s.to_vec())
+	}
+}
+
+/// *English*: Creates tree from sorted result of set operation.
+/// New tree has the same fanout and policy as the 1-st one.
+///
+/// *Russian*: Создаёт дерево из отсортированного результата операции над множествами.
+/// У нового дерева такие же ширина узлов и политика, как у 1-го.
+
+fn from_set_op<T>(like: &BTree<T>, seq: SortedSeq<T>) -> BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	let mut tree = BTree {
+		root: BNode::leaf(vec![]),
+		size: 0,
+		degree: like.degree,
+		limit: None,
+		policy: like.policy,
+	};
+	tree.rebuild(&seq.to_vec());
+	tree
+}
+
+/// *English*: **BitAnd** creates tree from intersection of 2 trees
+///
+/// *Russian*: **BitAnd** создаёт дерево из пересечения 2-х деревьев
+
+impl<T> BitAnd for &BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Output = BTree<T>;
+	
+	#[inline]
+	fn bitand(self, rhs: Self) -> BTree<T> {
+		from_set_op(self, self.intersection(rhs))
+	}
+}
+
+/// *English*: **BitOr** creates tree from union of 2 trees
+///
+/// *Russian*: **BitOr** создаёт дерево из объединения 2-х деревьев
+///
+/// # Example
+///
+/// ```
+/// use binartree::btree::BTree;
+/// use std::iter::FromIterator;
+///
+/// let tree1 = BTree::from_iter(1..10);
+/// let tree2 = BTree::from_iter(5..15);
+///
+/// assert_eq!((&tree1 | &tree2).to_vec(), (1..15).collect::<Vec<i32>>());
+/// assert_eq!((&tree1 & &tree2).to_vec(), (5..10).collect::<Vec<i32>>());
+/// assert_eq!((&tree1 ^ &tree2).len(), 9);
+/// ```
+
+impl<T> BitOr for &BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Output = BTree<T>;
+	
+	#[inline]
+	fn bitor(self, rhs: Self) -> BTree<T> {
+		from_set_op(self, self.union(rhs))
+	}
+}
+
+/// *English*: **BitXor** creates tree from symmetric difference of 2 trees
+///
+/// *Russian*: **BitXor** создаёт дерево из симметрической разности 2-х деревьев
+
+impl<T> BitXor for &BTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Output = BTree<T>;
+	
+	#[inline]
+	fn bitxor(self, rhs: Self) -> BTree<T> {
+		from_set_op(self, self.symmetric_difference(rhs))
+	}
+}
@@ -0,0 +1,441 @@
+use crate::codec::FixedKey;
+use crate::error::FormatError;
+use crate::frozen::FrozenTree;
+use crate::node::Node;
+use crate::paged::PagedTree;
+use crate::tree::{BinaryTree, DuplicatePolicy};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::vec;
+
+/// *English*: Default memory budget of **BulkLoader** in bytes (64 MiB)
+///
+/// *Russian*: Бюджет памяти **BulkLoader** по-умолчанию в байтах (64 МиБ)
+
+pub const DEFAULT_MEMORY: usize = 64 << 20;
+
+/// *English*: Default number of runs, which are merged at once
+///
+/// *Russian*: Количество серий, сливаемых за раз, по-умолчанию
+
+pub const DEFAULT_FAN_IN: usize = 64;
+
+/// *English*: Counter for unique names of spill files
+///
+/// *Russian*: Счётчик для уникальных имён временных файлов
+
+static RUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// **Bulk Loader**
+/// ---------------
+///
+/// *English*: **BulkLoader** sorts keys, which don't fit in memory, and builds trees
+/// from them without inserting one key at a time. Keys are read from any iterator or
+/// reader (in fixed-width encoding of **FixedKey**) and collected into chunks of
+/// *memory* bytes. Every full chunk is sorted and spilled to a temporary file,
+/// then files are merged by k-way merge (in several passes, if there are more
+/// than *fan_in* of them). Temporary files are removed, when they aren't needed.
+/// Result is lazy **Sorted** stream, which becomes balanced **BinaryTree**,
+/// **FrozenTree** or **PagedTree**.
+///
+/// *Russian*: **BulkLoader** сортирует ключи, которые не помещаются в память, и строит
+/// из них деревья без вставки по одному ключу. Ключи читаются из любого итератора или
+/// потока (в кодировке фиксированной ширины **FixedKey**) и собираются в порции по
+/// *memory* байт. Каждая полная порция сортируется и сбрасывается во временный файл,
+/// затем файлы сливаются k-путевым слиянием (в несколько проходов, если их больше
+/// *fan_in*). Временные файлы удаляются, когда они больше не нужны.
+/// Результат - ленивый поток **Sorted**, который становится сбалансированным
+/// **BinaryTree**, **FrozenTree** или **PagedTree**.
+///
+/// # Example
+///
+/// ```
+/// use binartree::bulk::BulkLoader;
+///
+/// let loader = BulkLoader::new().with_memory(1024);
+/// let keys = (0..10_000u64).map(|x| x * 7919 % 10_000);
+/// let tree = loader.sort(keys).unwrap().into_tree().unwrap();
+/// assert_eq!(tree.len(), 10_000);
+/// assert_eq!(tree.to_vec(), (0..10_000).collect::<Vec<u64>>());
+/// assert!(tree.height() <= 14);
+/// ```
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkLoader {
+	memory: usize,
+	fan_in: usize,
+	temp_dir: PathBuf,
+	policy: DuplicatePolicy,
+}
+
+impl Default for BulkLoader {
+	#[inline]
+	fn default() -> Self {
+		BulkLoader::new()
+	}
+}
+
+impl BulkLoader {
+	/// *English*: Method **new()** creates loader with default memory budget, fan-in,
+	/// system temporary directory and *KeepAll* policy
+	///
+	/// *Russian*: Метод **new()** создаёт загрузчик с бюджетом памяти и числом сливаемых
+	/// серий по-умолчанию, системной временной папкой и политикой *KeepAll*
+	
+	pub fn new() -> Self {
+		BulkLoader {
+			memory: DEFAULT_MEMORY,
+			fan_in: DEFAULT_FAN_IN,
+			temp_dir: std::env::temp_dir(),
+			policy: DuplicatePolicy::KeepAll,
+		}
+	}
+	
+	/// *English*: Method **with_memory()** sets memory budget in bytes.
+	/// Sorting chunk and read buffers of merge fit in it (at least one key per chunk).
+	///
+	/// *Russian*: Метод **with_memory()** задаёт бюджет памяти в байтах.
+	/// В него помещаются сортируемая порция и буферы чтения при слиянии
+	/// (хотя бы один ключ на порцию).
+	
+	#[inline]
+	pub fn with_memory(mut self, memory: usize) -> Self {
+		self.memory = memory;
+		self
+	}
+	
+	/// *English*: Method **with_fan_in()** sets number of runs, which are merged at once.
+	/// It must be at least 2, otherwise it'll panic.
+	///
+	/// *Russian*: Метод **with_fan_in()** задаёт количество серий, сливаемых за раз.
+	/// Оно должно быть не меньше 2, иначе будет вызвана *паника*.
+	
+	#[inline]
+	pub fn with_fan_in(mut self, fan_in: usize) -> Self {
+		assert!(fan_in >= 2, "Fan-in must be at least 2");
+		self.fan_in = fan_in;
+		self
+	}
+	
+	/// *English*: Method **with_temp_dir()** sets directory for spill files
+	///
+	/// *Russian*: Метод **with_temp_dir()** задаёт папку для временных файлов
+	
+	#[inline]
+	pub fn with_temp_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+		self.temp_dir = dir.as_ref().to_path_buf();
+		self
+	}
+	
+	/// *English*: Method **with_policy()** sets **DuplicatePolicy** of result:
+	/// with *Reject* or *Replace* only one of equal keys is left.
+	///
+	/// *Russian*: Метод **with_policy()** задаёт **DuplicatePolicy** результата:
+	/// при *Reject* или *Replace* из равных ключей остаётся только один.
+	
+	#[inline]
+	pub fn with_policy(mut self, policy: DuplicatePolicy) -> Self {
+		self.policy = policy;
+		self
+	}
+	
+	/// *English*: Method **memory()** returns memory budget in bytes
+	///
+	/// *Russian*: Метод **memory()** возвращает бюджет памяти в байтах
+	
+	#[inline]
+	pub fn memory(&self) -> usize {
+		self.memory
+	}
+	
+	/// *English*: Method **sort()** sorts keys of iterator
+	///
+	/// *Russian*: Метод **sort()** сортирует ключи итератора
+	
+	#[inline]
+	pub fn sort<T, I>(&self, iter: I) -> Result<Sorted<T>, FormatError>
+		where T: FixedKey, I: IntoIterator<Item = T>
+	{
+		self.sort_results(iter.into_iter().map(Ok))
+	}
+	
+	/// *English*: Method **sort_reader()** sorts keys of reader, they are encoded
+	/// one after another like in **FixedKey::encode()**. Length of input must be
+	/// a multiple of key width, otherwise it's *Truncated* error.
+	///
+	/// *Russian*: Метод **sort_reader()** сортирует ключи из потока, они закодированы
+	/// друг за другом как в **FixedKey::encode()**. Длина входа должна быть кратна
+	/// ширине ключа, иначе будет ошибка *Truncated*.
+	
+	#[inline]
+	pub fn sort_reader<T, R>(&self, reader: R) -> Result<Sorted<T>, FormatError>
+		where T: FixedKey, R: Read
+	{
+		self.sort_results(KeyReader::new(reader))
+	}
+	
+	/// *English*: Size of read buffer for one run, so that *fan_in* of them fit in memory
+	///
+	/// *Russian*: Размер буфера чтения одной серии, чтобы *fan_in* таких поместились в память
+	
+	fn buffer<T: FixedKey>(&self) -> usize {
+		(self.memory / self.fan_in).max(T::WIDTH)
+	}
+	
+	fn sort_results<T, I>(&self, iter: I) -> Result<Sorted<T>, FormatError>
+		where T: FixedKey, I: Iterator<Item = Result<T, FormatError>>
+	{
+		let chunk = (self.memory / T::WIDTH).max(1);
+		let mut keys: Vec<T> = Vec::new();
+		let mut runs = Vec::new();
+		
+		for key in iter {
+			if keys.len() == keys.capacity() {
+				// grows without doubling over the budget
+				keys.reserve_exact(keys.len().max(16).min(chunk - keys.len()));
+			}
+			keys.push(key?);
+			if keys.len() == chunk {
+				keys.sort_unstable();
+				runs.push(self.spill(keys.iter().copied().map(Ok))?);
+				keys.clear();
+			}
+		}
+		
+		keys.sort_unstable();
+		if runs.is_empty() {
+			return Ok(Sorted { source: Source::Memory(keys.into_iter()), last: None, policy: self.policy });
+		}
+		if !keys.is_empty() {
+			runs.push(self.spill(keys.iter().copied().map(Ok))?);
+		}
+		drop(keys);
+		
+		while runs.len() > self.fan_in {
+			let group = runs.drain(..self.fan_in).collect();
+			let merged = self.spill(Merge::<T>::new(group, self.buffer::<T>())?)?;
+			runs.push(merged);
+		}
+		
+		let merge = Merge::new(runs, self.buffer::<T>())?;
+		Ok(Sorted { source: Source::Merge(merge), last: None, policy: self.policy })
+	}
+	
+	/// *English*: Writes sorted keys to new temporary file
+	///
+	/// *Russian*: Записывает отсортированные ключи в новый временный файл
+	
+	fn spill<T, I>(&self, keys: I) -> Result<Run, FormatError>
+		where T: FixedKey, I: Iterator<Item = Result<T, FormatError>>
+	{
+		let number = RUN_COUNTER.fetch_add(1, Ordering::Relaxed);
+		let path = self.temp_dir.join(format!("binartree-run-{}-{}", std::process::id(), number));
+		let file = OpenOptions::new().read(true).write(true).create_new(true).open(&path)?;
+		let mut run = Run { file, path };
+		
+		let mut writer = BufWriter::with_capacity(self.buffer::<T>(), &run.file);
+		let mut bytes = vec![0; T::WIDTH];
+		for key in keys {
+			key?.encode(&mut bytes);
+			writer.write_all(&bytes)?;
+		}
+		writer.flush()?;
+		drop(writer);
+		
+		run.file.seek(SeekFrom::Start(0))?;
+		Ok(run)
+	}
+}
+
+/// *English*: Temporary file with sorted keys, it's removed on drop
+///
+/// *Russian*: Временный файл с отсортированными ключами, он удаляется при удалении
+
+#[derive(Debug)]
+struct Run {
+	file: File,
+	path: PathBuf,
+}
+
+impl Drop for Run {
+	fn drop(&mut self) {
+		let _ = fs::remove_file(&self.path);
+	}
+}
+
+/// *English*: Iterator over keys of reader
+///
+/// *Russian*: Итератор по ключам потока
+
+struct KeyReader<T, R> {
+	reader: R,
+	bytes: Vec<u8>,
+	done: bool,
+	key: std::marker::PhantomData<T>,
+}
+
+impl<T: FixedKey, R: Read> KeyReader<T, R> {
+	fn new(reader: R) -> Self {
+		KeyReader { reader, bytes: vec![0; T::WIDTH], done: false, key: std::marker::PhantomData }
+	}
+}
+
+impl<T: FixedKey, R: Read> Iterator for KeyReader<T, R> {
+	type Item = Result<T, FormatError>;
+	
+	fn next(&mut self) -> Option<Result<T, FormatError>> {
+		if self.done {
+			return None;
+		}
+		
+		let mut filled = 0;
+		while filled < self.bytes.len() {
+			match self.reader.read(&mut self.bytes[filled..]) {
+				Ok(0) => break,
+				Ok(n) => filled += n,
+				Err(error) if error.kind() == ErrorKind::Interrupted => (),
+				Err(error) => {
+					self.done = true;
+					return Some(Err(error.into()));
+				}
+			}
+		}
+		
+		match filled {
+			0 => { self.done = true; None },
+			n if n < self.bytes.len() => { self.done = true; Some(Err(FormatError::Truncated)) },
+			_ => Some(Ok(T::decode(&self.bytes))),
+		}
+	}
+}
+
+/// *English*: K-way merge of runs with binary heap
+///
+/// *Russian*: K-путевое слияние серий при помощи двоичной кучи
+
+struct Merge<T> {
+	readers: Vec<KeyReader<T, BufReader<File>>>,
+	heap: BinaryHeap<Reverse<(T, usize)>>,
+	// runs are kept until merge ends, so files are removed after reading
+	_runs: Vec<Run>,
+}
+
+impl<T: FixedKey> Merge<T> {
+	fn new(runs: Vec<Run>, buffer: usize) -> Result<Self, FormatError> {
+		let mut readers = Vec::with_capacity(runs.len());
+		for run in runs.iter() {
+			readers.push(KeyReader::new(BufReader::with_capacity(buffer, run.file.try_clone()?)));
+		}
+		
+		let mut heap = BinaryHeap::with_capacity(runs.len());
+		for (i, reader) in readers.iter_mut().enumerate() {
+			if let Some(key) = reader.next() {
+				heap.push(Reverse((key?, i)));
+			}
+		}
+		Ok(Merge { readers, heap, _runs: runs })
+	}
+}
+
+impl<T: FixedKey> Iterator for Merge<T> {
+	type Item = Result<T, FormatError>;
+	
+	fn next(&mut self) -> Option<Result<T, FormatError>> {
+		let Reverse((key, i)) = self.heap.pop()?;
+		match self.readers[i].next() {
+			Some(Ok(next)) => self.heap.push(Reverse((next, i))),
+			Some(Err(error)) => {
+				self.heap.clear();
+				return Some(Err(error));
+			},
+			None => (),
+		}
+		Some(Ok(key))
+	}
+}
+
+enum Source<T> {
+	Memory(vec::IntoIter<T>),
+	Merge(Merge<T>),
+}
+
+/// *English*: Lazy sorted stream of **BulkLoader**. Keys come from memory or from
+/// merge of spill files, I/O error ends the stream.
+///
+/// *Russian*: Ленивый отсортированный поток **BulkLoader**. Ключи берутся из памяти
+/// или из слияния временных файлов, ошибка ввода-вывода заканчивает поток.
+
+pub struct Sorted<T> {
+	source: Source<T>,
+	last: Option<T>,
+	policy: DuplicatePolicy,
+}
+
+impl<T: FixedKey> Iterator for Sorted<T> {
+	type Item = Result<T, FormatError>;
+	
+	fn next(&mut self) -> Option<Result<T, FormatError>> {
+		loop {
+			let key = match self.source {
+				Source::Memory(ref mut iter) => iter.next().map(Ok),
+				Source::Merge(ref mut merge) => merge.next(),
+			};
+			match key {
+				Some(Ok(key)) if self.policy != DuplicatePolicy::KeepAll && self.last == Some(key) => continue,
+				Some(Ok(key)) => {
+					self.last = Some(key);
+					return Some(Ok(key));
+				},
+				other => return other,
+			}
+		}
+	}
+}
+
+impl<T: FixedKey> Sorted<T> {
+	/// *English*: Method **into_tree()** builds *balanced* **BinaryTree** in O(n).
+	/// Tree lives in memory, so only sorting is external.
+	///
+	/// *Russian*: Метод **into_tree()** строит *сбалансированное* **BinaryTree** за O(n).
+	/// Дерево живёт в памяти, так что внешней является только сортировка.
+	
+	pub fn into_tree(self) -> Result<BinaryTree<T>, FormatError> {
+		let policy = self.policy;
+		let keys = self.collect::<Result<Vec<T>, FormatError>>()?;
+		let mut tree = BinaryTree::with_policy(policy);
+		tree.top = Node::from_sorted(&keys);
+		tree.size = keys.len();
+		tree.check();
+		Ok(tree)
+	}
+	
+	/// *English*: Method **into_frozen()** builds **FrozenTree** in O(n)
+	///
+	/// *Russian*: Метод **into_frozen()** строит **FrozenTree** за O(n)
+	
+	pub fn into_frozen(self) -> Result<FrozenTree<T>, FormatError> {
+		let keys = self.collect::<Result<Vec<T>, FormatError>>()?;
+		Ok(FrozenTree::from_sorted(&keys))
+	}
+	
+	/// *English*: Method **into_paged()** writes keys to new **PagedTree** file.
+	/// Keys come in order, so only the rightmost path of tree is in use and
+	/// memory is bounded by page cache.
+	///
+	/// *Russian*: Метод **into_paged()** записывает ключи в новый файл **PagedTree**.
+	/// Ключи идут по порядку, так что используется лишь самый правый путь дерева,
+	/// и память ограничена кэшем страниц.
+	
+	pub fn into_paged<P: AsRef<Path>>(self, path: P, page_size: usize, cache_pages: usize) -> Result<PagedTree<T>, FormatError> {
+		let mut tree = PagedTree::create_with_policy(path, page_size, cache_pages, self.policy)?;
+		for key in self {
+			tree.insert(&key?)?;
+		}
+		tree.flush()?;
+		Ok(tree)
+	}
+}
@@ -0,0 +1,533 @@
+use crate::branch::Branch;
+use crate::error::FormatError;
+use crate::node::Node;
+use crate::tree::{BinaryTree, DuplicatePolicy};
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+
+/// **Binary format**
+/// -----------------
+///
+/// *English*: Stable on-disk format of **BinaryTree** with fixed-width keys.
+/// All numbers are little-endian. Version 1.0 looks like this:
+///
+/// *Russian*: Стабильный формат хранения **BinaryTree** с ключами фиксированной ширины.
+/// Все числа в порядке little-endian. Версия 1.0 выглядит так:
+///
+/// ```text
+/// offset  size  field
+///      0     4  magic "BNTR"
+///      4     1  major version (1)
+///      5     1  minor version (0)
+///      6     2  header length (24), newer minors may append fields
+///      8     2  flags: low byte is required, high byte is optional
+///     10     1  key type tag
+///     11     1  duplicate policy (0 - KeepAll, 1 - Reject, 2 - Replace)
+///     12     4  key width in bytes
+///     16     8  element count
+///     24     -  body: sorted keys or pre-order records (links byte + key)
+///      -     8  FNV-1a 64 checksum of everything before it
+/// ```
+///
+/// *English*: Readers accept any minor version and skip unknown header bytes
+/// and optional flags, but reject unknown major versions and required flags.
+///
+/// *Russian*: Читатели принимают любую младшую версию и пропускают неизвестные байты
+/// заголовка и необязательные флаги, но отвергают неизвестные основные версии и
+/// обязательные флаги.
+
+pub const MAGIC: [u8; 4] = *b"BNTR";
+
+/// *English*: Major version of the format. Changes only with incompatible layout.
+///
+/// *Russian*: Основная версия формата. Меняется только при несовместимой раскладке.
+
+pub const MAJOR_VERSION: u8 = 1;
+
+/// *English*: Minor version of the format, which this crate writes
+///
+/// *Russian*: Младшая версия формата, которую пишет этот крейт
+
+pub const MINOR_VERSION: u8 = 0;
+
+/// *English*: Length of version 1.0 header
+///
+/// *Russian*: Длина заголовка версии 1.0
+
+const HEADER_LEN: usize = 24;
+
+/// *English*: Required flag: body keeps the shape of tree (pre-order records)
+///
+/// *Russian*: Обязательный флаг: тело сохраняет форму дерева (записи в прямом порядке)
+
+const FLAG_SHAPE: u16 = 1;
+
+/// *English*: Required flags, which this reader knows
+///
+/// *Russian*: Обязательные флаги, которые знает этот читатель
+
+const KNOWN_REQUIRED: u16 = FLAG_SHAPE;
+
+/// *English*: Bits of links byte of pre-order record
+///
+/// *Russian*: Биты байта связей у записи в прямом порядке
+
+const HAS_LEFT: u8 = 1;
+const HAS_RIGHT: u8 = 2;
+
+/// *English*: How body of tree is written
+///
+/// *Russian*: Как записывается тело дерева
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+	/// *English*: Only sorted keys. It's the most compact layout,
+	/// loaded tree is *balanced*.
+	///
+	/// *Russian*: Только отсортированные ключи. Самая компактная раскладка,
+	/// загруженное дерево *сбалансировано*.
+	Sorted,
+	
+	/// *English*: Pre-order records with links byte. Loaded tree has
+	/// *exactly the same shape*, so it takes one byte per key more.
+	///
+	/// *Russian*: Записи в прямом порядке с байтом связей. Загруженное дерево имеет
+	/// *точно такую же форму*, поэтому занимает на байт больше на каждый ключ.
+	Shape,
+}
+
+/// *English*: Key with fixed-width binary representation, which can be stored.
+/// It's implemented for all fixed-width integers and byte strings *[u8; N]*.
+/// *TAG* and *WIDTH* are stored in header, so reading with wrong type fails.
+///
+/// *Russian*: Ключ с двоичным представлением фиксированной ширины, который можно сохранить.
+/// Реализован для всех целых чисел фиксированной ширины и байтовых строк *[u8; N]*.
+/// *TAG* и *WIDTH* хранятся в заголовке, так что чтение с неверным типом не удастся.
+
+pub trait FixedKey: Copy + Clone + Ord + Eq {
+	/// *English*: Type tag of key in header
+	///
+	/// *Russian*: Метка типа ключа в заголовке
+	const TAG: u8;
+	
+	/// *English*: Number of bytes in encoded key
+	///
+	/// *Russian*: Количество байт в закодированном ключе
+	const WIDTH: usize;
+	
+	/// *English*: Writes key to *WIDTH* bytes
+	///
+	/// *Russian*: Записывает ключ в *WIDTH* байт
+	fn encode(&self, out: &mut [u8]);
+	
+	/// *English*: Reads key from *WIDTH* bytes
+	///
+	/// *Russian*: Читает ключ из *WIDTH* байт
+	fn decode(src: &[u8]) -> Self;
+}
+
+macro_rules! impl_fixed_key {
+	($($int:ty => $tag:expr),*) => {
+		$(
+			impl FixedKey for $int {
+				const TAG: u8 = $tag;
+				const WIDTH: usize = core::mem::size_of::<$int>();
+				
+				#[inline]
+				fn encode(&self, out: &mut [u8]) {
+					out.copy_from_slice(&self.to_le_bytes());
+				}
+				
+				#[inline]
+				fn decode(src: &[u8]) -> Self {
+					let mut bytes = [0; core::mem::size_of::<$int>()];
+					bytes.copy_from_slice(src);
+					<$int>::from_le_bytes(bytes)
+				}
+			}
+		)*
+	};
+}
+
+impl_fixed_key!(
+	u8 => 0x01, u16 => 0x02, u32 => 0x03, u64 => 0x04, u128 => 0x05,
+	i8 => 0x11, i16 => 0x12, i32 => 0x13, i64 => 0x14, i128 => 0x15
+);
+
+impl<const N: usize> FixedKey for [u8; N] {
+	const TAG: u8 = 0x20;
+	const WIDTH: usize = N;
+	
+	#[inline]
+	fn encode(&self, out: &mut [u8]) {
+		out.copy_from_slice(self);
+	}
+	
+	#[inline]
+	fn decode(src: &[u8]) -> Self {
+		let mut bytes = [0; N];
+		bytes.copy_from_slice(src);
+		bytes
+	}
+}
+
+/// *English*: FNV-1a 64 hash. Every step is a bijection of state,
+/// so any change of one byte always changes the result.
+///
+/// *Russian*: Хэш FNV-1a 64. Каждый шаг - биекция состояния,
+/// так что изменение любого одного байта всегда меняет результат.
+
+pub(crate) fn checksum(bytes: &[u8]) -> u64 {
+	Fnv::default().update(bytes).0
+}
+
+#[derive(Clone, Copy)]
+struct Fnv(u64);
+
+impl Default for Fnv {
+	#[inline]
+	fn default() -> Self {
+		Fnv(0xcbf29ce484222325)
+	}
+}
+
+impl Fnv {
+	#[inline]
+	fn update(mut self, bytes: &[u8]) -> Self {
+		for byte in bytes {
+			self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x100000001b3);
+		}
+		self
+	}
+}
+
+/// *English*: Writer, which buffers output and hashes everything written
+///
+/// *Russian*: Писатель, который буферизует вывод и хэширует всё записанное
+
+struct HashWriter<W: Write> {
+	inner: W,
+	buf: Vec<u8>,
+	hash: Fnv,
+}
+
+impl<W: Write> HashWriter<W> {
+	fn put(&mut self, bytes: &[u8]) -> io::Result<()> {
+		self.hash = self.hash.update(bytes);
+		self.buf.extend_from_slice(bytes);
+		if self.buf.len() >= 1 << 13 {
+			self.inner.write_all(&self.buf)?;
+			self.buf.clear();
+		}
+		Ok(())
+	}
+	
+	fn finish(mut self) -> io::Result<()> {
+		let sum = self.hash.0.to_le_bytes();
+		self.buf.extend_from_slice(&sum);
+		self.inner.write_all(&self.buf)?;
+		self.inner.flush()
+	}
+}
+
+/// *English*: Reader, which hashes everything read
+///
+/// *Russian*: Читатель, который хэширует всё прочитанное
+
+struct HashReader<R: Read> {
+	inner: R,
+	hash: Fnv,
+}
+
+impl<R: Read> HashReader<R> {
+	fn take(&mut self, buf: &mut [u8]) -> Result<(), FormatError> {
+		self.inner.read_exact(buf)?;
+		self.hash = self.hash.update(buf);
+		Ok(())
+	}
+}
+
+/// *English*: Header fields, which are needed to read body
+///
+/// *Russian*: Поля заголовка, нужные для чтения тела
+
+struct Header {
+	flags: u16,
+	policy: DuplicatePolicy,
+	count: u64,
+}
+
+impl Header {
+	fn read<T: FixedKey, R: Read>(src: &mut HashReader<R>) -> Result<Self, FormatError> {
+		let mut head = [0; HEADER_LEN];
+		src.take(&mut head[..4])?;
+		if head[..4] != MAGIC {
+			return Err(FormatError::Magic);
+		}
+		src.take(&mut head[4..8])?;
+		if head[4] != MAJOR_VERSION {
+			return Err(FormatError::Version { major: head[4] });
+		}
+		
+		let header_len = usize::from(u16::from_le_bytes([head[6], head[7]]));
+		if header_len < HEADER_LEN {
+			return Err(FormatError::Layout);
+		}
+		src.take(&mut head[8..])?;
+		
+		// fields of newer minor versions
+		let mut rest = header_len - HEADER_LEN;
+		let mut skip = [0; 64];
+		while rest > 0 {
+			let len = rest.min(skip.len());
+			src.take(&mut skip[..len])?;
+			rest -= len;
+		}
+		
+		let flags = u16::from_le_bytes([head[8], head[9]]);
+		if flags & 0xff & !KNOWN_REQUIRED != 0 {
+			return Err(FormatError::Flags { flags });
+		}
+		
+		let width = u32::from_le_bytes([head[12], head[13], head[14], head[15]]);
+		if head[10] != T::TAG || width as usize != T::WIDTH {
+			return Err(FormatError::KeyType { tag: head[10], width });
+		}
+		
+		let policy = match head[11] {
+			0 => DuplicatePolicy::KeepAll,
+			1 => DuplicatePolicy::Reject,
+			2 => DuplicatePolicy::Replace,
+			_ => return Err(FormatError::Layout),
+		};
+		
+		let mut count = [0; 8];
+		count.copy_from_slice(&head[16..24]);
+		Ok(Header { flags, policy, count: u64::from_le_bytes(count) })
+	}
+}
+
+/// *English*: Methods for storing tree with fixed-width keys
+///
+/// *Russian*: Методы для хранения дерева с ключами фиксированной ширины
+
+impl<T> BinaryTree<T>
+	where T: Copy + Clone + Ord + Eq + FixedKey
+{
+	/// *English*: Method **write_to()** writes tree in **Layout::Sorted**.
+	/// Loaded tree will be balanced. Policy is stored, limit and balance factor aren't.
+	///
+	/// *Russian*: Метод **write_to()** записывает дерево в **Layout::Sorted**.
+	/// Загруженное дерево будет сбалансировано. Политика сохраняется,
+	/// предел и коэффициент баланса - нет.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let tree = BinaryTree::from_iter(vec![3u32, 1, 2]);
+	/// let mut bytes = Vec::new();
+	/// tree.write_to(&mut bytes).unwrap();
+	///
+	/// let loaded = BinaryTree::<u32>::read_from(bytes.as_slice()).unwrap();
+	/// assert_eq!(loaded, tree);
+	/// ```
+	
+	#[inline]
+	pub fn write_to<W: Write>(&self, out: W) -> io::Result<()> {
+		self.write_layout_to(out, Layout::Sorted)
+	}
+	
+	/// *English*: Method **write_layout_to()** writes tree in given layout.
+	/// **Layout::Shape** keeps the exact shape of tree. Takes O(n) time
+	/// and O(height) memory.
+	///
+	/// *Russian*: Метод **write_layout_to()** записывает дерево в данной раскладке.
+	/// **Layout::Shape** сохраняет точную форму дерева. Занимает O(n) времени
+	/// и O(высоты) памяти.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::codec::Layout;
+	/// use binartree::tree::BinaryTree;
+	///
+	/// let mut tree = BinaryTree::new();
+	/// for key in 0..100u64 {
+	///     tree.insert(&key);
+	/// }
+	///
+	/// let mut bytes = Vec::new();
+	/// tree.write_layout_to(&mut bytes, Layout::Shape).unwrap();
+	///
+	/// let loaded = BinaryTree::<u64>::read_from(bytes.as_slice()).unwrap();
+	/// assert_eq!(loaded.height(), 100);
+	/// ```
+	
+	pub fn write_layout_to<W: Write>(&self, out: W, layout: Layout) -> io::Result<()> {
+		let mut out = HashWriter { inner: out, buf: Vec::with_capacity(1 << 13), hash: Fnv::default() };
+		let flags = if layout == Layout::Shape { FLAG_SHAPE } else { 0 };
+		let policy: u8 = match self.policy {
+			DuplicatePolicy::KeepAll => 0,
+			DuplicatePolicy::Reject => 1,
+			DuplicatePolicy::Replace => 2,
+		};
+		
+		out.put(&MAGIC)?;
+		out.put(&[MAJOR_VERSION, MINOR_VERSION])?;
+		out.put(&(HEADER_LEN as u16).to_le_bytes())?;
+		out.put(&flags.to_le_bytes())?;
+		out.put(&[T::TAG, policy])?;
+		out.put(&(T::WIDTH as u32).to_le_bytes())?;
+		out.put(&(self.size as u64).to_le_bytes())?;
+		
+		let mut record = vec![0; T::WIDTH + 1];
+		match layout {
+			Layout::Sorted => for key in self.iter() {
+				key.encode(&mut record[..T::WIDTH]);
+				out.put(&record[..T::WIDTH])?;
+			},
+			
+			Layout::Shape => {
+				let mut stack = Vec::new();
+				if let Node::NonEmpty(ref branch) = self.top {
+					stack.push(branch);
+				}
+				
+				while let Some(branch) = stack.pop() {
+					let mut links = 0;
+					if let Node::NonEmpty(ref right) = branch.right {
+						links |= HAS_RIGHT;
+						stack.push(right);
+					}
+					if let Node::NonEmpty(ref left) = branch.left {
+						links |= HAS_LEFT;
+						stack.push(left);
+					}
+					
+					record[0] = links;
+					branch.key.encode(&mut record[1..]);
+					out.put(&record)?;
+				}
+			}
+		}
+		out.finish()
+	}
+	
+	/// *English*: Method **read_from()** reads tree written by **write_to()**
+	/// or **write_layout_to()**. Data is checked completely: header, checksum,
+	/// links, order of keys and policy, so damaged data gives *FormatError*
+	/// and never a broken tree or panic. Extra bytes after tree aren't read.
+	///
+	/// *Russian*: Метод **read_from()** читает дерево, записанное **write_to()**
+	/// или **write_layout_to()**. Данные проверяются полностью: заголовок, контрольная
+	/// сумма, связи, порядок ключей и политика, так что повреждённые данные дают
+	/// *FormatError*, но никогда не сломанное дерево или панику.
+	/// Лишние байты после дерева не читаются.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::error::FormatError;
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut bytes = Vec::new();
+	/// BinaryTree::from_iter(vec![*b"ab", *b"cd"]).write_to(&mut bytes).unwrap();
+	///
+	/// bytes.pop();
+	/// assert_eq!(BinaryTree::<[u8; 2]>::read_from(bytes.as_slice()), Err(FormatError::Truncated));
+	/// ```
+	
+	pub fn read_from<R: Read>(src: R) -> Result<Self, FormatError> {
+		let mut src = HashReader { inner: src, hash: Fnv::default() };
+		let header = Header::read::<T, R>(&mut src)?;
+		let shape = header.flags & FLAG_SHAPE != 0;
+		
+		// count isn't trusted until checksum is verified, so memory is reserved lazily
+		let count = usize::try_from(header.count).map_err(|_| FormatError::Layout)?;
+		let capacity = count.min(1 << 16);
+		let mut keys = Vec::with_capacity(capacity);
+		let mut links = Vec::with_capacity(if shape { capacity } else { 0 });
+		let mut record = vec![0; T::WIDTH + 1];
+		
+		for _ in 0..count {
+			if shape {
+				src.take(&mut record)?;
+				links.push(record[0]);
+				keys.push(T::decode(&record[1..]));
+			} else {
+				src.take(&mut record[..T::WIDTH])?;
+				keys.push(T::decode(&record[..T::WIDTH]));
+			}
+		}
+		
+		let expected = src.hash.0;
+		let mut sum = [0; 8];
+		src.inner.read_exact(&mut sum)?;
+		if u64::from_le_bytes(sum) != expected {
+			return Err(FormatError::Checksum);
+		}
+		
+		let mut tree = BinaryTree::with_policy(header.policy);
+		tree.top = if shape { from_preorder(&keys, &links)? } else { Node::from_sorted(&keys) };
+		tree.size = count;
+		tree.validate().map_err(FormatError::Invariant)?;
+		Ok(tree)
+	}
+}
+
+/// *English*: Builds tree from pre-order records without recursion.
+/// At first child indices are found with stack of free slots,
+/// then nodes are built from the end, because children follow their parents.
+///
+/// *Russian*: Строит дерево из записей в прямом порядке без рекурсии.
+/// Сначала индексы потомков находятся с помощью стека свободных мест,
+/// затем узлы строятся с конца, ведь потомки идут после родителей.
+
+fn from_preorder<T>(keys: &[T], links: &[u8]) -> Result<Node<T>, FormatError>
+	where T: Copy + Clone + Ord + Eq
+{
+	if keys.is_empty() {
+		return Ok(Node::Empty);
+	}
+	
+	// (parent, is_left) of every record, root has no parent
+	let mut parents = vec![None; keys.len()];
+	let mut slots = vec![None];
+	
+	for (index, link) in links.iter().enumerate() {
+		if link & !(HAS_LEFT | HAS_RIGHT) != 0 {
+			return Err(FormatError::Layout);
+		}
+		parents[index] = slots.pop().ok_or(FormatError::Layout)?;
+		if link & HAS_RIGHT != 0 {
+			slots.push(Some((index, false)));
+		}
+		if link & HAS_LEFT != 0 {
+			slots.push(Some((index, true)));
+		}
+	}
+	if !slots.is_empty() {
+		return Err(FormatError::Layout);
+	}
+	
+	let mut nodes: Vec<Node<T>> = keys.iter()
+		.map(|key| Node::NonEmpty(Box::new(Branch { key: *key, right: Node::Empty, left: Node::Empty })))
+		.collect();
+	
+	for index in (1..keys.len()).rev() {
+		let node = std::mem::take(&mut nodes[index]);
+		if let Some((parent, is_left)) = parents[index] {
+			let branch = nodes[parent].ignore_mut();
+			if is_left {
+				branch.left = node;
+			} else {
+				branch.right = node;
+			}
+		}
+	}
+	Ok(std::mem::take(&mut nodes[0]))
+}
@@ -0,0 +1,448 @@
+use crate::codec::{checksum, FixedKey};
+use crate::error::FormatError;
+use crate::tree::{BinaryTree, DuplicatePolicy};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// *English*: Magic bytes of write-ahead log
+///
+/// *Russian*: Магические байты журнала упреждающей записи
+
+pub const WAL_MAGIC: [u8; 4] = *b"BNWL";
+
+/// *English*: Length of log header: magic, major version, key tag,
+/// key width (2 bytes) and checksum of base snapshot (8 bytes)
+///
+/// *Russian*: Длина заголовка журнала: магия, основная версия, метка ключа,
+/// ширина ключа (2 байта) и контрольная сумма базового снимка (8 байт)
+
+const WAL_HEADER_LEN: usize = 16;
+
+const SNAPSHOT: &str = "snapshot";
+const SNAPSHOT_TMP: &str = "snapshot.tmp";
+const WAL: &str = "wal";
+const WAL_TMP: &str = "wal.tmp";
+
+/// *English*: Operations in log records
+///
+/// *Russian*: Операции в записях журнала
+
+const OP_INSERT: u8 = 1;
+const OP_REMOVE: u8 = 2;
+const OP_CLEAR: u8 = 3;
+
+/// **Durable Tree**
+/// ----------------
+///
+/// *English*: **DurableTree** is a **BinaryTree**, which survives crashes.
+/// It lives in a directory with two files: *snapshot* (tree in binary format
+/// of **codec.rs**) and *wal* (write-ahead log). Every **insert()**, **remove()**
+/// and **clear()** is appended to the log *before* it's applied, **checkpoint()**
+/// writes new snapshot and starts empty log. **open()** reads snapshot
+/// and replays the log. Record is a fixed-size *op, key, checksum*, so a torn
+/// write at the end of log is found and cut off: tree recovers to the state after
+/// the last complete record. Log remembers checksum of its snapshot, so log
+/// of the previous snapshot (after crash in the middle of checkpoint) is ignored.
+/// All query methods of **BinaryTree** are available through **Deref**.
+///
+/// *Russian*: **DurableTree** - это **BinaryTree**, которое переживает сбои.
+/// Оно живёт в каталоге с двумя файлами: *snapshot* (дерево в двоичном формате
+/// **codec.rs**) и *wal* (журнал упреждающей записи). Каждая операция **insert()**,
+/// **remove()** и **clear()** дописывается в журнал *до* её применения, **checkpoint()**
+/// записывает новый снимок и начинает пустой журнал. **open()** читает снимок
+/// и воспроизводит журнал. Запись имеет фиксированный размер: *операция, ключ,
+/// контрольная сумма*, так что оборванная запись в конце журнала находится и отрезается:
+/// дерево восстанавливается до состояния после последней полной записи. Журнал помнит
+/// контрольную сумму своего снимка, так что журнал предыдущего снимка (после сбоя
+/// посреди **checkpoint()**) игнорируется. Все методы запросов **BinaryTree**
+/// доступны через **Deref**.
+///
+/// # Example
+///
+/// ```
+/// use binartree::durable::DurableTree;
+///
+/// let dir = std::env::temp_dir().join(format!("binartree-doc-durable-{}", std::process::id()));
+/// {
+///     let mut tree = DurableTree::<u64>::open(&dir).unwrap();
+///     tree.insert(&3).unwrap();
+///     tree.insert(&1).unwrap();
+///     tree.checkpoint().unwrap();
+///     tree.remove(&3).unwrap();
+/// }
+///
+/// let tree = DurableTree::<u64>::open(&dir).unwrap();
+/// assert_eq!(tree.to_vec(), vec![1]);
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+
+#[derive(Debug)]
+pub struct DurableTree<T>
+	where T: Copy + Clone + Ord + Eq + FixedKey
+{
+	tree: BinaryTree<T>,
+	dir: PathBuf,
+	log: File,
+	records: usize,
+	auto_checkpoint: Option<usize>,
+	sync: bool,
+}
+
+/// *English*: Size of one log record
+///
+/// *Russian*: Размер одной записи журнала
+
+#[inline]
+fn record_len<T: FixedKey>() -> usize {
+	1 + T::WIDTH + 4
+}
+
+/// *English*: Encodes log record: operation, key (zeros for *clear*) and 4 bytes of checksum
+///
+/// *Russian*: Кодирует запись журнала: операцию, ключ (нули для *clear*) и 4 байта контрольной суммы
+
+fn encode_record<T: FixedKey>(op: u8, key: Option<&T>, out: &mut [u8]) {
+	out.iter_mut().for_each(|byte| *byte = 0);
+	out[0] = op;
+	if let Some(key) = key {
+		key.encode(&mut out[1..=T::WIDTH]);
+	}
+	let sum = checksum(&out[..=T::WIDTH]) as u32;
+	out[T::WIDTH + 1..].copy_from_slice(&sum.to_le_bytes());
+}
+
+/// *English*: Writes file completely and syncs it, then renames it to the final name.
+/// Rename is atomic, so other side sees either old or new file.
+///
+/// *Russian*: Записывает файл полностью и синхронизирует его, затем переименовывает
+/// в окончательное имя. Переименование атомарно, так что видно либо старый, либо новый файл.
+
+fn replace_file(dir: &Path, tmp: &str, name: &str, bytes: &[u8]) -> io::Result<()> {
+	let tmp = dir.join(tmp);
+	let mut file = File::create(&tmp)?;
+	file.write_all(bytes)?;
+	file.sync_all()?;
+	fs::rename(&tmp, dir.join(name))?;
+	
+	// directory entry must be durable too (not supported on some platforms)
+	if let Ok(dir) = File::open(dir) {
+		let _ = dir.sync_all();
+	}
+	Ok(())
+}
+
+impl<T> DurableTree<T>
+	where T: Copy + Clone + Ord + Eq + FixedKey
+{
+	/// *English*: Method **open()** opens tree in directory or creates empty one.
+	/// Tree is recovered from snapshot and log, torn tail of log is cut off.
+	/// Existing tree keeps its policy, new tree has *KeepAll* policy.
+	///
+	/// *Russian*: Метод **open()** открывает дерево в каталоге или создаёт пустое.
+	/// Дерево восстанавливается из снимка и журнала, оборванный хвост журнала отрезается.
+	/// Существующее дерево сохраняет свою политику, новое имеет политику *KeepAll*.
+	
+	#[inline]
+	pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self, FormatError> {
+		DurableTree::open_inner(dir.as_ref(), DuplicatePolicy::KeepAll, false)
+	}
+	
+	/// *English*: Method **open_with_policy()** works like **open()**, but new tree
+	/// gets given policy. Existing tree must have the same policy (it's stored in snapshot),
+	/// otherwise *FormatError::Policy* is returned.
+	///
+	/// *Russian*: Метод **open_with_policy()** работает как **open()**, но новое дерево
+	/// получает данную политику. Существующее дерево должно иметь ту же политику (она хранится
+	/// в снимке), иначе возвращается *FormatError::Policy*.
+	
+	#[inline]
+	pub fn open_with_policy<P: AsRef<Path>>(dir: P, policy: DuplicatePolicy) -> Result<Self, FormatError> {
+		DurableTree::open_inner(dir.as_ref(), policy, true)
+	}
+	
+	/// *English*: Opens tree, *strict* checks that existing tree has given policy.
+	/// New tree is written to snapshot at once, so its policy is never lost.
+	///
+	/// *Russian*: Открывает дерево, *strict* проверяет, что существующее дерево имеет данную политику.
+	/// Новое дерево сразу записывается в снимок, так что его политика не теряется.
+	
+	fn open_inner(dir: &Path, policy: DuplicatePolicy, strict: bool) -> Result<Self, FormatError> {
+		let dir = dir.to_path_buf();
+		fs::create_dir_all(&dir)?;
+		
+		let (mut tree, base, created) = match fs::read(dir.join(SNAPSHOT)) {
+			Ok(bytes) => {
+				let tree = BinaryTree::read_from(bytes.as_slice())?;
+				if strict && tree.policy() != policy {
+					return Err(FormatError::Policy { stored: tree.policy() });
+				}
+				let mut base = [0; 8];
+				base.copy_from_slice(&bytes[bytes.len() - 8..]);
+				(tree, u64::from_le_bytes(base), false)
+			},
+			Err(error) if error.kind() == io::ErrorKind::NotFound => (BinaryTree::with_policy(policy), 0, true),
+			Err(error) => return Err(error.into()),
+		};
+		
+		let mut log = Vec::new();
+		match File::open(dir.join(WAL)) {
+			Ok(mut file) => { file.read_to_end(&mut log)?; },
+			Err(error) if error.kind() == io::ErrorKind::NotFound => (),
+			Err(error) => return Err(error.into()),
+		}
+		
+		let records = DurableTree::replay(&mut tree, &log, base)?;
+		let valid = WAL_HEADER_LEN + records * record_len::<T>();
+		if records == 0 || log.len() != valid {
+			// start log from scratch or cut off the torn tail
+			if records == 0 {
+				replace_file(&dir, WAL_TMP, WAL, &DurableTree::<T>::header(base))?;
+			} else {
+				let file = OpenOptions::new().write(true).open(dir.join(WAL))?;
+				file.set_len(valid as u64)?;
+				file.sync_all()?;
+			}
+		}
+		
+		let log = OpenOptions::new().append(true).open(dir.join(WAL))?;
+		let mut durable = DurableTree { tree, dir, log, records, auto_checkpoint: None, sync: true };
+		if created {
+			durable.checkpoint()?;
+		}
+		Ok(durable)
+	}
+	
+	/// *English*: Header of log for snapshot with given checksum
+	///
+	/// *Russian*: Заголовок журнала для снимка с данной контрольной суммой
+	
+	fn header(base: u64) -> [u8; WAL_HEADER_LEN] {
+		let mut header = [0; WAL_HEADER_LEN];
+		header[..4].copy_from_slice(&WAL_MAGIC);
+		header[4] = 1;
+		header[5] = T::TAG;
+		header[6..8].copy_from_slice(&(T::WIDTH as u16).to_le_bytes());
+		header[8..].copy_from_slice(&base.to_le_bytes());
+		header
+	}
+	
+	/// *English*: Applies complete records of log to tree. Returns their number.
+	/// Log with torn header or of other snapshot has no records for this tree.
+	///
+	/// *Russian*: Применяет полные записи журнала к дереву. Возвращает их количество.
+	/// В журнале с оборванным заголовком или от другого снимка нет записей для этого дерева.
+	
+	fn replay(tree: &mut BinaryTree<T>, log: &[u8], base: u64) -> Result<usize, FormatError> {
+		if log.len() < WAL_HEADER_LEN {
+			return Ok(0);
+		}
+		
+		let header = DurableTree::<T>::header(base);
+		if log[..4] != WAL_MAGIC {
+			return Err(FormatError::Magic);
+		}
+		if log[4] != 1 {
+			return Err(FormatError::Version { major: log[4] });
+		}
+		if log[5..8] != header[5..8] {
+			return Err(FormatError::KeyType { tag: log[5], width: u32::from(u16::from_le_bytes([log[6], log[7]])) });
+		}
+		if log[8..WAL_HEADER_LEN] != header[8..] {
+			return Ok(0);
+		}
+		
+		let mut expected = vec![0; record_len::<T>()];
+		let mut records = 0;
+		for record in log[WAL_HEADER_LEN..].chunks_exact(record_len::<T>()) {
+			let key = T::decode(&record[1..=T::WIDTH]);
+			encode_record(record[0], Some(&key), &mut expected);
+			if record != expected.as_slice() {
+				break;
+			}
+			
+			match record[0] {
+				OP_INSERT => { tree.insert(&key); },
+				OP_REMOVE => tree.remove(&key),
+				OP_CLEAR => tree.clear(),
+				_ => return Err(FormatError::Layout),
+			}
+			records += 1;
+		}
+		Ok(records)
+	}
+	
+	/// *English*: Method **set_sync()** chooses, whether every mutation waits
+	/// until its record reaches the disk (*true* by default). Without it
+	/// records are only given to OS and may be lost on power failure (not on crash
+	/// of the process), but mutations are much faster.
+	///
+	/// *Russian*: Метод **set_sync()** выбирает, ждёт ли каждое изменение,
+	/// пока его запись достигнет диска (по-умолчанию *true*). Без этого
+	/// записи лишь передаются ОС и могут потеряться при отключении питания (но не при
+	/// падении процесса), зато изменения гораздо быстрее.
+	
+	#[inline]
+	pub fn set_sync(&mut self, sync: bool) {
+		self.sync = sync;
+	}
+	
+	/// *English*: Method **set_auto_checkpoint()** makes tree call **checkpoint()**,
+	/// when log has given number of records. *None* turns it off (default).
+	///
+	/// *Russian*: Метод **set_auto_checkpoint()** заставляет дерево вызывать **checkpoint()**,
+	/// когда в журнале накопилось данное количество записей. *None* выключает это
+	/// (по-умолчанию).
+	
+	#[inline]
+	pub fn set_auto_checkpoint(&mut self, records: Option<usize>) {
+		self.auto_checkpoint = records;
+	}
+	
+	/// *English*: Method **log_records()** returns number of records in log since last checkpoint
+	///
+	/// *Russian*: Метод **log_records()** возвращает количество записей в журнале после
+	/// последнего снимка
+	
+	#[inline]
+	pub fn log_records(&self) -> usize {
+		self.records
+	}
+	
+	/// *English*: Method **dir()** returns directory of tree
+	///
+	/// *Russian*: Метод **dir()** возвращает каталог дерева
+	
+	#[inline]
+	pub fn dir(&self) -> &Path {
+		&self.dir
+	}
+	
+	/// *English*: Appends record to log. Tree isn't changed yet.
+	///
+	/// *Russian*: Дописывает запись в журнал. Дерево ещё не изменено.
+	
+	fn append(&mut self, op: u8, key: Option<&T>) -> io::Result<()> {
+		let mut record = vec![0; record_len::<T>()];
+		encode_record(op, key, &mut record);
+		let mut result = self.log.write_all(&record);
+		if result.is_ok() && self.sync {
+			result = self.log.sync_data();
+		}
+		if let Err(error) = result {
+			// don't leave a torn or failed record before the next ones:
+			// caller sees the error, so recovery must not replay it
+			let _ = self.log.set_len((WAL_HEADER_LEN + self.records * record.len()) as u64);
+			return Err(error);
+		}
+		self.records += 1;
+		Ok(())
+	}
+	
+	/// *English*: Checkpoints, if log became too long
+	///
+	/// *Russian*: Делает снимок, если журнал стал слишком длинным
+	
+	fn after_append(&mut self) -> io::Result<()> {
+		match self.auto_checkpoint {
+			Some(limit) if self.records >= limit => self.checkpoint(),
+			_ => Ok(()),
+		}
+	}
+	
+	/// *English*: Method **insert()** logs and adds value like **BinaryTree::insert()**.
+	/// Nothing is logged, if policy rejects value. Returns *true*, if tree was changed.
+	///
+	/// *Russian*: Метод **insert()** записывает в журнал и добавляет значение как
+	/// **BinaryTree::insert()**. Если политика отвергает значение, ничего не записывается.
+	/// Возвращает *true*, если дерево изменилось.
+	
+	pub fn insert(&mut self, val: &T) -> io::Result<bool> {
+		if self.tree.policy() == DuplicatePolicy::Reject && self.tree.contains(val) {
+			return Ok(false);
+		}
+		
+		self.append(OP_INSERT, Some(val))?;
+		let changed = self.tree.insert(val);
+		self.after_append()?;
+		Ok(changed)
+	}
+	
+	/// *English*: Method **remove()** logs and removes one occurrence of value.
+	/// Returns *false* (and logs nothing), if there is no such value.
+	///
+	/// *Russian*: Метод **remove()** записывает в журнал и удаляет одно вхождение значения.
+	/// Возвращает *false* (и ничего не записывает), если такого значения нет.
+	
+	pub fn remove(&mut self, val: &T) -> io::Result<bool> {
+		if !self.tree.contains(val) {
+			return Ok(false);
+		}
+		
+		self.append(OP_REMOVE, Some(val))?;
+		self.tree.remove(val);
+		self.after_append()?;
+		Ok(true)
+	}
+	
+	/// *English*: Method **clear()** logs and removes all elements
+	///
+	/// *Russian*: Метод **clear()** записывает в журнал и удаляет все элементы
+	
+	pub fn clear(&mut self) -> io::Result<()> {
+		self.append(OP_CLEAR, None)?;
+		self.tree.clear();
+		self.after_append()
+	}
+	
+	/// *English*: Method **checkpoint()** writes snapshot of tree and starts empty log.
+	/// Both files are replaced atomically: crash at any moment leaves either old
+	/// snapshot with its log or new snapshot (old log is ignored then).
+	///
+	/// *Russian*: Метод **checkpoint()** записывает снимок дерева и начинает пустой журнал.
+	/// Оба файла заменяются атомарно: сбой в любой момент оставляет либо старый
+	/// снимок с его журналом, либо новый снимок (старый журнал тогда игнорируется).
+	
+	pub fn checkpoint(&mut self) -> io::Result<()> {
+		let mut snapshot = Vec::new();
+		self.tree.write_to(&mut snapshot)?;
+		replace_file(&self.dir, SNAPSHOT_TMP, SNAPSHOT, &snapshot)?;
+		
+		let mut base = [0; 8];
+		base.copy_from_slice(&snapshot[snapshot.len() - 8..]);
+		replace_file(&self.dir, WAL_TMP, WAL, &DurableTree::<T>::header(u64::from_le_bytes(base)))?;
+		
+		self.log = OpenOptions::new().append(true).open(self.dir.join(WAL))?;
+		self.records = 0;
+		Ok(())
+	}
+	
+	/// *English*: Method **sync()** waits until all records reach the disk.
+	/// It's needed only if **set_sync(false)** was called.
+	///
+	/// *Russian*: Метод **sync()** ждёт, пока все записи достигнут диска.
+	/// Нужен, только если был вызван **set_sync(false)**.
+	
+	#[inline]
+	pub fn sync(&mut self) -> io::Result<()> {
+		self.log.sync_data()
+	}
+}
+
+/// *English*: Read access to the tree. Mutations must go through **DurableTree**,
+/// so there is no **DerefMut**.
+///
+/// *Russian*: Доступ к дереву на чтение. Изменения должны идти через **DurableTree**,
+/// поэтому **DerefMut** нет.
+
+impl<T> Deref for DurableTree<T>
+	where T: Copy + Clone + Ord + Eq + FixedKey
+{
+	type Target = BinaryTree<T>;
+	
+	#[inline]
+	fn deref(&self) -> &BinaryTree<T> {
+		&self.tree
+	}
+}
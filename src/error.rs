@@ -0,0 +1,207 @@
+use core::fmt::{self, Display, Formatter};
+use crate::tree::DuplicatePolicy;
+
+/// **Errors of the crate**
+/// ---------------------------------------
+
+/// *English*: Errors of fallible (*try_\**) methods. They are returned
+/// instead of panics, so you can handle them by yourself.
+///
+/// *Russian*: Ошибки методов, которые могут не сработать (*try_\**).
+/// Они возвращаются вместо паники, так что их можно обработать самостоятельно.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+	/// *English*: Tree is empty, but operation needs at least one element
+	///
+	/// *Russian*: Дерево пусто, но операции нужен хотя бы один элемент
+	Empty,
+	
+	/// *English*: Value isn't in the tree
+	///
+	/// *Russian*: Значения нет в дереве
+	NotFound,
+	
+	/// *English*: Tree's capacity limit is reached
+	///
+	/// *Russian*: Достигнут предел ёмкости дерева
+	Full,
+}
+
+/// *English*: Human-readable description of the error
+///
+/// *Russian*: Понятное человеку описание ошибки
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match *self {
+			Error::Empty => write!(f, "tree is empty"),
+			Error::NotFound => write!(f, "value isn't in the tree"),
+			Error::Full => write!(f, "tree's capacity limit is reached"),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// *English*: Broken structure of the tree, which was found by **validate()**.
+/// If you see it, it's a bug in the crate (or memory was corrupted).
+///
+/// *Russian*: Нарушенная структура дерева, найденная методом **validate()**.
+/// Если вы её видите, значит в крейте баг (или память была повреждена).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvariantError {
+	/// *English*: Key at in-order position *index* is less than the previous one,
+	/// so some left branch has a greater key or some right branch has a less key.
+	///
+	/// *Russian*: Ключ на позиции *index* в обходе меньше предыдущего,
+	/// т.е. в какой-то левой ветви ключ больше, или в какой-то правой - меньше.
+	Order { index: usize },
+	
+	/// *English*: Key at in-order position *index* is equal to the previous one,
+	/// but tree's policy doesn't allow duplicates.
+	///
+	/// *Russian*: Ключ на позиции *index* в обходе равен предыдущему,
+	/// но политика дерева не допускает повторов.
+	Duplicate { index: usize },
+	
+	/// *English*: Tree's *size* field isn't equal to the real number of nodes.
+	///
+	/// *Russian*: Поле *size* дерева не равно реальному количеству узлов.
+	Size { expected: usize, actual: usize },
+	
+	/// *English*: Node of multiway tree at *depth* has too few or too many keys,
+	/// wrong number of children or isn't at the same level as other leaves.
+	///
+	/// *Russian*: Узел многопутевого дерева на глубине *depth* имеет слишком мало или
+	/// слишком много ключей, неверное число потомков или лежит не на одном уровне с другими листьями.
+	Shape { depth: usize },
+}
+
+/// *English*: Human-readable description of the broken invariant
+///
+/// *Russian*: Понятное человеку описание нарушенного инварианта
+
+impl Display for InvariantError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match *self {
+			InvariantError::Order { index } =>
+				write!(f, "key at in-order position {} is less than the previous one", index),
+			InvariantError::Duplicate { index } =>
+				write!(f, "key at in-order position {} is a forbidden duplicate", index),
+			InvariantError::Size { expected, actual } =>
+				write!(f, "tree's size is {}, but it has {} nodes", expected, actual),
+			InvariantError::Shape { depth } =>
+				write!(f, "node at depth {} breaks the shape of multiway tree", depth),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvariantError {}
+
+/// *English*: Errors of reading tree from its binary format (check **codec.rs**).
+/// Any damage of data is reported by one of them, reading never panics.
+/// The set of variants depends on features (`Io` exists only with `std`), so match it with a wildcard arm.
+///
+/// *Russian*: Ошибки чтения дерева из двоичного формата (изучите **codec.rs**).
+/// О любом повреждении данных сообщает одна из них, чтение никогда не паникует.
+/// Набор вариантов зависит от возможностей (`Io` есть только с `std`), поэтому сопоставляйте его с веткой `_`.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FormatError {
+	/// *English*: Data doesn't start with the magic bytes of the format
+	///
+	/// *Russian*: Данные не начинаются с магических байт формата
+	Magic,
+	
+	/// *English*: Data was written by incompatible (newer) major version of the format
+	///
+	/// *Russian*: Данные записаны несовместимой (более новой) основной версией формата
+	Version { major: u8 },
+	
+	/// *English*: Data needs features (required flags), which this reader doesn't know
+	///
+	/// *Russian*: Данным нужны возможности (обязательные флаги), которые неизвестны читателю
+	Flags { flags: u16 },
+	
+	/// *English*: Keys in data have other type or width than requested
+	///
+	/// *Russian*: Ключи в данных имеют другой тип или ширину, чем запрошено
+	KeyType { tag: u8, width: u32 },
+	
+	/// *English*: Data ends before the end of the tree
+	///
+	/// *Russian*: Данные заканчиваются раньше конца дерева
+	Truncated,
+	
+	/// *English*: Checksum of data doesn't match the stored one
+	///
+	/// *Russian*: Контрольная сумма данных не совпадает с записанной
+	Checksum,
+	
+	/// *English*: Header or node links are inconsistent
+	///
+	/// *Russian*: Заголовок или связи узлов противоречивы
+	Layout,
+	
+	/// *English*: Decoded tree breaks its invariant (order or policy)
+	///
+	/// *Russian*: Прочитанное дерево нарушает свой инвариант (порядок или политику)
+	Invariant(InvariantError),
+	
+	/// *English*: Stored tree has other duplicate policy than requested
+	///
+	/// *Russian*: Сохранённое дерево имеет другую политику дубликатов, чем запрошено
+	Policy { stored: DuplicatePolicy },
+	
+	/// *English*: Reader or writer failed with this kind of I/O error
+	///
+	/// *Russian*: Чтение или запись завершились с таким видом ошибки ввода-вывода
+	#[cfg(feature = "std")]
+	Io(std::io::ErrorKind),
+}
+
+/// *English*: Human-readable description of the format error
+///
+/// *Russian*: Понятное человеку описание ошибки формата
+
+impl Display for FormatError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match *self {
+			FormatError::Magic => write!(f, "data isn't a serialized tree"),
+			FormatError::Version { major } => write!(f, "unsupported format version {}", major),
+			FormatError::Flags { flags } => write!(f, "unsupported required flags {:#06x}", flags),
+			FormatError::KeyType { tag, width } =>
+				write!(f, "keys have type tag {} and width {}, which don't match requested type", tag, width),
+			FormatError::Truncated => write!(f, "data is truncated"),
+			FormatError::Checksum => write!(f, "checksum mismatch"),
+			FormatError::Layout => write!(f, "header or node links are inconsistent"),
+			FormatError::Invariant(error) => write!(f, "decoded tree is broken: {}", error),
+			FormatError::Policy { stored } =>
+				write!(f, "tree is stored with {:?} policy, which doesn't match requested one", stored),
+			#[cfg(feature = "std")]
+			FormatError::Io(kind) => write!(f, "I/O error: {}", kind),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FormatError {}
+
+/// *English*: Failed read of the whole tree means *Truncated*, other I/O errors are kept
+///
+/// *Russian*: Неудачное чтение всего дерева означает *Truncated*, другие ошибки ввода-вывода сохраняются
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for FormatError {
+	fn from(error: std::io::Error) -> Self {
+		match error.kind() {
+			std::io::ErrorKind::UnexpectedEof => FormatError::Truncated,
+			kind => FormatError::Io(kind),
+		}
+	}
+}
@@ -0,0 +1,520 @@
+use crate::error::Error;
+use core::cmp::Ordering;
+
+/// *English*: Index of absent node
+///
+/// *Russian*: Индекс отсутствующего узла
+
+const NIL: usize = usize::MAX;
+
+/// *English*: **Slot** is a cell of **StaticTree** array.
+/// Free slots have no key and are linked into free list with *left* index.
+///
+/// *Russian*: **Slot** - ячейка массива **StaticTree**.
+/// У свободных ячеек нет ключа, и они связаны в список свободных ячеек индексом *left*.
+
+#[derive(Debug, Clone, Copy)]
+struct Slot<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	key: Option<T>,
+	left: usize,
+	right: usize,
+	parent: usize,
+}
+
+/// *English*: **StaticTree** is a binary search tree, which never allocates.
+/// All *N* nodes are stored in an inline array and linked with indices,
+/// so the tree may live on the stack or in a *static*.
+/// Like **BinaryTree**, equal keys are stored in the *right* branch.
+/// When all *N* slots are used, **insert()** returns *Error::Full*.
+/// Tree isn't balanced, so operations take O(height).
+///
+/// *Russian*: **StaticTree** - двоичное дерево поиска, которое никогда не выделяет память.
+/// Все *N* узлов хранятся во встроенном массиве и связаны индексами,
+/// так что дерево может жить на стеке или в *static*.
+/// Как и в **BinaryTree**, равные ключи хранятся в *правой* ветке.
+/// Когда все *N* ячеек заняты, **insert()** возвращает *Error::Full*.
+/// Дерево не балансируется, так что операции занимают O(высоты).
+///
+/// # Example
+///
+/// ```
+/// use binartree::fixed::StaticTree;
+/// use binartree::error::Error;
+///
+/// let mut tree = StaticTree::<i32, 3>::new();
+/// assert_eq!(tree.insert(&2), Ok(()));
+/// assert_eq!(tree.insert(&1), Ok(()));
+/// assert_eq!(tree.insert(&3), Ok(()));
+/// assert_eq!(tree.insert(&4), Err(Error::Full));
+///
+/// assert_eq!(tree.remove(&2), Ok(()));
+/// assert_eq!(tree.insert(&4), Ok(()));
+/// assert_eq!(tree.iter().collect::<Vec<i32>>(), vec![1, 3, 4]);
+/// ```
+
+#[derive(Debug, Clone, Copy)]
+pub struct StaticTree<T, const N: usize>
+	where T: Copy + Clone + Ord + Eq
+{
+	slots: [Slot<T>; N],
+	top: usize,
+	free: usize,
+	size: usize,
+}
+
+impl<T, const N: usize> Default for StaticTree<T, N>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T, const N: usize> StaticTree<T, N>
+	where T: Copy + Clone + Ord + Eq
+{
+	/// *English*: Method **new()** creates *empty* tree. It's *const*, so it can be used in *static*
+	///
+	/// *Russian*: Метод **new()** создаёт *пустое* дерево. Он *const*, так что его можно использовать в *static*
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::fixed::StaticTree;
+	///
+	/// const TREE: StaticTree<u8, 16> = StaticTree::new();
+	/// assert!(TREE.is_empty());
+	/// assert_eq!(TREE.capacity(), 16);
+	/// ```
+	
+	pub const fn new() -> Self {
+		let mut slots = [Slot { key: None, left: NIL, right: NIL, parent: NIL }; N];
+		let mut i = 1;
+		while i < N {
+			slots[i - 1].left = i;
+			i += 1;
+		}
+		
+		StaticTree {
+			slots,
+			top: NIL,
+			free: if N > 0 { 0 } else { NIL },
+			size: 0,
+		}
+	}
+	
+	/// *English*: Method **len()** returns number of elements
+	///
+	/// *Russian*: Метод **len()** возвращает количество элементов
+	
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.size
+	}
+	
+	/// *English*: Method **is_empty()** checks if tree is empty
+	///
+	/// *Russian*: Метод **is_empty()** проверяет, пусто ли дерево
+	
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.size == 0
+	}
+	
+	/// *English*: Method **capacity()** returns max number of elements, which is *N*
+	///
+	/// *Russian*: Метод **capacity()** возвращает наибольшее количество элементов, то есть *N*
+	
+	#[inline]
+	pub const fn capacity(&self) -> usize {
+		N
+	}
+	
+	/// *English*: Key of used slot
+	///
+	/// *Russian*: Ключ занятой ячейки
+	
+	#[inline]
+	fn key(&self, index: usize) -> &T {
+		match self.slots[index].key {
+			Some(ref key) => key,
+			None => unreachable!("Free slot in tree"),
+		}
+	}
+	
+	/// *English*: Index of min node in subtree *index*
+	///
+	/// *Russian*: Индекс наименьшего узла в поддереве *index*
+	
+	fn min_of(&self, mut index: usize) -> usize {
+		while index != NIL && self.slots[index].left != NIL {
+			index = self.slots[index].left;
+		}
+		index
+	}
+	
+	/// *English*: Index of max node in subtree *index*
+	///
+	/// *Russian*: Индекс наибольшего узла в поддереве *index*
+	
+	fn max_of(&self, mut index: usize) -> usize {
+		while index != NIL && self.slots[index].right != NIL {
+			index = self.slots[index].right;
+		}
+		index
+	}
+	
+	/// *English*: Index of next node in *in-order* walk or *NIL*
+	///
+	/// *Russian*: Индекс следующего узла при *центрированном* обходе или *NIL*
+	
+	fn next_of(&self, mut index: usize) -> usize {
+		if self.slots[index].right != NIL {
+			return self.min_of(self.slots[index].right);
+		}
+		
+		let mut parent = self.slots[index].parent;
+		while parent != NIL && self.slots[parent].right == index {
+			index = parent;
+			parent = self.slots[parent].parent;
+		}
+		parent
+	}
+	
+	/// *English*: Index of previous node in *in-order* walk or *NIL*
+	///
+	/// *Russian*: Индекс предыдущего узла при *центрированном* обходе или *NIL*
+	
+	fn prev_of(&self, mut index: usize) -> usize {
+		if self.slots[index].left != NIL {
+			return self.max_of(self.slots[index].left);
+		}
+		
+		let mut parent = self.slots[index].parent;
+		while parent != NIL && self.slots[parent].left == index {
+			index = parent;
+			parent = self.slots[parent].parent;
+		}
+		parent
+	}
+	
+	/// *English*: Index of any node with key *val* or *NIL*
+	///
+	/// *Russian*: Индекс любого узла с ключом *val* или *NIL*
+	
+	fn find(&self, val: &T) -> usize {
+		let mut cur = self.top;
+		while cur != NIL {
+			match val.cmp(self.key(cur)) {
+				Ordering::Less => cur = self.slots[cur].left,
+				Ordering::Greater => cur = self.slots[cur].right,
+				Ordering::Equal => break,
+			}
+		}
+		cur
+	}
+	
+	/// *English*: Puts subtree *new* in place of subtree *old*
+	///
+	/// *Russian*: Ставит поддерево *new* на место поддерева *old*
+	
+	fn transplant(&mut self, old: usize, new: usize) {
+		let parent = self.slots[old].parent;
+		if parent == NIL {
+			self.top = new;
+		} else if self.slots[parent].left == old {
+			self.slots[parent].left = new;
+		} else {
+			self.slots[parent].right = new;
+		}
+		
+		if new != NIL {
+			self.slots[new].parent = parent;
+		}
+	}
+	
+	/// *English*: Method **insert()** adds value to tree
+	/// or returns *Error::Full*, if all slots are used
+	///
+	/// *Russian*: Метод **insert()** добавляет значение в дерево
+	/// или возвращает *Error::Full*, если все ячейки заняты
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::fixed::StaticTree;
+	/// use binartree::error::Error;
+	///
+	/// let mut tree = StaticTree::<i32, 2>::new();
+	/// assert_eq!(tree.insert(&1), Ok(()));
+	/// assert_eq!(tree.insert(&1), Ok(()));
+	/// assert_eq!(tree.insert(&1), Err(Error::Full));
+	/// assert_eq!(tree.len(), 2);
+	/// ```
+	
+	pub fn insert(&mut self, val: &T) -> Result<(), Error> {
+		if self.free == NIL {
+			return Err(Error::Full);
+		}
+		
+		let mut parent = NIL;
+		let mut cur = self.top;
+		while cur != NIL {
+			parent = cur;
+			cur = if *val < *self.key(cur) {
+				self.slots[cur].left
+			} else {
+				self.slots[cur].right
+			};
+		}
+		
+		let index = self.free;
+		self.free = self.slots[index].left;
+		self.slots[index] = Slot { key: Some(*val), left: NIL, right: NIL, parent };
+		
+		if parent == NIL {
+			self.top = index;
+		} else if *val < *self.key(parent) {
+			self.slots[parent].left = index;
+		} else {
+			self.slots[parent].right = index;
+		}
+		
+		self.size += 1;
+		Ok(())
+	}
+	
+	/// *English*: Method **remove()** removes one occurrence of value
+	/// or returns *Error::NotFound*, if there is no such value.
+	/// Slot becomes free and can be used again.
+	///
+	/// *Russian*: Метод **remove()** удаляет одно вхождение значения
+	/// или возвращает *Error::NotFound*, если такого значения нет.
+	/// Ячейка освобождается и может быть использована снова.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::fixed::StaticTree;
+	/// use binartree::error::Error;
+	///
+	/// let mut tree = StaticTree::<i32, 4>::new();
+	/// tree.insert(&1).unwrap();
+	/// tree.insert(&1).unwrap();
+	///
+	/// assert_eq!(tree.remove(&1), Ok(()));
+	/// assert_eq!(tree.remove(&1), Ok(()));
+	/// assert_eq!(tree.remove(&1), Err(Error::NotFound));
+	/// ```
+	
+	pub fn remove(&mut self, val: &T) -> Result<(), Error> {
+		let index = self.find(val);
+		if index == NIL {
+			return Err(Error::NotFound);
+		}
+		
+		let Slot { left, right, .. } = self.slots[index];
+		if left == NIL {
+			self.transplant(index, right);
+		} else if right == NIL {
+			self.transplant(index, left);
+		} else {
+			let next = self.min_of(right);
+			if self.slots[next].parent != index {
+				self.transplant(next, self.slots[next].right);
+				self.slots[next].right = right;
+				self.slots[right].parent = next;
+			}
+			
+			self.transplant(index, next);
+			self.slots[next].left = left;
+			self.slots[left].parent = next;
+		}
+		
+		self.slots[index] = Slot { key: None, left: self.free, right: NIL, parent: NIL };
+		self.free = index;
+		self.size -= 1;
+		Ok(())
+	}
+	
+	/// *English*: Method **contains()** checks if value is in tree
+	///
+	/// *Russian*: Метод **contains()** проверяет, есть ли значение в дереве
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::fixed::StaticTree;
+	///
+	/// let mut tree = StaticTree::<i32, 4>::new();
+	/// tree.insert(&5).unwrap();
+	///
+	/// assert!(tree.contains(&5));
+	/// assert!(!tree.contains(&6));
+	/// ```
+	
+	#[inline]
+	pub fn contains(&self, val: &T) -> bool {
+		self.find(val) != NIL
+	}
+	
+	/// *English*: Method **first()** returns min value or *Error::Empty*, if tree is empty
+	///
+	/// *Russian*: Метод **first()** возвращает наименьшее значение
+	/// или *Error::Empty*, если дерево пустое
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::fixed::StaticTree;
+	/// use binartree::error::Error;
+	///
+	/// let mut tree = StaticTree::<i32, 4>::new();
+	/// assert_eq!(tree.first(), Err(Error::Empty));
+	///
+	/// tree.insert(&2).unwrap();
+	/// tree.insert(&1).unwrap();
+	/// assert_eq!(tree.first(), Ok(&1));
+	/// ```
+	
+	pub fn first(&self) -> Result<&T, Error> {
+		match self.min_of(self.top) {
+			NIL => Err(Error::Empty),
+			index => Ok(self.key(index)),
+		}
+	}
+	
+	/// *English*: Method **last()** returns max value or *Error::Empty*, if tree is empty
+	///
+	/// *Russian*: Метод **last()** возвращает наибольшее значение
+	/// или *Error::Empty*, если дерево пустое
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::fixed::StaticTree;
+	/// use binartree::error::Error;
+	///
+	/// let mut tree = StaticTree::<i32, 4>::new();
+	/// assert_eq!(tree.last(), Err(Error::Empty));
+	///
+	/// tree.insert(&1).unwrap();
+	/// tree.insert(&2).unwrap();
+	/// assert_eq!(tree.last(), Ok(&2));
+	/// ```
+	
+	pub fn last(&self) -> Result<&T, Error> {
+		match self.max_of(self.top) {
+			NIL => Err(Error::Empty),
+			index => Ok(self.key(index)),
+		}
+	}
+	
+	/// *English*: Method **clear()** removes all elements
+	///
+	/// *Russian*: Метод **clear()** удаляет все элементы
+	
+	#[inline]
+	pub fn clear(&mut self) {
+		*self = Self::new();
+	}
+	
+	/// *English*: Method **iter()** returns *sorted* iterator, which doesn't allocate:
+	/// it moves with parent links
+	///
+	/// *Russian*: Метод **iter()** возвращает *отсортированный* итератор, который
+	/// не выделяет память: он перемещается по ссылкам на родителей
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::fixed::StaticTree;
+	///
+	/// let mut tree = StaticTree::<i32, 8>::new();
+	/// for i in [3, 1, 4, 1, 5] {
+	///     tree.insert(&i).unwrap();
+	/// }
+	///
+	/// assert_eq!(tree.iter().collect::<Vec<i32>>(), vec![1, 1, 3, 4, 5]);
+	/// assert_eq!(tree.iter().rev().collect::<Vec<i32>>(), vec![5, 4, 3, 1, 1]);
+	/// ```
+	
+	#[inline]
+	pub fn iter(&self) -> Iter<'_, T, N> {
+		Iter {
+			tree: self,
+			front: self.min_of(self.top),
+			back: self.max_of(self.top),
+			len: self.size,
+		}
+	}
+}
+
+/// *English*: **Iter** is a *sorted* iterator over **StaticTree**
+///
+/// *Russian*: **Iter** - *отсортированный* итератор по **StaticTree**
+
+#[derive(Debug, Clone)]
+pub struct Iter<'a, T, const N: usize>
+	where T: Copy + Clone + Ord + Eq
+{
+	tree: &'a StaticTree<T, N>,
+	front: usize,
+	back: usize,
+	len: usize,
+}
+
+impl<'a, T, const N: usize> Iterator for Iter<'a, T, N>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Item = T;
+	
+	fn next(&mut self) -> Option<T> {
+		if self.len == 0 {
+			return None;
+		}
+		
+		let key = *self.tree.key(self.front);
+		self.front = self.tree.next_of(self.front);
+		self.len -= 1;
+		Some(key)
+	}
+	
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.len, Some(self.len))
+	}
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for Iter<'a, T, N>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn next_back(&mut self) -> Option<T> {
+		if self.len == 0 {
+			return None;
+		}
+		
+		let key = *self.tree.key(self.back);
+		self.back = self.tree.prev_of(self.back);
+		self.len -= 1;
+		Some(key)
+	}
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for Iter<'a, T, N>
+	where T: Copy + Clone + Ord + Eq
+{}
+
+impl<'a, T, const N: usize> IntoIterator for &'a StaticTree<T, N>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Item = T;
+	type IntoIter = Iter<'a, T, N>;
+	
+	#[inline]
+	fn into_iter(self) -> Iter<'a, T, N> {
+		self.iter()
+	}
+}
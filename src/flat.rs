@@ -0,0 +1,1609 @@
+use crate::node::Node;
+use crate::iter::TreeIter;
+use crate::seq::SortedSeq;
+use crate::tree::{BinaryTree, DuplicatePolicy, filter_present, set_result};
+use crate::error::{Error, InvariantError};
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::iter::{Copied, FromIterator};
+use core::ops::{BitAnd, BitOr, BitXor, Bound, RangeBounds};
+use core::slice;
+
+/// *English*: Iterator over keys of **FlatTree**. It yields keys *by value*
+/// in ascending order, like **TreeIter**, but doesn't copy the whole tree.
+///
+/// *Russian*: Итератор по ключам **FlatTree**. Возвращает ключи *по значению*
+/// по возрастанию, как **TreeIter**, но не копирует всё дерево.
+
+pub type Iter<'a, T> = Copied<slice::Iter<'a, T>>;
+
+/// *English*: Default size, after which **AdaptiveTree** switches to nodes
+///
+/// *Russian*: Размер по-умолчанию, после которого **AdaptiveTree** переходит на узлы
+
+pub const ADAPTIVE_THRESHOLD: usize = 64;
+
+/// *English*: Balance factor of node tree inside **AdaptiveTree**
+///
+/// *Russian*: Коэффициент балансировки дерева из узлов внутри **AdaptiveTree**
+
+const ADAPTIVE_BALANCE_FACTOR: f64 = 2.0;
+
+/// **Flat Tree**
+/// --------------------
+///
+/// *English*: **FlatTree** keeps keys in one *sorted vector* and searches them
+/// with binary search. It has the same API as **BinaryTree** (policies, limits,
+/// bounds, set algebra, iterators), but no pointers: for sets of a few dozen
+/// elements it's much faster, because all keys lie in one cache-friendly block.
+/// Insertion and removal shift the tail of vector, so they take O(n),
+/// which is cheap for small sets. Use **AdaptiveTree**, if size isn't known.
+///
+/// *Russian*: **FlatTree** хранит ключи в одном *отсортированном векторе* и ищет их
+/// бинарным поиском. У него такой же API, как у **BinaryTree** (политики, пределы,
+/// границы, операции над множествами, итераторы), но нет указателей: для множеств
+/// из нескольких десятков элементов он гораздо быстрее, потому что все ключи лежат
+/// в одном блоке памяти. Вставка и удаление сдвигают хвост вектора, так что занимают O(n),
+/// что дёшево для маленьких множеств. Используйте **AdaptiveTree**, если размер неизвестен.
+///
+/// # Example
+///
+/// ```
+/// use binartree::flat::FlatTree;
+/// use std::iter::FromIterator;
+///
+/// let mut tree = FlatTree::from_iter(vec![5, 1, 4]);
+/// tree.insert(&2);
+/// tree.remove(&4);
+///
+/// assert_eq!(tree.to_vec(), vec![1, 2, 5]);
+/// assert_eq!(tree.as_slice(), &[1, 2, 5]);
+/// assert!(tree.contains(&5));
+/// ```
+
+#[derive(Debug, Clone)]
+pub struct FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	keys: Vec<T>,
+	limit: Option<usize>,
+	policy: DuplicatePolicy,
+}
+
+/// *English*: **Default** flat tree is *empty*
+///
+/// *Russian*: По-умолчанию плоское дерево *пусто*
+
+impl<T> Default for FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn default() -> Self {
+		FlatTree {
+			keys: Vec::new(),
+			limit: None,
+			policy: DuplicatePolicy::KeepAll,
+		}
+	}
+}
+
+/// *English*: Flat trees are *equal*, if they have the same sorted elements.
+/// Limit and policy aren't compared.
+///
+/// *Russian*: Плоские деревья *равны*, если у них одинаковые отсортированные элементы.
+/// Предел и политика не сравниваются.
+
+impl<T> PartialEq for FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn eq(&self, other: &Self) -> bool {
+		self.keys == other.keys
+	}
+}
+
+impl<T> Eq for FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{}
+
+/// *English*: Hash is built from size and sorted elements, like hash of **BinaryTree**,
+/// so flat tree and node tree with the same elements have equal hashes.
+///
+/// *Russian*: Хэш строится из размера и отсортированных элементов, как хэш **BinaryTree**,
+/// так что у плоского дерева и дерева из узлов с одинаковыми элементами равные хэши.
+
+impl<T> Hash for FlatTree<T>
+	where T: Copy + Clone + Ord + Eq + Hash
+{
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.keys.len().hash(state);
+		for elem in self.keys.iter() {
+			elem.hash(state);
+		}
+	}
+}
+
+/// *English*: Flat trees are compared *lexicographically* by their sorted elements
+///
+/// *Russian*: Плоские деревья сравниваются *лексикографически* по их отсортированным элементам
+
+impl<T> PartialOrd for FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<T> Ord for FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.keys.cmp(&other.keys)
+	}
+}
+
+impl<T> FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	/// *English*: Method **new()** creates *empty* flat tree
+	///
+	/// *Russian*: Метод **new()** создаёт *пустое* плоское дерево
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	///
+	/// let tree = FlatTree::<i32>::new();
+	/// assert!(tree.is_empty());
+	/// ```
+	
+	#[inline]
+	pub fn new() -> Self {
+		FlatTree::default()
+	}
+	
+	/// *English*: Method **with_capacity()** creates *empty* flat tree,
+	/// which can hold *capacity* elements without reallocation
+	///
+	/// *Russian*: Метод **with_capacity()** создаёт *пустое* плоское дерево,
+	/// которое может хранить *capacity* элементов без перевыделения памяти
+	
+	#[inline]
+	pub fn with_capacity(capacity: usize) -> Self {
+		let mut tree = FlatTree::new();
+		tree.keys.reserve(capacity);
+		tree
+	}
+	
+	/// *English*: Method **with_limit()** creates *empty* flat tree,
+	/// which can't hold more than *limit* elements (like **BinaryTree::with_limit()**)
+	///
+	/// *Russian*: Метод **with_limit()** создаёт *пустое* плоское дерево,
+	/// которое не может хранить больше *limit* элементов (как **BinaryTree::with_limit()**)
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	/// use binartree::error::Error;
+	///
+	/// let mut tree = FlatTree::with_limit(1);
+	/// assert_eq!(tree.try_insert(&1), Ok(true));
+	/// assert_eq!(tree.try_insert(&2), Err(Error::Full));
+	/// ```
+	
+	#[inline]
+	pub fn with_limit(limit: usize) -> Self {
+		let mut tree = FlatTree::new();
+		tree.limit = Some(limit);
+		tree
+	}
+	
+	/// *English*: Method **with_policy()** creates *empty* flat tree
+	/// with given policy for duplicates (like **BinaryTree::with_policy()**)
+	///
+	/// *Russian*: Метод **with_policy()** создаёт *пустое* плоское дерево
+	/// с данной политикой для повторов (как **BinaryTree::with_policy()**)
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	/// use binartree::tree::DuplicatePolicy;
+	///
+	/// let mut tree = FlatTree::with_policy(DuplicatePolicy::Reject);
+	/// assert!(tree.insert(&1));
+	/// assert!(!tree.insert(&1));
+	/// assert_eq!(tree.len(), 1);
+	/// ```
+	
+	#[inline]
+	pub fn with_policy(policy: DuplicatePolicy) -> Self {
+		let mut tree = FlatTree::new();
+		tree.policy = policy;
+		tree
+	}
+	
+	/// *English*: Method **policy()** returns policy for duplicates
+	///
+	/// *Russian*: Метод **policy()** возвращает политику для повторов
+	
+	#[inline]
+	pub fn policy(&self) -> DuplicatePolicy {
+		self.policy
+	}
+	
+	/// *English*: Method **limit()** returns max number of elements or *None*, if tree is unbounded
+	///
+	/// *Russian*: Метод **limit()** возвращает наибольшее количество элементов
+	/// или *None*, если дерево неограничено
+	
+	#[inline]
+	pub fn limit(&self) -> Option<usize> {
+		self.limit
+	}
+	
+	/// *English*: Method **len()** returns number of elements
+	///
+	/// *Russian*: Метод **len()** возвращает количество элементов
+	
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.keys.len()
+	}
+	
+	/// *English*: Method **is_empty()** checks that tree has no elements
+	///
+	/// *Russian*: Метод **is_empty()** проверяет, что в дереве нет элементов
+	
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.keys.is_empty()
+	}
+	
+	/// *English*: Method **as_slice()** returns sorted slice of all elements without copying
+	///
+	/// *Russian*: Метод **as_slice()** возвращает отсортированный срез всех элементов без копирования
+	
+	#[inline]
+	pub fn as_slice(&self) -> &[T] {
+		&self.keys
+	}
+	
+	/// *English*: Method **to_vec()** returns sorted vector of elements
+	///
+	/// *Russian*: Метод **to_vec()** возвращает отсортированный вектор элементов
+	
+	#[inline]
+	pub fn to_vec(&self) -> Vec<T> {
+		self.keys.clone()
+	}
+	
+	/// *English*: Method **to_deque()** returns sorted deque of elements
+	///
+	/// *Russian*: Метод **to_deque()** возвращает отсортированный дек элементов
+	
+	#[inline]
+	pub fn to_deque(&self) -> VecDeque<T> {
+		self.keys.iter().copied().collect()
+	}
+	
+	/// *English*: Index of the first key, which isn't less than value
+	///
+	/// *Russian*: Индекс первого ключа, который не меньше значения
+	
+	#[inline]
+	fn lower_index<Q>(&self, val: &Q) -> usize
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.keys.partition_point(|key| key.borrow() < val)
+	}
+	
+	/// *English*: Index of the first key, which is greater than value
+	///
+	/// *Russian*: Индекс первого ключа, который больше значения
+	
+	#[inline]
+	fn upper_index<Q>(&self, val: &Q) -> usize
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.keys.partition_point(|key| key.borrow() <= val)
+	}
+	
+	/// *English*: Method **insert()** adds value like **BinaryTree::insert()**:
+	/// equal values are placed after the old ones, policy decides what to do
+	/// with duplicates and *panic* is called, if bounded tree is full.
+	/// Returns *true*, if tree was changed.
+	///
+	/// *Russian*: Метод **insert()** добавляет значение как **BinaryTree::insert()**:
+	/// равные значения ставятся после старых, политика решает, что делать
+	/// с повторами, а если ограниченное дерево заполнено, то вызывается *паника*.
+	/// Возвращает *true*, если дерево изменилось.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	///
+	/// let mut tree = FlatTree::new();
+	/// tree.insert(&3);
+	/// tree.insert(&1);
+	/// tree.insert(&3);
+	///
+	/// assert_eq!(tree.to_vec(), vec![1, 3, 3]);
+	/// ```
+	
+	#[inline]
+	pub fn insert(&mut self, val: &T) -> bool {
+		match self.try_insert(val) {
+			Ok(changed) => changed,
+			Err(error) => panic!("{}", error),
+		}
+	}
+	
+	/// *English*: Method **try_insert()** adds value like **insert()**,
+	/// but returns *Error::Full* instead of panic, if bounded tree is full.
+	///
+	/// *Russian*: Метод **try_insert()** добавляет значение как **insert()**,
+	/// но вместо паники возвращает *Error::Full*, если ограниченное дерево заполнено.
+	
+	pub fn try_insert(&mut self, val: &T) -> Result<bool, Error> {
+		let index = self.upper_index(val);
+		let found = index > 0 && self.keys[index - 1] == *val;
+		
+		match self.policy {
+			DuplicatePolicy::KeepAll => (),
+			DuplicatePolicy::Reject => if found {
+				return Ok(false);
+			},
+			DuplicatePolicy::Replace => if found {
+				self.keys[index - 1] = *val;
+				return Ok(true);
+			},
+		}
+		
+		if matches!(self.limit, Some(limit) if self.keys.len() >= limit) {
+			return Err(Error::Full);
+		}
+		
+		self.keys.insert(index, *val);
+		Ok(true)
+	}
+	
+	/// *English*: Method **contains()** checks that value is in the tree.
+	/// Value may be any borrowed form of key.
+	///
+	/// *Russian*: Метод **contains()** проверяет наличие значения в дереве.
+	/// Значение может быть любой заимствованной формой ключа.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	///
+	/// let names = FlatTree::from(vec!["Alice", "Bob"]);
+	/// let name = String::from("Bob");
+	///
+	/// assert!(names.contains(name.as_str()));
+	/// assert!(!names.contains("Eve"));
+	/// ```
+	
+	#[inline]
+	pub fn contains<Q>(&self, val: &Q) -> bool
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.get(val).is_some()
+	}
+	
+	/// *English*: Method **get()** returns key, which is equal to value, or *None*
+	///
+	/// *Russian*: Метод **get()** возвращает ключ, равный значению, или *None*
+	
+	#[inline]
+	pub fn get<Q>(&self, val: &Q) -> Option<&T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.keys.get(self.lower_index(val)).filter(|key| (*key).borrow() == val)
+	}
+	
+	/// *English*: Method **range()** returns *sorted* iterator over keys from range.
+	/// Takes O(log n) to find the range.
+	///
+	/// *Russian*: Метод **range()** возвращает *отсортированный* итератор по ключам из диапазона.
+	/// Поиск диапазона занимает O(log n).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let tree = FlatTree::from_iter(vec![5, 1, 4, 2, 3, 3]);
+	///
+	/// assert_eq!(tree.range(2..4).collect::<Vec<i32>>(), vec![2, 3, 3]);
+	/// assert_eq!(tree.range(4..).collect::<Vec<i32>>(), vec![4, 5]);
+	/// ```
+	
+	pub fn range<Q, R>(&self, range: R) -> Iter<'_, T>
+		where T: Borrow<Q>, Q: Ord + ?Sized, R: RangeBounds<Q>
+	{
+		let start = match range.start_bound() {
+			Bound::Included(start) => self.lower_index(start),
+			Bound::Excluded(start) => self.upper_index(start),
+			Bound::Unbounded => 0,
+		};
+		let end = match range.end_bound() {
+			Bound::Included(end) => self.upper_index(end),
+			Bound::Excluded(end) => self.lower_index(end),
+			Bound::Unbounded => self.keys.len(),
+		};
+		
+		self.keys[start..end.max(start)].iter().copied()
+	}
+	
+	/// *English*: Method **range_by()** returns *sorted* iterator over keys,
+	/// for which comparator returns *Equal* (like **BinaryTree::range_by()**)
+	///
+	/// *Russian*: Метод **range_by()** возвращает *отсортированный* итератор по ключам,
+	/// для которых компаратор возвращает *Equal* (как **BinaryTree::range_by()**)
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let events = FlatTree::from_iter(vec![(10, 1), (20, 2), (20, 3), (30, 4)]);
+	///
+	/// let at_20 = events.range_by(|&(time, _)| time.cmp(&20));
+	/// assert_eq!(at_20.collect::<Vec<(i32, i32)>>(), vec![(20, 2), (20, 3)]);
+	/// ```
+	
+	pub fn range_by<F>(&self, mut cmp: F) -> Iter<'_, T>
+		where F: FnMut(&T) -> Ordering
+	{
+		let start = self.keys.partition_point(|key| cmp(key) == Ordering::Less);
+		let end = start + self.keys[start..].partition_point(|key| cmp(key) != Ordering::Greater);
+		self.keys[start..end].iter().copied()
+	}
+	
+	/// *English*: Method **find_by()** searches key with comparator
+	/// (like **BinaryTree::find_by()**). Returns *None*, if there is no such key.
+	///
+	/// *Russian*: Метод **find_by()** ищет ключ с компаратором
+	/// (как **BinaryTree::find_by()**). Возвращает *None*, если такого ключа нет.
+	
+	#[inline]
+	pub fn find_by<F>(&self, cmp: F) -> Option<&T>
+		where F: FnMut(&T) -> Ordering
+	{
+		self.keys.binary_search_by(cmp).ok().map(|index| &self.keys[index])
+	}
+	
+	/// *English*: Method **partition_point()** returns the smallest key, for which
+	/// predicate is *false*, or *None*, if it's *true* for all keys
+	/// (like **BinaryTree::partition_point()**)
+	///
+	/// *Russian*: Метод **partition_point()** возвращает наименьший ключ, для которого
+	/// предикат *ложен*, или *None*, если он *истинен* для всех ключей
+	/// (как **BinaryTree::partition_point()**)
+	
+	#[inline]
+	pub fn partition_point<P>(&self, pred: P) -> Option<&T>
+		where P: FnMut(&T) -> bool
+	{
+		self.keys.get(self.keys.partition_point(pred))
+	}
+	
+	/// *English*: Method **lower_bound()** returns the smallest key *above* the bound
+	/// or *None*, if there is no such key (like **BinaryTree::lower_bound()**)
+	///
+	/// *Russian*: Метод **lower_bound()** возвращает наименьший ключ *выше* границы
+	/// или *None*, если такого ключа нет (как **BinaryTree::lower_bound()**)
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	/// use std::iter::FromIterator;
+	/// use std::ops::Bound;
+	///
+	/// let tree = FlatTree::from_iter(vec![10, 20, 30]);
+	///
+	/// assert_eq!(tree.lower_bound(Bound::Excluded(&20)), Some(&30));
+	/// assert_eq!(tree.upper_bound(Bound::Excluded(&20)), Some(&10));
+	/// ```
+	
+	pub fn lower_bound<Q>(&self, bound: Bound<&Q>) -> Option<&T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		let index = match bound {
+			Bound::Included(val) => self.lower_index(val),
+			Bound::Excluded(val) => self.upper_index(val),
+			Bound::Unbounded => 0,
+		};
+		self.keys.get(index)
+	}
+	
+	/// *English*: Method **upper_bound()** returns the biggest key *below* the bound
+	/// or *None*, if there is no such key (like **BinaryTree::upper_bound()**)
+	///
+	/// *Russian*: Метод **upper_bound()** возвращает наибольший ключ *ниже* границы
+	/// или *None*, если такого ключа нет (как **BinaryTree::upper_bound()**)
+	
+	pub fn upper_bound<Q>(&self, bound: Bound<&Q>) -> Option<&T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		let index = match bound {
+			Bound::Included(val) => self.upper_index(val),
+			Bound::Excluded(val) => self.lower_index(val),
+			Bound::Unbounded => self.keys.len(),
+		};
+		index.checked_sub(1).map(|index| &self.keys[index])
+	}
+	
+	/// *English*: Method **first()** returns *minimum value*.
+	/// If tree is empty, it'll panic.
+	///
+	/// *Russian*: Метод **first()** возвращает *минимальное значение*.
+	/// Если дерево пустое, то будет вызвана *паника*.
+	
+	#[inline]
+	pub fn first(&self) -> &T {
+		match self.try_first() {
+			Ok(key) => key,
+			Err(error) => panic!("{}", error),
+		}
+	}
+	
+	/// *English*: Method **try_first()** returns *minimum value* or *Error::Empty*
+	///
+	/// *Russian*: Метод **try_first()** возвращает *минимальное значение* или *Error::Empty*
+	
+	#[inline]
+	pub fn try_first(&self) -> Result<&T, Error> {
+		self.keys.first().ok_or(Error::Empty)
+	}
+	
+	/// *English*: Method **last()** returns *maximum value*.
+	/// If tree is empty, it'll panic.
+	///
+	/// *Russian*: Метод **last()** возвращает *максимальное значение*.
+	/// Если дерево пустое, то будет вызвана *паника*.
+	
+	#[inline]
+	pub fn last(&self) -> &T {
+		match self.try_last() {
+			Ok(key) => key,
+			Err(error) => panic!("{}", error),
+		}
+	}
+	
+	/// *English*: Method **try_last()** returns *maximum value* or *Error::Empty*
+	///
+	/// *Russian*: Метод **try_last()** возвращает *максимальное значение* или *Error::Empty*
+	
+	#[inline]
+	pub fn try_last(&self) -> Result<&T, Error> {
+		self.keys.last().ok_or(Error::Empty)
+	}
+	
+	/// *English*: Method **iter()** returns *sorted* iterator over keys
+	///
+	/// *Russian*: Метод **iter()** возвращает *отсортированный* итератор по ключам
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	///
+	/// let tree = FlatTree::from(vec![3, 1, 2]);
+	/// assert_eq!(tree.iter().collect::<Vec<i32>>(), vec![1, 2, 3]);
+	/// ```
+	
+	#[inline]
+	pub fn iter(&self) -> Iter<'_, T> {
+		self.keys.iter().copied()
+	}
+	
+	/// *English*: Method **append()** adds all elements of other tree.
+	/// If bounded tree can't take all of them, it'll *panic* before adding anything.
+	///
+	/// *Russian*: Метод **append()** добавляет все элементы другого дерева.
+	/// Если ограниченное дерево не может принять их все, то до добавления будет вызвана *паника*.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	///
+	/// let mut tree = FlatTree::from(vec![1, 4]);
+	/// tree.append(&FlatTree::from(vec![2, 3]));
+	/// assert_eq!(tree.to_vec(), vec![1, 2, 3, 4]);
+	/// ```
+	
+	#[inline]
+	pub fn append(&mut self, src: &Self) {
+		if let Err(error) = self.try_append(src) {
+			panic!("{}", error);
+		}
+	}
+	
+	/// *English*: Method **try_append()** adds all elements of other tree like **append()**,
+	/// but if bounded tree can't take all of them, it returns *Error::Full* and *adds nothing*.
+	/// Duplicates, which policy rejects or replaces, don't take place.
+	///
+	/// *Russian*: Метод **try_append()** добавляет все элементы другого дерева как **append()**,
+	/// но если ограниченное дерево не может принять их все, то возвращает *Error::Full*
+	/// и *ничего не добавляет*. Повторы, которые политика отклоняет или заменяет, места не занимают.
+	
+	pub fn try_append(&mut self, src: &Self) -> Result<(), Error> {
+		if matches!(self.limit, Some(limit) if self.keys.len() + self.append_growth(src) > limit) {
+			return Err(Error::Full);
+		}
+		
+		if self.policy == DuplicatePolicy::KeepAll {
+			let mut seq = SortedSeq::from_sorted(self.to_deque());
+			seq.merge(SortedSeq::from_sorted(src.to_deque()));
+			self.keys = seq.to_vec();
+			return Ok(());
+		}
+		
+		for elem in src.keys.iter() {
+			self.insert(elem);
+		}
+		Ok(())
+	}
+	
+	/// *English*: Number of elements, which append of *src* adds under tree's policy
+	///
+	/// *Russian*: Количество элементов, которое добавит присоединение *src* при политике дерева
+	
+	fn append_growth(&self, src: &Self) -> usize {
+		match self.policy {
+			DuplicatePolicy::KeepAll => src.keys.len(),
+			DuplicatePolicy::Reject | DuplicatePolicy::Replace => {
+				let mut keys = src.keys.clone();
+				keys.dedup();
+				filter_present(&keys, &self.keys, false).len()
+			}
+		}
+	}
+	
+	/// *English*: Method **clear()** removes all elements
+	///
+	/// *Russian*: Метод **clear()** удаляет все элементы
+	
+	#[inline]
+	pub fn clear(&mut self) {
+		self.keys.clear();
+	}
+	
+	/// *English*: Method **remove()** removes *one occurrence* of value, if it's there
+	///
+	/// *Russian*: Метод **remove()** удаляет *одно вхождение* значения, если оно есть
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	///
+	/// let mut tree = FlatTree::from(vec![1, 2, 2, 3]);
+	/// tree.remove(&2);
+	/// tree.remove(&5);
+	/// assert_eq!(tree.to_vec(), vec![1, 2, 3]);
+	/// ```
+	
+	#[inline]
+	pub fn remove<Q>(&mut self, val: &Q)
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		let _ = self.try_remove(val);
+	}
+	
+	/// *English*: Method **try_remove()** removes value like **remove()**,
+	/// but returns *Error::NotFound*, if there is no such value.
+	///
+	/// *Russian*: Метод **try_remove()** удаляет значение как **remove()**,
+	/// но возвращает *Error::NotFound*, если такого значения нет.
+	
+	pub fn try_remove<Q>(&mut self, val: &Q) -> Result<(), Error>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		let index = self.lower_index(val);
+		match self.keys.get(index) {
+			Some(key) if key.borrow() == val => {
+				self.keys.remove(index);
+				Ok(())
+			}
+			_ => Err(Error::NotFound),
+		}
+	}
+	
+	/// *English*: Result of set operation. If tree has no duplicates
+	/// (policy isn't *KeepAll*), result has no duplicates too.
+	///
+	/// *Russian*: Результат операции над множествами. Если в дереве нет повторов
+	/// (политика не *KeepAll*), в результате их тоже нет.
+	
+	#[inline]
+	fn set_result(&self, elems: VecDeque<T>) -> SortedSeq<T> {
+		set_result(self.policy, elems)
+	}
+	
+	/// *English*: Method **difference()** returns *sorted* elements,
+	/// that are *in 1-st tree, but not in 2-nd*. Takes O(n + m).
+	///
+	/// *Russian*: Метод **difference()** возвращает *отсортированные* элементы,
+	/// *которые есть в 1 дереве, но которых нет во 2*. Занимает O(n + m).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let tree1 = FlatTree::from_iter(1..6);
+	/// let tree2 = FlatTree::from_iter(3..9);
+	///
+	/// assert_eq!(tree1.difference(&tree2).to_vec(), vec![1, 2]);
+	/// assert_eq!(tree1.intersection(&tree2).to_vec(), vec![3, 4, 5]);
+	/// assert_eq!(tree1.symmetric_difference(&tree2).to_vec(), vec![1, 2, 6, 7, 8]);
+	/// assert_eq!(tree1.union(&tree2).to_vec(), (1..9).collect::<Vec<i32>>());
+	/// ```
+	
+	#[inline]
+	pub fn difference(&self, other: &Self) -> SortedSeq<T> {
+		self.set_result(filter_present(&self.keys, &other.keys, false))
+	}
+	
+	/// *English*: Method **intersection()** returns *sorted* elements,
+	/// that are *in 1-st and 2-nd tree*. Takes O(n + m).
+	///
+	/// *Russian*: Метод **intersection()** возвращает *отсортированные* элементы,
+	/// *которые есть и в 1, и во 2 дереве*. Занимает O(n + m).
+	
+	#[inline]
+	pub fn intersection(&self, other: &Self) -> SortedSeq<T> {
+		self.set_result(filter_present(&self.keys, &other.keys, true))
+	}
+	
+	/// *English*: Method **symmetric_difference()** returns *sorted* elements,
+	/// that are *only in 1-st or 2-nd tree*. Takes O(n + m).
+	///
+	/// *Russian*: Метод **symmetric_difference()** возвращает *отсортированные* элементы,
+	/// которые есть *либо только в 1 дереве, либо только во 2*. Занимает O(n + m).
+	
+	pub fn symmetric_difference(&self, other: &Self) -> SortedSeq<T> {
+		let mut seq = SortedSeq::from_sorted(filter_present(&self.keys, &other.keys, false));
+		seq.merge(SortedSeq::from_sorted(filter_present(&other.keys, &self.keys, false)));
+		self.set_result(seq.to_deque())
+	}
+	
+	/// *English*: Method **union()** returns *sorted* elements of 1-st tree
+	/// and elements of 2-nd tree, which are not in 1-st. Takes O(n + m).
+	///
+	/// *Russian*: Метод **union()** возвращает *отсортированные* элементы 1 дерева
+	/// и элементы 2 дерева, которых нет в 1. Занимает O(n + m).
+	
+	pub fn union(&self, other: &Self) -> SortedSeq<T> {
+		let mut seq = SortedSeq::from_sorted(self.to_deque());
+		seq.merge(SortedSeq::from_sorted(filter_present(&other.keys, &self.keys, false)));
+		self.set_result(seq.to_deque())
+	}
+	
+	/// *English*: Method **is_disjoint()** checks, that trees have no elements in common
+	///
+	/// *Russian*: Метод **is_disjoint()** проверяет, что у деревьев нет общих элементов
+	
+	#[inline]
+	pub fn is_disjoint(&self, other: &Self) -> bool {
+		self.intersection(other).is_empty()
+	}
+	
+	/// *English*: Method **drain_filter()** removes all values, which match the function,
+	/// and returns *iterator with removed values*
+	///
+	/// *Russian*: Метод **drain_filter()** удаляет все значения, которые соответствуют функции,
+	/// и возвращает *итератор с удалёнными значениями*
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = FlatTree::from_iter(1..11);
+	/// let evens = tree.drain_filter(|x| x % 2 == 0);
+	///
+	/// assert_eq!(tree.to_vec(), vec![1, 3, 5, 7, 9]);
+	/// assert_eq!(evens.collect::<Vec<i32>>(), vec![2, 4, 6, 8, 10]);
+	/// ```
+	
+	pub fn drain_filter<F: FnMut(&T) -> bool>(&mut self, mut fun: F) -> TreeIter<T> {
+		let mut removed = VecDeque::new();
+		self.keys.retain(|key| if fun(key) {
+			removed.push_back(*key);
+			false
+		} else {
+			true
+		});
+		TreeIter { iter: removed }
+	}
+	
+	/// *English*: Method **extract_if()** returns *lazy* iterator, which visits keys
+	/// in *ascending* order and removes those, for which predicate returns *true*
+	/// (like **BinaryTree::extract_if()**). Every removal shifts the tail, so it takes O(n).
+	///
+	/// *Russian*: Метод **extract_if()** возвращает *ленивый* итератор, который обходит
+	/// ключи *по возрастанию* и удаляет те, для которых предикат возвращает *true*
+	/// (как **BinaryTree::extract_if()**). Каждое удаление сдвигает хвост, так что занимает O(n).
+	
+	#[inline]
+	pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, F>
+		where F: FnMut(&T) -> bool
+	{
+		ExtractIf {
+			tree: self,
+			pred,
+			index: 0,
+		}
+	}
+	
+	/// *English*: Method **retain()** keeps only keys, for which predicate returns *true*.
+	/// Keys are visited in *ascending* order. Takes O(n).
+	///
+	/// *Russian*: Метод **retain()** оставляет только ключи, для которых предикат
+	/// возвращает *true*. Ключи обходятся *по возрастанию*. Занимает O(n).
+	
+	#[inline]
+	pub fn retain<F>(&mut self, pred: F)
+		where F: FnMut(&T) -> bool
+	{
+		self.keys.retain(pred);
+	}
+	
+	/// *English*: Method **pop_first()** removes and returns min value or *None*, if tree is empty.
+	/// Shifts all elements, so it takes O(n).
+	///
+	/// *Russian*: Метод **pop_first()** удаляет и возвращает наименьшее значение
+	/// или *None*, если дерево пустое. Сдвигает все элементы, так что занимает O(n).
+	
+	#[inline]
+	pub fn pop_first(&mut self) -> Option<T> {
+		if self.keys.is_empty() {
+			return None;
+		}
+		Some(self.keys.remove(0))
+	}
+	
+	/// *English*: Method **try_pop_first()** removes and returns min value or *Error::Empty*
+	///
+	/// *Russian*: Метод **try_pop_first()** удаляет и возвращает наименьшее значение или *Error::Empty*
+	
+	#[inline]
+	pub fn try_pop_first(&mut self) -> Result<T, Error> {
+		self.pop_first().ok_or(Error::Empty)
+	}
+	
+	/// *English*: Method **pop_last()** removes and returns max value or *None*, if tree is empty
+	///
+	/// *Russian*: Метод **pop_last()** удаляет и возвращает наибольшее значение
+	/// или *None*, если дерево пустое
+	
+	#[inline]
+	pub fn pop_last(&mut self) -> Option<T> {
+		self.keys.pop()
+	}
+	
+	/// *English*: Method **try_pop_last()** removes and returns max value or *Error::Empty*
+	///
+	/// *Russian*: Метод **try_pop_last()** удаляет и возвращает наибольшее значение или *Error::Empty*
+	
+	#[inline]
+	pub fn try_pop_last(&mut self) -> Result<T, Error> {
+		self.pop_last().ok_or(Error::Empty)
+	}
+	
+	/// *English*: Method **replace_val()** changes all keys with
+	/// *some* value to *another* value
+	///
+	/// *Russian*: Метод **replace_val()** заменяет все ключи
+	/// с *одним* значением на ключи с *другим* значением
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	///
+	/// let mut tree = FlatTree::from(vec![1, 1, 2]);
+	/// tree.replace_val(&1, &3);
+	/// assert_eq!(tree.to_vec(), vec![2, 3, 3]);
+	/// ```
+	
+	pub fn replace_val(&mut self, old_val: &T, new_val: &T) {
+		if old_val == new_val {
+			return;
+		}
+		
+		let start = self.lower_index(old_val);
+		let end = self.upper_index(old_val);
+		self.keys.drain(start..end);
+		
+		for _ in start..end {
+			self.insert(new_val);
+		}
+	}
+	
+	/// *English*: Method **try_replace_val()** works like **replace_val()**,
+	/// but returns *Error::NotFound*, if there are no keys with old value.
+	///
+	/// *Russian*: Метод **try_replace_val()** работает как **replace_val()**,
+	/// но возвращает *Error::NotFound*, если ключей со старым значением нет.
+	
+	pub fn try_replace_val(&mut self, old_val: &T, new_val: &T) -> Result<(), Error> {
+		if !self.contains(old_val) {
+			return Err(Error::NotFound);
+		}
+		self.replace_val(old_val, new_val);
+		Ok(())
+	}
+	
+	/// *English*: Method **multi_remove()** removes one occurrence of every value
+	/// from *src* in one pass. Takes O(n + m log m).
+	///
+	/// *Russian*: Метод **multi_remove()** удаляет по одному вхождению каждого значения
+	/// из *src* за один проход. Занимает O(n + m log m).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::flat::FlatTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = FlatTree::from_iter(vec![1, 2, 2, 3, 4]);
+	/// tree.multi_remove(vec![4, 2, 7]);
+	/// assert_eq!(tree.to_vec(), vec![1, 2, 3]);
+	/// ```
+	
+	pub fn multi_remove(&mut self, mut src: Vec<T>) {
+		src.sort();
+		let mut i = 0;
+		
+		self.keys.retain(|key| {
+			while i < src.len() && src[i] < *key {
+				i += 1;
+			}
+			if i < src.len() && src[i] == *key {
+				i += 1;
+				return false;
+			}
+			true
+		});
+	}
+	
+	/// *English*: Method **validate()** checks, that keys are sorted
+	/// and there are no duplicates, if policy forbids them. Takes O(n).
+	///
+	/// *Russian*: Метод **validate()** проверяет, что ключи отсортированы
+	/// и нет повторов, если политика их запрещает. Занимает O(n).
+	
+	pub fn validate(&self) -> Result<(), InvariantError> {
+		for index in 1..self.keys.len() {
+			match self.keys[index - 1].cmp(&self.keys[index]) {
+				Ordering::Greater => return Err(InvariantError::Order { index }),
+				Ordering::Equal if self.policy != DuplicatePolicy::KeepAll =>
+					return Err(InvariantError::Duplicate { index }),
+				_ => (),
+			}
+		}
+		Ok(())
+	}
+}
+
+/// *English*: Lazy iterator of **FlatTree::extract_if()**
+///
+/// *Russian*: Ленивый итератор **FlatTree::extract_if()**
+
+pub struct ExtractIf<'a, T, F>
+	where T: Copy + Clone + Ord + Eq, F: FnMut(&T) -> bool
+{
+	tree: &'a mut FlatTree<T>,
+	pred: F,
+	index: usize,
+}
+
+impl<T, F> Iterator for ExtractIf<'_, T, F>
+	where T: Copy + Clone + Ord + Eq, F: FnMut(&T) -> bool
+{
+	type Item = T;
+	
+	fn next(&mut self) -> Option<T> {
+		while self.index < self.tree.keys.len() {
+			if (self.pred)(&self.tree.keys[self.index]) {
+				return Some(self.tree.keys.remove(self.index));
+			}
+			self.index += 1;
+		}
+		None
+	}
+}
+
+/// *English*: Owning iterator over sorted keys
+///
+/// *Russian*: Владеющий итератор по отсортированным ключам
+
+impl<T> IntoIterator for FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Item = T;
+	type IntoIter = vec::IntoIter<T>;
+	
+	#[inline]
+	fn into_iter(self) -> vec::IntoIter<T> {
+		self.keys.into_iter()
+	}
+}
+
+impl<'a, T> IntoIterator for &'a FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Item = T;
+	type IntoIter = Iter<'a, T>;
+	
+	#[inline]
+	fn into_iter(self) -> Iter<'a, T> {
+		self.iter()
+	}
+}
+
+/// *English*: **Extend<T>** trait for flat tree. Values are added one by one
+/// with **insert()**, so policy and limit are respected.
+///
+/// *Russian*: Трейт **Extend<T>** для плоского дерева. Значения добавляются по одному
+/// через **insert()**, так что политика и предел соблюдаются.
+
+impl<T> Extend<T> for FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+		for it in iter {
+			self.insert(&it);
+		}
+	}
+}
+
+/// *English*: **FromIterator<T>** trait for flat tree. Elements are collected
+/// and sorted at once, so it takes O(n log n).
+///
+/// *Russian*: Трейт **FromIterator<T>** для плоского дерева. Элементы собираются
+/// и сортируются за раз, так что это занимает O(n log n).
+
+impl<T> FromIterator<T> for FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+		FlatTree::from(iter.into_iter().collect::<Vec<T>>())
+	}
+}
+
+/// *English*: Converts vector to flat tree. Vector is sorted in place
+/// (stable, so equal keys keep their order), no extra memory is needed.
+///
+/// *Russian*: Конвертирует вектор в плоское дерево. Вектор сортируется на месте
+/// (устойчиво, так что равные ключи сохраняют порядок), дополнительная память не нужна.
+
+impl<T> From<Vec<T>> for FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn from(mut keys: Vec<T>) -> Self {
+		keys.sort();
+		FlatTree {
+			keys,
+			limit: None,
+			policy: DuplicatePolicy::KeepAll,
+		}
+	}
+}
+
+/// *English*: Converts deque to flat tree
+///
+/// *Russian*: Конвертирует дек в плоское дерево
+
+impl<T> From<VecDeque<T>> for FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn from(s: VecDeque<T>) -> Self {
+		FlatTree::from(Vec::from(s))
+	}
+}
+
+/// *English*: Converts slice to flat tree
+///
+/// *Russian*: Конвертирует срез в плоское дерево
+
+impl<T> From<&mut [T]> for FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn from(s: &mut [T]) -> Self {
+		FlatTree::from(s.to_vec())
+	}
+}
+
+/// *English*: Converts node tree to flat tree with the same policy and limit. Takes O(n).
+///
+/// *Russian*: Конвертирует дерево из узлов в плоское дерево
+/// с такими же политикой и пределом. Занимает O(n).
+///
+/// # Example
+///
+/// ```
+/// use binartree::flat::FlatTree;
+/// use binartree::tree::BinaryTree;
+///
+/// let tree = BinaryTree::from(vec![3, 1, 2]);
+/// let flat = FlatTree::from(tree.clone());
+/// assert_eq!(flat.to_vec(), tree.to_vec());
+///
+/// let back = BinaryTree::from(flat);
+/// assert_eq!(back, tree);
+/// assert_eq!(back.height(), 2);
+/// ```
+
+impl<T> From<BinaryTree<T>> for FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn from(tree: BinaryTree<T>) -> Self {
+		FlatTree {
+			keys: tree.to_vec(),
+			limit: tree.limit,
+			policy: tree.policy,
+		}
+	}
+}
+
+/// *English*: Converts flat tree to *balanced* node tree
+/// with the same policy and limit. Takes O(n).
+///
+/// *Russian*: Конвертирует плоское дерево в *сбалансированное* дерево из узлов
+/// с такими же политикой и пределом. Занимает O(n).
+
+impl<T> From<FlatTree<T>> for BinaryTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn from(flat: FlatTree<T>) -> Self {
+		let mut tree = BinaryTree::new();
+		tree.top = Node::from_sorted(&flat.keys);
+		tree.size = flat.keys.len();
+		tree.limit = flat.limit;
+		tree.policy = flat.policy;
+		tree.check();
+		tree
+	}
+}
+
+/// *English*: **BitAnd** creates flat tree from intersection of 2 trees.
+/// New tree has the same policy as the 1-st one.
+///
+/// *Russian*: **BitAnd** создаёт плоское дерево из пересечения 2-х деревьев.
+/// У нового дерева такая же политика, как у 1-го.
+
+impl<T> BitAnd for &FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Output = FlatTree<T>;
+	
+	fn bitand(self, rhs: Self) -> FlatTree<T> {
+		FlatTree {
+			keys: self.intersection(rhs).to_vec(),
+			limit: None,
+			policy: self.policy,
+		}
+	}
+}
+
+/// *English*: **BitOr** creates flat tree from union of 2 trees.
+/// New tree has the same policy as the 1-st one.
+///
+/// *Russian*: **BitOr** создаёт плоское дерево из объединения 2-х деревьев.
+/// У нового дерева такая же политика, как у 1-го.
+///
+/// # Example
+///
+/// ```
+/// use binartree::flat::FlatTree;
+/// use std::iter::FromIterator;
+///
+/// let tree1 = FlatTree::from_iter(1..10);
+/// let tree2 = FlatTree::from_iter(5..15);
+///
+/// assert_eq!((&tree1 | &tree2).to_vec(), (1..15).collect::<Vec<i32>>());
+/// assert_eq!((&tree1 & &tree2).to_vec(), (5..10).collect::<Vec<i32>>());
+/// assert_eq!((&tree1 ^ &tree2).len(), 9);
+/// ```
+
+impl<T> BitOr for &FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Output = FlatTree<T>;
+	
+	fn bitor(self, rhs: Self) -> FlatTree<T> {
+		FlatTree {
+			keys: self.union(rhs).to_vec(),
+			limit: None,
+			policy: self.policy,
+		}
+	}
+}
+
+/// *English*: **BitXor** creates flat tree from symmetric difference of 2 trees.
+/// New tree has the same policy as the 1-st one.
+///
+/// *Russian*: **BitXor** создаёт плоское дерево из симметрической разности 2-х деревьев.
+/// У нового дерева такая же политика, как у 1-го.
+
+impl<T> BitXor for &FlatTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Output = FlatTree<T>;
+	
+	fn bitxor(self, rhs: Self) -> FlatTree<T> {
+		FlatTree {
+			keys: self.symmetric_difference(rhs).to_vec(),
+			limit: None,
+			policy: self.policy,
+		}
+	}
+}
+
+/// *English*: Inner representation of **AdaptiveTree**
+///
+/// *Russian*: Внутреннее представление **AdaptiveTree**
+
+#[derive(Debug, Clone)]
+enum Repr<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	Flat(FlatTree<T>),
+	Nodes(BinaryTree<T>),
+}
+
+/// **Adaptive Tree**
+/// --------------------
+///
+/// *English*: **AdaptiveTree** stays **FlatTree** while it's small and converts
+/// itself to balanced **BinaryTree** (with auto rebalance), when it grows above *threshold*.
+/// When it shrinks to a half of threshold, it becomes flat again
+/// (the gap stops it from converting back and forth on every insert/remove).
+///
+/// *Russian*: **AdaptiveTree** остаётся **FlatTree**, пока оно маленькое, и превращается
+/// в сбалансированное **BinaryTree** (с автобалансировкой), когда вырастает больше *порога*.
+/// Когда оно уменьшается до половины порога, оно снова становится плоским
+/// (зазор не даёт ему превращаться туда и обратно на каждой вставке/удалении).
+///
+/// # Example
+///
+/// ```
+/// use binartree::flat::AdaptiveTree;
+///
+/// let mut tree = AdaptiveTree::with_threshold(4);
+/// tree.extend(1..=4);
+/// assert!(tree.is_flat());
+///
+/// tree.insert(&5);
+/// assert!(!tree.is_flat());
+///
+/// tree.remove(&5);
+/// tree.remove(&4);
+/// assert!(!tree.is_flat());
+/// tree.remove(&3);
+/// assert!(tree.is_flat());
+///
+/// assert_eq!(tree.to_vec(), vec![1, 2]);
+/// ```
+
+#[derive(Debug, Clone)]
+pub struct AdaptiveTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	repr: Repr<T>,
+	threshold: usize,
+}
+
+/// *English*: **Default** adaptive tree is *empty* and has **ADAPTIVE_THRESHOLD**
+///
+/// *Russian*: По-умолчанию адаптивное дерево *пусто* и имеет порог **ADAPTIVE_THRESHOLD**
+
+impl<T> Default for AdaptiveTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn default() -> Self {
+		AdaptiveTree::with_threshold(ADAPTIVE_THRESHOLD)
+	}
+}
+
+impl<T> AdaptiveTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	/// *English*: Method **new()** creates *empty* adaptive tree with **ADAPTIVE_THRESHOLD**
+	///
+	/// *Russian*: Метод **new()** создаёт *пустое* адаптивное дерево с порогом **ADAPTIVE_THRESHOLD**
+	
+	#[inline]
+	pub fn new() -> Self {
+		AdaptiveTree::default()
+	}
+	
+	/// *English*: Method **with_threshold()** creates *empty* adaptive tree,
+	/// which becomes node tree, when it has more than *threshold* elements
+	///
+	/// *Russian*: Метод **with_threshold()** создаёт *пустое* адаптивное дерево,
+	/// которое становится деревом из узлов, когда в нём больше *threshold* элементов
+	
+	#[inline]
+	pub fn with_threshold(threshold: usize) -> Self {
+		AdaptiveTree::with_policy(threshold, DuplicatePolicy::KeepAll)
+	}
+	
+	/// *English*: Method **with_policy()** creates *empty* adaptive tree
+	/// with given threshold and policy for duplicates
+	///
+	/// *Russian*: Метод **with_policy()** создаёт *пустое* адаптивное дерево
+	/// с данными порогом и политикой для повторов
+	
+	#[inline]
+	pub fn with_policy(threshold: usize, policy: DuplicatePolicy) -> Self {
+		AdaptiveTree {
+			repr: Repr::Flat(FlatTree::with_policy(policy)),
+			threshold,
+		}
+	}
+	
+	/// *English*: Method **threshold()** returns size, after which tree becomes node tree
+	///
+	/// *Russian*: Метод **threshold()** возвращает размер, после которого дерево становится деревом из узлов
+	
+	#[inline]
+	pub fn threshold(&self) -> usize {
+		self.threshold
+	}
+	
+	/// *English*: Method **is_flat()** checks, that tree is flat now
+	///
+	/// *Russian*: Метод **is_flat()** проверяет, что дерево сейчас плоское
+	
+	#[inline]
+	pub fn is_flat(&self) -> bool {
+		matches!(self.repr, Repr::Flat(_))
+	}
+	
+	/// *English*: Method **policy()** returns policy for duplicates
+	///
+	/// *Russian*: Метод **policy()** возвращает политику для повторов
+	
+	#[inline]
+	pub fn policy(&self) -> DuplicatePolicy {
+		match &self.repr {
+			Repr::Flat(flat) => flat.policy(),
+			Repr::Nodes(tree) => tree.policy(),
+		}
+	}
+	
+	/// *English*: Method **len()** returns number of elements
+	///
+	/// *Russian*: Метод **len()** возвращает количество элементов
+	
+	#[inline]
+	pub fn len(&self) -> usize {
+		match &self.repr {
+			Repr::Flat(flat) => flat.len(),
+			Repr::Nodes(tree) => tree.len(),
+		}
+	}
+	
+	/// *English*: Method **is_empty()** checks that tree has no elements
+	///
+	/// *Russian*: Метод **is_empty()** проверяет, что в дереве нет элементов
+	
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+	
+	/// *English*: Converts tree, if it has crossed the threshold
+	///
+	/// *Russian*: Конвертирует дерево, если оно пересекло порог
+	
+	fn adapt(&mut self) {
+		let len = self.len();
+		let repr = match &mut self.repr {
+			Repr::Flat(flat) if len > self.threshold => {
+				let mut tree = BinaryTree::from(core::mem::take(flat));
+				tree.set_auto_rebalance(Some(ADAPTIVE_BALANCE_FACTOR));
+				Repr::Nodes(tree)
+			}
+			Repr::Nodes(tree) if len <= self.threshold / 2 => {
+				let mut flat = FlatTree::from(core::mem::take(tree));
+				flat.keys.shrink_to_fit();
+				Repr::Flat(flat)
+			}
+			_ => return,
+		};
+		self.repr = repr;
+	}
+	
+	/// *English*: Method **insert()** adds value like **BinaryTree::insert()**
+	///
+	/// *Russian*: Метод **insert()** добавляет значение как **BinaryTree::insert()**
+	
+	pub fn insert(&mut self, val: &T) -> bool {
+		let changed = match &mut self.repr {
+			Repr::Flat(flat) => flat.insert(val),
+			Repr::Nodes(tree) => tree.insert(val),
+		};
+		self.adapt();
+		changed
+	}
+	
+	/// *English*: Method **remove()** removes one occurrence of value, if it's there
+	///
+	/// *Russian*: Метод **remove()** удаляет одно вхождение значения, если оно есть
+	
+	#[inline]
+	pub fn remove<Q>(&mut self, val: &Q)
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		let _ = self.try_remove(val);
+	}
+	
+	/// *English*: Method **try_remove()** removes value like **remove()**,
+	/// but returns *Error::NotFound*, if there is no such value.
+	///
+	/// *Russian*: Метод **try_remove()** удаляет значение как **remove()**,
+	/// но возвращает *Error::NotFound*, если такого значения нет.
+	
+	pub fn try_remove<Q>(&mut self, val: &Q) -> Result<(), Error>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		match &mut self.repr {
+			Repr::Flat(flat) => flat.try_remove(val)?,
+			Repr::Nodes(tree) => tree.try_remove(val)?,
+		}
+		self.adapt();
+		Ok(())
+	}
+	
+	/// *English*: Method **contains()** checks that value is in the tree
+	///
+	/// *Russian*: Метод **contains()** проверяет наличие значения в дереве
+	
+	#[inline]
+	pub fn contains<Q>(&self, val: &Q) -> bool
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.get(val).is_some()
+	}
+	
+	/// *English*: Method **get()** returns key, which is equal to value, or *None*
+	///
+	/// *Russian*: Метод **get()** возвращает ключ, равный значению, или *None*
+	
+	#[inline]
+	pub fn get<Q>(&self, val: &Q) -> Option<&T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		match &self.repr {
+			Repr::Flat(flat) => flat.get(val),
+			Repr::Nodes(tree) => tree.get(val),
+		}
+	}
+	
+	/// *English*: Method **try_first()** returns *minimum value* or *Error::Empty*
+	///
+	/// *Russian*: Метод **try_first()** возвращает *минимальное значение* или *Error::Empty*
+	
+	#[inline]
+	pub fn try_first(&self) -> Result<&T, Error> {
+		match &self.repr {
+			Repr::Flat(flat) => flat.try_first(),
+			Repr::Nodes(tree) => tree.try_first(),
+		}
+	}
+	
+	/// *English*: Method **try_last()** returns *maximum value* or *Error::Empty*
+	///
+	/// *Russian*: Метод **try_last()** возвращает *максимальное значение* или *Error::Empty*
+	
+	#[inline]
+	pub fn try_last(&self) -> Result<&T, Error> {
+		match &self.repr {
+			Repr::Flat(flat) => flat.try_last(),
+			Repr::Nodes(tree) => tree.try_last(),
+		}
+	}
+	
+	/// *English*: Method **range()** returns *sorted* iterator over keys from range
+	///
+	/// *Russian*: Метод **range()** возвращает *отсортированный* итератор по ключам из диапазона
+	
+	pub fn range<Q, R>(&self, range: R) -> TreeIter<T>
+		where T: Borrow<Q>, Q: Ord + ?Sized, R: RangeBounds<Q>
+	{
+		match &self.repr {
+			Repr::Flat(flat) => flat.range(range).collect(),
+			Repr::Nodes(tree) => tree.range(range),
+		}
+	}
+	
+	/// *English*: Method **iter()** returns *sorted* iterator over keys
+	///
+	/// *Russian*: Метод **iter()** возвращает *отсортированный* итератор по ключам
+	
+	pub fn iter(&self) -> TreeIter<T> {
+		match &self.repr {
+			Repr::Flat(flat) => flat.iter().collect(),
+			Repr::Nodes(tree) => tree.iter(),
+		}
+	}
+	
+	/// *English*: Method **to_vec()** returns sorted vector of elements
+	///
+	/// *Russian*: Метод **to_vec()** возвращает отсортированный вектор элементов
+	
+	pub fn to_vec(&self) -> Vec<T> {
+		match &self.repr {
+			Repr::Flat(flat) => flat.to_vec(),
+			Repr::Nodes(tree) => tree.to_vec(),
+		}
+	}
+	
+	/// *English*: Method **clear()** removes all elements, tree becomes flat
+	///
+	/// *Russian*: Метод **clear()** удаляет все элементы, дерево становится плоским
+	
+	#[inline]
+	pub fn clear(&mut self) {
+		self.repr = Repr::Flat(FlatTree::with_policy(self.policy()));
+	}
+}
+
+/// *English*: **Extend<T>** trait for adaptive tree
+///
+/// *Russian*: Трейт **Extend<T>** для адаптивного дерева
+
+impl<T> Extend<T> for AdaptiveTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+		for it in iter {
+			self.insert(&it);
+		}
+	}
+}
+
+/// *English*: **FromIterator<T>** trait for adaptive tree with **ADAPTIVE_THRESHOLD**
+///
+/// *Russian*: Трейт **FromIterator<T>** для адаптивного дерева с порогом **ADAPTIVE_THRESHOLD**
+
+impl<T> FromIterator<T> for AdaptiveTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+		let mut tree = AdaptiveTree::new();
+		tree.extend(iter);
+		tree
+	}
+}
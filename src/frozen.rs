@@ -0,0 +1,686 @@
+use crate::error::Error;
+use crate::iter::TreeIter;
+use crate::node::Node;
+use crate::tree::BinaryTree;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::iter::FromIterator;
+use core::ops::{Bound, RangeBounds};
+
+#[cfg(feature = "std")]
+use crate::codec::{checksum, FixedKey};
+#[cfg(feature = "std")]
+use crate::error::{FormatError, InvariantError};
+
+/// **Frozen Tree**
+/// ---------------
+///
+/// *English*: **FrozenTree** is a read-only tree, which keeps keys in one array
+/// in *Eytzinger* (BFS) order: root is at position 1, children of position *k* are
+/// at *2k* and *2k + 1*. Top levels of all searches share the same few cache lines,
+/// and next level can be computed without branches: *k = 2k + (key < val)*.
+/// It's made for sets, which are built once and queried very often.
+/// Duplicates of source tree are kept.
+///
+/// *Russian*: **FrozenTree** - дерево только для чтения, которое хранит ключи в одном
+/// массиве в порядке *Эйтцингера* (обход в ширину): корень в позиции 1, потомки позиции
+/// *k* - в *2k* и *2k + 1*. Верхние уровни всех поисков лежат в одних и тех же кэш-линиях,
+/// а следующий уровень вычисляется без ветвлений: *k = 2k + (key < val)*.
+/// Подходит для множеств, которые строятся один раз и очень часто опрашиваются.
+/// Повторы исходного дерева сохраняются.
+///
+/// # Example
+///
+/// ```
+/// use binartree::tree::BinaryTree;
+/// use std::iter::FromIterator;
+/// use std::ops::Bound;
+///
+/// let frozen = BinaryTree::from_iter(vec![40, 10, 30, 20, 50]).freeze();
+///
+/// assert!(frozen.contains(&30));
+/// assert_eq!(frozen.lower_bound(Bound::Excluded(&30)), Some(&40));
+/// assert_eq!(frozen.rank(&35), 3);
+/// assert_eq!(frozen.iter().collect::<Vec<i32>>(), vec![10, 20, 30, 40, 50]);
+/// ```
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FrozenTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	/// Eytzinger position *k* is stored at *keys[k - 1]*
+	keys: Vec<T>,
+}
+
+/// *English*: **Default** frozen tree is *empty*
+///
+/// *Russian*: По-умолчанию замороженное дерево *пусто*
+
+impl<T> Default for FrozenTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn default() -> Self {
+		FrozenTree { keys: Vec::new() }
+	}
+}
+
+/// *English*: In-order successor of Eytzinger position in tree with *len* keys.
+/// Returns 0 after the last position.
+///
+/// *Russian*: Следующая по порядку позиция Эйтцингера в дереве из *len* ключей.
+/// Возвращает 0 после последней позиции.
+
+#[inline]
+fn next_pos(mut k: usize, len: usize) -> usize {
+	if 2 * k < len {
+		k = 2 * k + 1;
+		while 2 * k <= len {
+			k *= 2;
+		}
+		k
+	} else {
+		// climb while we are right child, then once more
+		k >>= k.trailing_ones();
+		k >> 1
+	}
+}
+
+/// *English*: In-order predecessor of Eytzinger position. Returns 0 before the first one.
+///
+/// *Russian*: Предыдущая по порядку позиция Эйтцингера. Возвращает 0 перед первой.
+
+#[inline]
+fn prev_pos(mut k: usize, len: usize) -> usize {
+	if 2 * k <= len {
+		k *= 2;
+		while 2 * k < len {
+			k = 2 * k + 1;
+		}
+		k
+	} else {
+		// climb while we are left child, then once more
+		k >>= k.trailing_zeros();
+		k >> 1
+	}
+}
+
+/// *English*: The leftmost Eytzinger position (or 0 for empty tree)
+///
+/// *Russian*: Самая левая позиция Эйтцингера (или 0 для пустого дерева)
+
+#[inline]
+fn first_pos(len: usize) -> usize {
+	if len == 0 {
+		return 0;
+	}
+	let mut k = 1;
+	while 2 * k <= len {
+		k *= 2;
+	}
+	k
+}
+
+/// *English*: Number of keys in subtree with root at position *k*
+///
+/// *Russian*: Количество ключей в поддереве с корнем в позиции *k*
+
+fn subtree_len(k: usize, len: usize) -> usize {
+	let (mut low, mut high, mut count) = (k, k, 0);
+	while low <= len {
+		count += high.min(len) - low + 1;
+		low *= 2;
+		high = 2 * high + 1;
+	}
+	count
+}
+
+impl<T> FrozenTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	/// *English*: Builds frozen tree from *sorted* slice in O(n)
+	///
+	/// *Russian*: Строит замороженное дерево из *отсортированного* среза за O(n)
+	
+	pub(crate) fn from_sorted(src: &[T]) -> Self {
+		let len = src.len();
+		let mut keys = src.to_vec();
+		let mut k = first_pos(len);
+		for key in src {
+			keys[k - 1] = *key;
+			k = next_pos(k, len);
+		}
+		FrozenTree { keys }
+	}
+	
+	/// *English*: Method **len()** returns number of keys
+	///
+	/// *Russian*: Метод **len()** возвращает количество ключей
+	
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.keys.len()
+	}
+	
+	/// *English*: Method **is_empty()** checks that tree has no keys
+	///
+	/// *Russian*: Метод **is_empty()** проверяет, что в дереве нет ключей
+	
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.keys.is_empty()
+	}
+	
+	/// *English*: Method **as_slice()** returns keys in Eytzinger order
+	///
+	/// *Russian*: Метод **as_slice()** возвращает ключи в порядке Эйтцингера
+	
+	#[inline]
+	pub fn as_slice(&self) -> &[T] {
+		&self.keys
+	}
+	
+	/// *English*: Branch-free descent. *pred* must be *true* for some prefix of sorted keys.
+	/// Returns position of the first key, for which it's *false*, or 0.
+	///
+	/// *Russian*: Спуск без ветвлений. *pred* должен быть *true* для какого-то префикса
+	/// отсортированных ключей. Возвращает позицию первого ключа, для которого он *false*, или 0.
+	
+	#[inline]
+	fn search<P>(&self, mut pred: P) -> usize
+		where P: FnMut(&T) -> bool
+	{
+		let len = self.keys.len();
+		let mut k = 1;
+		while k <= len {
+			k = 2 * k + usize::from(pred(&self.keys[k - 1]));
+		}
+		// drop the right turns after the last left one and the left turn itself
+		k >> (k.trailing_ones() + 1)
+	}
+	
+	/// *English*: In-order position (rank) of Eytzinger position, O(log² n)
+	///
+	/// *Russian*: Позиция по порядку (ранг) для позиции Эйтцингера, O(log² n)
+	
+	fn sorted_index(&self, k: usize) -> usize {
+		let len = self.keys.len();
+		if k == 0 {
+			return len;
+		}
+		
+		let mut index = subtree_len(2 * k, len);
+		let mut node = k;
+		while node > 1 {
+			if node & 1 == 1 {
+				index += subtree_len(node - 1, len) + 1;
+			}
+			node >>= 1;
+		}
+		index
+	}
+	
+	#[inline]
+	fn key_at(&self, k: usize) -> Option<&T> {
+		k.checked_sub(1).and_then(|index| self.keys.get(index))
+	}
+	
+	/// *English*: Position of the first key *above* the bound or 0
+	///
+	/// *Russian*: Позиция первого ключа *выше* границы или 0
+	
+	fn lower_pos<Q>(&self, bound: Bound<&Q>) -> usize
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		match bound {
+			Bound::Included(val) => self.search(|key| key.borrow() < val),
+			Bound::Excluded(val) => self.search(|key| key.borrow() <= val),
+			Bound::Unbounded => first_pos(self.keys.len()),
+		}
+	}
+	
+	/// *English*: Method **contains()** checks that value is in the tree.
+	/// Takes O(log n) without unpredictable branches.
+	///
+	/// *Russian*: Метод **contains()** проверяет наличие значения в дереве.
+	/// Занимает O(log n) без непредсказуемых ветвлений.
+	
+	#[inline]
+	pub fn contains<Q>(&self, val: &Q) -> bool
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.get(val).is_some()
+	}
+	
+	/// *English*: Method **get()** returns the first key, which is equal to value, or *None*
+	///
+	/// *Russian*: Метод **get()** возвращает первый ключ, равный значению, или *None*
+	
+	#[inline]
+	pub fn get<Q>(&self, val: &Q) -> Option<&T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.lower_bound(Bound::Included(val)).filter(|key| (*key).borrow() == val)
+	}
+	
+	/// *English*: Method **partition_point()** returns the smallest key, for which
+	/// predicate is *false*, or *None* (like **BinaryTree::partition_point()**)
+	///
+	/// *Russian*: Метод **partition_point()** возвращает наименьший ключ, для которого
+	/// предикат *ложен*, или *None* (как **BinaryTree::partition_point()**)
+	
+	#[inline]
+	pub fn partition_point<P>(&self, pred: P) -> Option<&T>
+		where P: FnMut(&T) -> bool
+	{
+		self.key_at(self.search(pred))
+	}
+	
+	/// *English*: Method **lower_bound()** returns the smallest key *above* the bound
+	/// or *None*, if there is no such key (like **BinaryTree::lower_bound()**)
+	///
+	/// *Russian*: Метод **lower_bound()** возвращает наименьший ключ *выше* границы
+	/// или *None*, если такого ключа нет (как **BinaryTree::lower_bound()**)
+	
+	#[inline]
+	pub fn lower_bound<Q>(&self, bound: Bound<&Q>) -> Option<&T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.key_at(self.lower_pos(bound))
+	}
+	
+	/// *English*: Method **upper_bound()** returns the biggest key *below* the bound
+	/// or *None*, if there is no such key (like **BinaryTree::upper_bound()**)
+	///
+	/// *Russian*: Метод **upper_bound()** возвращает наибольший ключ *ниже* границы
+	/// или *None*, если такого ключа нет (как **BinaryTree::upper_bound()**)
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::frozen::FrozenTree;
+	/// use std::ops::Bound;
+	///
+	/// let frozen = FrozenTree::from(vec![10, 20, 30]);
+	///
+	/// assert_eq!(frozen.upper_bound(Bound::Included(&20)), Some(&20));
+	/// assert_eq!(frozen.upper_bound(Bound::Excluded(&20)), Some(&10));
+	/// assert_eq!(frozen.upper_bound(Bound::Excluded(&10)), None);
+	/// ```
+	
+	pub fn upper_bound<Q>(&self, bound: Bound<&Q>) -> Option<&T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		let len = self.keys.len();
+		let after = match bound {
+			Bound::Included(val) => self.search(|key| key.borrow() <= val),
+			Bound::Excluded(val) => self.search(|key| key.borrow() < val),
+			Bound::Unbounded => 0,
+		};
+		
+		match after {
+			0 => self.key_at(last_pos(len)),
+			k => self.key_at(prev_pos(k, len)),
+		}
+	}
+	
+	/// *English*: Method **rank()** returns number of keys, which are *less* than value.
+	/// Takes O(log² n).
+	///
+	/// *Russian*: Метод **rank()** возвращает количество ключей, *меньших* значения.
+	/// Занимает O(log² n).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::frozen::FrozenTree;
+	///
+	/// let frozen = FrozenTree::from(vec![1, 3, 3, 5]);
+	///
+	/// assert_eq!(frozen.rank(&0), 0);
+	/// assert_eq!(frozen.rank(&3), 1);
+	/// assert_eq!(frozen.rank(&4), 3);
+	/// assert_eq!(frozen.rank(&9), 4);
+	/// ```
+	
+	#[inline]
+	pub fn rank<Q>(&self, val: &Q) -> usize
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.sorted_index(self.search(|key| key.borrow() < val))
+	}
+	
+	/// *English*: Method **try_first()** returns *minimum value* or *Error::Empty*
+	///
+	/// *Russian*: Метод **try_first()** возвращает *минимальное значение* или *Error::Empty*
+	
+	#[inline]
+	pub fn try_first(&self) -> Result<&T, Error> {
+		self.key_at(first_pos(self.keys.len())).ok_or(Error::Empty)
+	}
+	
+	/// *English*: Method **try_last()** returns *maximum value* or *Error::Empty*
+	///
+	/// *Russian*: Метод **try_last()** возвращает *максимальное значение* или *Error::Empty*
+	
+	#[inline]
+	pub fn try_last(&self) -> Result<&T, Error> {
+		self.key_at(last_pos(self.keys.len())).ok_or(Error::Empty)
+	}
+	
+	/// *English*: Method **iter()** returns *lazy sorted* iterator over keys
+	///
+	/// *Russian*: Метод **iter()** возвращает *ленивый отсортированный* итератор по ключам
+	
+	#[inline]
+	pub fn iter(&self) -> Iter<'_, T> {
+		Iter { keys: &self.keys, pos: first_pos(self.keys.len()), left: self.keys.len() }
+	}
+	
+	/// *English*: Method **range()** returns *lazy sorted* iterator over keys from range.
+	/// Start is found in O(log n), length of range in O(log² n).
+	///
+	/// *Russian*: Метод **range()** возвращает *ленивый отсортированный* итератор по ключам
+	/// из диапазона. Начало ищется за O(log n), длина диапазона - за O(log² n).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::frozen::FrozenTree;
+	///
+	/// let frozen = FrozenTree::from((0..100).collect::<Vec<i32>>());
+	///
+	/// assert_eq!(frozen.range(10..15).collect::<Vec<i32>>(), vec![10, 11, 12, 13, 14]);
+	/// assert_eq!(frozen.range(98..).len(), 2);
+	/// ```
+	
+	pub fn range<Q, R>(&self, range: R) -> Iter<'_, T>
+		where T: Borrow<Q>, Q: Ord + ?Sized, R: RangeBounds<Q>
+	{
+		let pos = self.lower_pos(range.start_bound());
+		let end = match range.end_bound() {
+			Bound::Included(val) => self.search(|key| key.borrow() <= val),
+			Bound::Excluded(val) => self.search(|key| key.borrow() < val),
+			Bound::Unbounded => 0,
+		};
+		
+		let (start, end) = (self.sorted_index(pos), self.sorted_index(end));
+		Iter { keys: &self.keys, pos, left: end.saturating_sub(start) }
+	}
+	
+	/// *English*: Method **to_vec()** returns sorted vector of keys
+	///
+	/// *Russian*: Метод **to_vec()** возвращает отсортированный вектор ключей
+	
+	#[inline]
+	pub fn to_vec(&self) -> Vec<T> {
+		self.iter().collect()
+	}
+	
+	/// *English*: Method **thaw()** creates balanced **BinaryTree** with the same keys
+	///
+	/// *Russian*: Метод **thaw()** создаёт сбалансированное **BinaryTree** с теми же ключами
+	
+	pub fn thaw(&self) -> BinaryTree<T> {
+		let mut tree = BinaryTree::new();
+		tree.top = Node::from_sorted(&self.to_vec());
+		tree.size = self.keys.len();
+		tree
+	}
+}
+
+/// *English*: The rightmost Eytzinger position (or 0 for empty tree)
+///
+/// *Russian*: Самая правая позиция Эйтцингера (или 0 для пустого дерева)
+
+#[inline]
+fn last_pos(len: usize) -> usize {
+	if len == 0 {
+		return 0;
+	}
+	let mut k = 1;
+	while 2 * k < len {
+		k = 2 * k + 1;
+	}
+	k
+}
+
+/// *English*: Lazy in-order iterator of **FrozenTree**
+///
+/// *Russian*: Ленивый итератор по порядку для **FrozenTree**
+
+#[derive(Debug, Clone)]
+pub struct Iter<'a, T>
+	where T: Copy + Clone + Ord + Eq
+{
+	keys: &'a [T],
+	pos: usize,
+	left: usize,
+}
+
+impl<T> Iterator for Iter<'_, T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Item = T;
+	
+	#[inline]
+	fn next(&mut self) -> Option<T> {
+		if self.left == 0 {
+			return None;
+		}
+		let key = self.keys[self.pos - 1];
+		self.pos = next_pos(self.pos, self.keys.len());
+		self.left -= 1;
+		Some(key)
+	}
+	
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.left, Some(self.left))
+	}
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T>
+	where T: Copy + Clone + Ord + Eq
+{}
+
+impl<'a, T> IntoIterator for &'a FrozenTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Item = T;
+	type IntoIter = Iter<'a, T>;
+	
+	#[inline]
+	fn into_iter(self) -> Iter<'a, T> {
+		self.iter()
+	}
+}
+
+impl<T> IntoIterator for FrozenTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Item = T;
+	type IntoIter = TreeIter<T>;
+	
+	#[inline]
+	fn into_iter(self) -> TreeIter<T> {
+		self.iter().collect()
+	}
+}
+
+/// *English*: Sorts vector and freezes it
+///
+/// *Russian*: Сортирует вектор и замораживает его
+
+impl<T> From<Vec<T>> for FrozenTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn from(mut keys: Vec<T>) -> Self {
+		keys.sort();
+		FrozenTree::from_sorted(&keys)
+	}
+}
+
+/// *English*: Freezes tree, like **BinaryTree::freeze()**
+///
+/// *Russian*: Замораживает дерево, как **BinaryTree::freeze()**
+
+impl<T> From<&BinaryTree<T>> for FrozenTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn from(tree: &BinaryTree<T>) -> Self {
+		FrozenTree::from_sorted(&tree.to_vec())
+	}
+}
+
+impl<T> FromIterator<T> for FrozenTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+		FrozenTree::from(iter.into_iter().collect::<Vec<T>>())
+	}
+}
+
+impl<T> BinaryTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	/// *English*: Method **freeze()** creates read-only **FrozenTree**
+	/// with the same keys in O(n). Check **frozen.rs** for more information.
+	///
+	/// *Russian*: Метод **freeze()** создаёт **FrozenTree** только для чтения
+	/// с теми же ключами за O(n). Изучите **frozen.rs** для полного понимания.
+	
+	#[inline]
+	pub fn freeze(&self) -> FrozenTree<T> {
+		FrozenTree::from(self)
+	}
+}
+
+/// *English*: Magic bytes of frozen tree buffer
+///
+/// *Russian*: Магические байты буфера замороженного дерева
+
+#[cfg(feature = "std")]
+pub const FROZEN_MAGIC: [u8; 4] = *b"BNFZ";
+
+/// *English*: Length of frozen tree buffer header
+///
+/// *Russian*: Длина заголовка буфера замороженного дерева
+
+#[cfg(feature = "std")]
+const FROZEN_HEADER_LEN: usize = 16;
+
+/// *English*: Byte buffer of frozen tree with fixed-width keys.
+/// It keeps the Eytzinger array as is, so loading is one pass without sorting:
+///
+/// *Russian*: Байтовый буфер замороженного дерева с ключами фиксированной ширины.
+/// Он хранит массив Эйтцингера как есть, так что загрузка - один проход без сортировки:
+///
+/// ```text
+/// offset  size  field
+///      0     4  magic "BNFZ"
+///      4     1  major version (1)
+///      5     1  key type tag
+///      6     2  key width in bytes
+///      8     8  element count
+///     16     -  keys in Eytzinger order
+///      -     8  FNV-1a 64 checksum of everything before it
+/// ```
+
+#[cfg(feature = "std")]
+impl<T> FrozenTree<T>
+	where T: Copy + Clone + Ord + Eq + FixedKey
+{
+	/// *English*: Method **to_bytes()** returns byte buffer of tree
+	///
+	/// *Russian*: Метод **to_bytes()** возвращает байтовый буфер дерева
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::frozen::FrozenTree;
+	///
+	/// let frozen = FrozenTree::from(vec![3u64, 1, 2]);
+	/// let bytes = frozen.to_bytes();
+	///
+	/// assert_eq!(FrozenTree::<u64>::from_bytes(&bytes), Ok(frozen));
+	/// ```
+	
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(FROZEN_HEADER_LEN + self.keys.len() * T::WIDTH + 8);
+		bytes.extend_from_slice(&FROZEN_MAGIC);
+		bytes.extend_from_slice(&[1, T::TAG]);
+		bytes.extend_from_slice(&(T::WIDTH as u16).to_le_bytes());
+		bytes.extend_from_slice(&(self.keys.len() as u64).to_le_bytes());
+		
+		let start = bytes.len();
+		bytes.resize(start + self.keys.len() * T::WIDTH, 0);
+		for (key, out) in self.keys.iter().zip(bytes[start..].chunks_exact_mut(T::WIDTH)) {
+			key.encode(out);
+		}
+		
+		let sum = checksum(&bytes);
+		bytes.extend_from_slice(&sum.to_le_bytes());
+		bytes
+	}
+	
+	/// *English*: Method **from_bytes()** loads tree from buffer made by **to_bytes()**.
+	/// Keys are decoded in place, checksum and order are verified, so damaged buffer
+	/// gives *FormatError*. Buffer must have no extra bytes.
+	///
+	/// *Russian*: Метод **from_bytes()** загружает дерево из буфера, созданного **to_bytes()**.
+	/// Ключи декодируются на месте, контрольная сумма и порядок проверяются, так что
+	/// повреждённый буфер даёт *FormatError*. В буфере не должно быть лишних байт.
+	
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, FormatError> {
+		if bytes.len() < FROZEN_HEADER_LEN {
+			return Err(if bytes.starts_with(&FROZEN_MAGIC[..bytes.len().min(4)]) { FormatError::Truncated } else { FormatError::Magic });
+		}
+		if bytes[..4] != FROZEN_MAGIC {
+			return Err(FormatError::Magic);
+		}
+		if bytes[4] != 1 {
+			return Err(FormatError::Version { major: bytes[4] });
+		}
+		
+		let width = u16::from_le_bytes([bytes[6], bytes[7]]);
+		if bytes[5] != T::TAG || usize::from(width) != T::WIDTH {
+			return Err(FormatError::KeyType { tag: bytes[5], width: u32::from(width) });
+		}
+		
+		let mut count = [0; 8];
+		count.copy_from_slice(&bytes[8..16]);
+		let body = (u64::from_le_bytes(count) as u128) * (T::WIDTH as u128);
+		let expected = FROZEN_HEADER_LEN as u128 + body + 8;
+		if (bytes.len() as u128) < expected {
+			return Err(FormatError::Truncated);
+		}
+		if bytes.len() as u128 > expected {
+			return Err(FormatError::Layout);
+		}
+		
+		let end = bytes.len() - 8;
+		let mut sum = [0; 8];
+		sum.copy_from_slice(&bytes[end..]);
+		if u64::from_le_bytes(sum) != checksum(&bytes[..end]) {
+			return Err(FormatError::Checksum);
+		}
+		
+		let keys = bytes[FROZEN_HEADER_LEN..end].chunks_exact(T::WIDTH).map(T::decode).collect();
+		let tree = FrozenTree { keys };
+		
+		let mut iter = tree.iter();
+		if let Some(mut prev) = iter.next() {
+			for (index, key) in iter.enumerate() {
+				if key < prev {
+					return Err(FormatError::Invariant(InvariantError::Order { index: index + 1 }));
+				}
+				prev = key;
+			}
+		}
+		Ok(tree)
+	}
+}
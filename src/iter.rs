@@ -1,7 +1,9 @@
-use std::collections::VecDeque;
-use std::iter::FromIterator;
-use std::ops::RangeBounds;
-use std::collections::vec_deque::Drain;
+use crate::tree::BinaryTree;
+use alloc::collections::VecDeque;
+use alloc::collections::vec_deque::Drain;
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+use core::ops::RangeBounds;
 
 /// **Realisation of Iterator for Tree**
 /// ---------------------------------------
@@ -847,3 +849,55 @@ impl<T> FromIterator<T> for TreeIter<T>
 }
 
 
+
+/// *English*: **ExtractIf** is a *lazy* iterator returned by **BinaryTree::extract_if()**.
+/// Every call of **next()** walks to the next unvisited node and removes it in place,
+/// if predicate returns *true*. Nodes, which weren't visited, stay in tree,
+/// when iterator is dropped.
+///
+/// *Russian*: **ExtractIf** - *ленивый* итератор, который возвращает **BinaryTree::extract_if()**.
+/// Каждый вызов **next()** идёт к следующему непосещённому узлу и удаляет его на месте,
+/// если предикат возвращает *true*. Непосещённые узлы остаются в дереве,
+/// когда итератор уничтожается.
+
+#[derive(Debug)]
+pub struct ExtractIf<'a, T, F>
+	where T: Copy + Clone + Ord + Eq, F: FnMut(&T) -> bool
+{
+	pub(crate) tree: &'a mut BinaryTree<T>,
+	pub(crate) pred: F,
+	pub(crate) last: Option<T>,
+	pub(crate) kept: usize,
+}
+
+impl<'a, T, F> Iterator for ExtractIf<'a, T, F>
+	where T: Copy + Clone + Ord + Eq, F: FnMut(&T) -> bool
+{
+	type Item = T;
+	
+	fn next(&mut self) -> Option<T> {
+		loop {
+			let path = self.tree.top.path_after(self.last.as_ref(), self.kept)?;
+			let node = self.tree.top.at_path_mut(&path);
+			let key = *node.get_key();
+			
+			if self.last != Some(key) {
+				self.last = Some(key);
+				self.kept = 0;
+			}
+			
+			if (self.pred)(&key) {
+				node.remove_here();
+				self.tree.size -= 1;
+				self.tree.check();
+				return Some(key);
+			}
+			self.kept += 1;
+		}
+	}
+	
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(0, Some(self.tree.len()))
+	}
+}
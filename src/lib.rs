@@ -1,13 +1,46 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
 /// *English*: Library with all modules.
-/// User need only tree and iter, so other modules are private.
+/// User need only tree, bounded, static, flat, adaptive, multiway and frozen trees, iter,
+/// sorted sequence, ordered set trait, transactions, queue, stats, render, errors, parallel methods,
+/// binary format, durable and paged trees, bulk loader (with *std* feature),
+/// so other modules are private.
 ///
 /// *Russian*: Библиотека со всеми модулями.
-/// Пользователь использует только самое дерево
-/// и итератор, так что незачем давать доступ
-/// к другим структурам.
+/// Пользователь использует только самое дерево, ограниченное, статическое,
+/// плоское, адаптивное, многопутевое и замороженное деревья, итератор,
+/// отсортированную последовательность, трейт упорядоченного множества, транзакции, очередь,
+/// статистику, рисование, ошибки, параллельные методы, двоичный формат,
+/// надёжное и страничное деревья, массовый загрузчик (с фичей *std*),
+/// так что незачем давать доступ к другим структурам.
+
+extern crate alloc;
 
 mod branch;
 mod node;
+#[cfg(test)]
 mod tests;
+pub mod bounded;
+pub mod btree;
+#[cfg(feature = "std")]
+pub mod bulk;
+#[cfg(feature = "std")]
+pub mod codec;
+#[cfg(feature = "std")]
+pub mod durable;
+pub mod error;
+pub mod fixed;
+pub mod flat;
+pub mod frozen;
 pub mod iter;
+#[cfg(feature = "std")]
+pub mod paged;
+#[cfg(feature = "std")]
+pub mod par;
+pub mod queue;
+pub mod render;
+pub mod seq;
+pub mod set;
+pub mod stats;
+pub mod transaction;
 pub mod tree;
@@ -1,7 +1,14 @@
 use crate::branch::Branch;
-use std::cmp::Ordering;
-use std::collections::VecDeque;
-use std::mem;
+use crate::error::InvariantError;
+use crate::stats::min_height;
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::ops::Bound;
+use core::mem;
 
 /// **Node Realisation**
 /// --------------------
@@ -93,6 +100,28 @@ impl<T> Node<T>
 		}
 	}
 	
+	/// *English*: Methods **branch()** and **branch_mut()** convert node to branch
+	/// like **ignore()**, but return *None* instead of panic.
+	///
+	/// *Russian*: Методы **branch()** и **branch_mut()** конвертируют узел в ветвь
+	/// как **ignore()**, но вместо паники возвращают *None*.
+	
+	#[inline]
+	pub(crate) fn branch(&self) -> Option<&Branch<T>> {
+		match *self {
+			Node::Empty => None,
+			Node::NonEmpty(ref branch) => Some(branch),
+		}
+	}
+	
+	#[inline]
+	pub(crate) fn branch_mut(&mut self) -> Option<&mut Branch<T>> {
+		match *self {
+			Node::Empty => None,
+			Node::NonEmpty(ref mut branch) => Some(branch),
+		}
+	}
+	
 	/// *English*: *Get key* from node. *Panic* is possible
 	///
 	/// *Russian*: *Получение ключа* из ветви. Возможна *паника*
@@ -103,47 +132,185 @@ impl<T> Node<T>
 	}
 	
 	/// *English*: Method **insert()** adds value to node.
+	/// Returns depth of the new node (this node has depth 0).
 	///
 	/// *Russian*: Метод **insert()** добавляет значения в узел.
+	/// Возвращает глубину нового узла (глубина этого узла - 0).
 	
-	pub(crate) fn insert(&mut self, val: &T) {
-		match *self {
-			Node::Empty => {
-				*self = Node::NonEmpty(Box::new(Branch {
-					key: (*val).clone(),
-					right: Node::Empty,
-					left: Node::Empty,
-				}))
-			}
-			Node::NonEmpty(ref mut branch) => {
-				if branch.key <= *val {
-					branch.right.insert(val);
-				} else {
-					branch.left.insert(val);
-				}
-			}
+	pub(crate) fn insert(&mut self, val: &T) -> usize {
+		let mut depth = 0;
+		let mut cur = self;
+		while let Node::NonEmpty(ref mut branch) = *cur {
+			cur = if branch.key <= *val {
+				&mut branch.right
+			} else {
+				&mut branch.left
+			};
+			depth += 1;
 		}
+		
+		*cur = Node::NonEmpty(Box::new(Branch {
+			key: (*val).clone(),
+			right: Node::Empty,
+			left: Node::Empty,
+		}));
+		depth
 	}
 	
 	/// *English*: Method **find()** searches for value in subtree of node.
+	/// Value may be any borrowed form of key (like in std collections).
 	/// If the value isn't exists or tree is empty, it'll return *Empty*, else *NonEmpty*
 	///
 	/// *Russian*: Метод **find()** ищет значение в поддереве узла.
+	/// Значение может быть любой заимствованной формой ключа (как в коллекциях std).
 	/// В случае, когда мы *не находим значение, или поддерево пусто*,
 	/// Вернётся *пустой узел*, иначе *узел с эквивалентным значением*.
 	
-	pub(crate) fn find(&self, val: &T) -> &Self {
+	pub(crate) fn find<Q>(&self, val: &Q) -> &Self
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.find_by(|key| key.borrow().cmp(val))
+	}
+	
+	/// *English*: Method **find_by()** descends subtree with comparator, which returns
+	/// ordering of key relative to the target (like **slice::binary_search_by()**).
+	/// Returns any node with *Equal* key or *Empty*, if there is no such node
+	///
+	/// *Russian*: Метод **find_by()** спускается по поддереву с компаратором, который
+	/// возвращает порядок ключа относительно цели (как **slice::binary_search_by()**).
+	/// Возвращает любой узел с ключом *Equal* или *пустой узел*, если такого нет
+	
+	pub(crate) fn find_by<F>(&self, mut cmp: F) -> &Self
+		where F: FnMut(&T) -> Ordering
+	{
 		let mut find = self;
 		while let Node::NonEmpty(ref branch) = *find {
-			match val.cmp(find.get_key()) {
-				Ordering::Less => find = &branch.left,
-				Ordering::Greater => find = &branch.right,
+			match cmp(&branch.key) {
+				Ordering::Greater => find = &branch.left,
+				Ordering::Less => find = &branch.right,
 				Ordering::Equal => return find,
 			}
 		}
 		&Node::Empty
 	}
 	
+	/// *English*: Mutable version of **find()**
+	///
+	/// *Russian*: Изменяемая версия **find()**
+	
+	pub(crate) fn find_mut<Q>(&mut self, val: &Q) -> &mut Self
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		let mut find = self;
+		loop {
+			match *find {
+				Node::Empty => return find,
+				Node::NonEmpty(ref branch) => match val.cmp(branch.key.borrow()) {
+					Ordering::Less => find = &mut find.ignore_mut().left,
+					Ordering::Greater => find = &mut find.ignore_mut().right,
+					Ordering::Equal => return find,
+				}
+			}
+		}
+	}
+	
+	/// *English*: Smallest key, for which predicate is *false*, if predicate
+	/// is *true* for the beginning of the sorted keys and *false* for the rest
+	///
+	/// *Russian*: Наименьший ключ, для которого предикат *ложен*, если предикат
+	/// *истинен* для начала отсортированных ключей и *ложен* для остальных
+	
+	pub(crate) fn partition_point<P>(&self, mut pred: P) -> Option<&T>
+		where P: FnMut(&T) -> bool
+	{
+		let mut cur = self;
+		let mut found = None;
+		while let Node::NonEmpty(ref branch) = *cur {
+			if pred(&branch.key) {
+				cur = &branch.right;
+			} else {
+				found = Some(&branch.key);
+				cur = &branch.left;
+			}
+		}
+		found
+	}
+	
+	/// *English*: Biggest key, which is not *after* bound: `<= val` for *Included*,
+	/// `< val` for *Excluded* and the max key for *Unbounded*
+	///
+	/// *Russian*: Наибольший ключ, который не *после* границы: `<= val` для *Included*,
+	/// `< val` для *Excluded* и наибольший ключ для *Unbounded*
+	
+	pub(crate) fn upper_key<Q>(&self, bound: Bound<&Q>) -> Option<&T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		let mut cur = self;
+		let mut found = None;
+		while let Node::NonEmpty(ref branch) = *cur {
+			let fits = match bound {
+				Bound::Included(val) => branch.key.borrow() <= val,
+				Bound::Excluded(val) => branch.key.borrow() < val,
+				Bound::Unbounded => true,
+			};
+			
+			if fits {
+				found = Some(&branch.key);
+				cur = &branch.right;
+			} else {
+				cur = &branch.left;
+			}
+		}
+		found
+	}
+	
+	/// *English*: *In-order* walk, which collects keys, for which comparator returns *Equal*.
+	/// Comparator must return *Less* for keys before the range and *Greater* for keys after it,
+	/// so only subtrees that may contain keys from range are visited.
+	/// Takes O(height + number of found keys).
+	///
+	/// *Russian*: *Центрированный* обход, который собирает ключи, для которых компаратор
+	/// возвращает *Equal*. Компаратор должен возвращать *Less* для ключей перед диапазоном
+	/// и *Greater* для ключей после него, так что посещаются только поддеревья,
+	/// в которых могут быть ключи из диапазона. Занимает O(высоты + количества найденных ключей).
+	
+	pub(crate) fn range_by<F>(&self, mut cmp: F) -> VecDeque<T>
+		where F: FnMut(&T) -> Ordering
+	{
+		let mut found = VecDeque::new();
+		let mut stack = Vec::new();
+		let mut cur = self;
+		
+		loop {
+			while let Node::NonEmpty(ref branch) = *cur {
+				let order = cmp(&branch.key);
+				stack.push((branch, order));
+				
+				// keys equal to this one may lay on both sides,
+				// so only keys before the range cut left branch
+				cur = match order {
+					Ordering::Less => &Node::Empty,
+					_ => &branch.left,
+				};
+			}
+			
+			match stack.pop() {
+				None => break,
+				Some((branch, order)) => {
+					if order == Ordering::Equal {
+						found.push_back(branch.key);
+					}
+					
+					cur = match order {
+						Ordering::Greater => &Node::Empty,
+						_ => &branch.right,
+					};
+				}
+			}
+		}
+		found
+	}
+	
 	/// *English*: Search min value in node. If it's empty return *Empty*
 	///
 	/// *Russian*: Поиск минимального узла в ветви.
@@ -151,8 +318,11 @@ impl<T> Node<T>
 	
 	pub(crate) fn min(&self) -> &Self {
 		let mut min = self;
-		while min.ignore().left != Node::Empty {
-			min = &min.ignore().left;
+		while let Node::NonEmpty(ref branch) = *min {
+			if branch.left == Node::Empty {
+				break;
+			}
+			min = &branch.left;
 		}
 		min
 	}
@@ -164,12 +334,438 @@ impl<T> Node<T>
 	
 	pub(crate) fn max(&self) -> &Self {
 		let mut max = self;
-		while max.ignore().right != Node::Empty {
-			max = &max.ignore().right;
+		while let Node::NonEmpty(ref branch) = *max {
+			if branch.right == Node::Empty {
+				break;
+			}
+			max = &branch.right;
 		}
 		max
 	}
 	
+	/// *English*: Number of levels in subtree. Empty node has height 0.
+	/// Uses BFS, so there is no recursion even for degenerate trees.
+	///
+	/// *Russian*: Количество уровней в поддереве. Пустой узел имеет высоту 0.
+	/// Используется обход в ширину, так что рекурсии нет даже для вырожденных деревьев.
+	
+	pub(crate) fn height(&self) -> usize {
+		let mut height = 0;
+		let mut level = Vec::new();
+		let mut next = Vec::new();
+		
+		if let Node::NonEmpty(ref branch) = *self {
+			level.push(branch);
+		}
+		
+		while !level.is_empty() {
+			height += 1;
+			for branch in level.drain(..) {
+				if let Node::NonEmpty(ref left) = branch.left {
+					next.push(left);
+				}
+				if let Node::NonEmpty(ref right) = branch.right {
+					next.push(right);
+				}
+			}
+			mem::swap(&mut level, &mut next);
+		}
+		height
+	}
+	
+	/// *English*: Depth of the first node with value (top node has depth 0).
+	/// If there is no such value, returns *None*
+	///
+	/// *Russian*: Глубина первого узла со значением (глубина головного узла - 0).
+	/// Если значения нет, возвращает *None*
+	
+	pub(crate) fn depth_of(&self, val: &T) -> Option<usize> {
+		let mut find = self;
+		let mut depth = 0;
+		while let Node::NonEmpty(ref branch) = *find {
+			match val.cmp(&branch.key) {
+				Ordering::Less => find = &branch.left,
+				Ordering::Greater => find = &branch.right,
+				Ordering::Equal => return Some(depth),
+			}
+			depth += 1;
+		}
+		None
+	}
+	
+	/// *English*: Number of nodes without children in subtree
+	///
+	/// *Russian*: Количество узлов без потомков в поддереве
+	
+	pub(crate) fn leaf_count(&self) -> usize {
+		let mut count = 0;
+		let mut stack = vec![self];
+		while let Some(node) = stack.pop() {
+			if let Node::NonEmpty(ref branch) = *node {
+				if branch.left == Node::Empty && branch.right == Node::Empty {
+					count += 1;
+				} else {
+					stack.push(&branch.left);
+					stack.push(&branch.right);
+				}
+			}
+		}
+		count
+	}
+	
+	/// *English*: Checks that in-order walk is sorted (left ≤ key ≤ right in every node).
+	/// If *unique* is true, equal keys are forbidden.
+	/// Returns number of nodes. Walk is iterative, so degenerate trees are fine.
+	///
+	/// *Russian*: Проверяет, что обход отсортирован (левый ≤ ключ ≤ правый в каждом узле).
+	/// Если *unique* равен true, равные ключи запрещены.
+	/// Возвращает количество узлов. Обход итеративный, так что вырожденные деревья не страшны.
+	
+	pub(crate) fn validate(&self, unique: bool) -> Result<usize, InvariantError> {
+		let mut count = 0;
+		let mut prev: Option<&T> = None;
+		let mut stack = Vec::new();
+		let mut cur = self;
+		
+		loop {
+			while let Node::NonEmpty(ref branch) = *cur {
+				stack.push(branch);
+				cur = &branch.left;
+			}
+			
+			match stack.pop() {
+				None => return Ok(count),
+				Some(branch) => {
+					if let Some(prev) = prev {
+						if branch.key < *prev {
+							return Err(InvariantError::Order { index: count });
+						}
+						if unique && branch.key == *prev {
+							return Err(InvariantError::Duplicate { index: count });
+						}
+					}
+					prev = Some(&branch.key);
+					count += 1;
+					cur = &branch.right;
+				}
+			}
+		}
+	}
+	
+	/// *English*: Left rotation. Right child becomes the top of subtree.
+	/// Node and it's right child must be not empty.
+	///
+	/// *Russian*: Левый поворот. Правый потомок становится вершиной поддерева.
+	/// Узел и его правый потомок должны быть не пусты.
+	///
+	/// ```text
+	///     x              r
+	///    / \            / \
+	///   a   r    =>    x   c
+	///      / \        / \
+	///     b   c      a   b
+	/// ```
+	
+	pub(crate) fn rotate_left(&mut self) {
+		let mut top = mem::take(self);
+		let mut pivot = mem::take(&mut top.ignore_mut().right);
+		top.ignore_mut().right = mem::take(&mut pivot.ignore_mut().left);
+		pivot.ignore_mut().left = top;
+		*self = pivot;
+	}
+	
+	/// *English*: Right rotation. Left child becomes the top of subtree.
+	/// Node and it's left child must be not empty.
+	///
+	/// *Russian*: Правый поворот. Левый потомок становится вершиной поддерева.
+	/// Узел и его левый потомок должны быть не пусты.
+	///
+	/// ```text
+	///       x          l
+	///      / \        / \
+	///     l   c  =>  a   x
+	///    / \            / \
+	///   a   b          b   c
+	/// ```
+	
+	pub(crate) fn rotate_right(&mut self) {
+		let mut top = mem::take(self);
+		let mut pivot = mem::take(&mut top.ignore_mut().left);
+		top.ignore_mut().left = mem::take(&mut pivot.ignore_mut().right);
+		pivot.ignore_mut().right = top;
+		*self = pivot;
+	}
+	
+	/// *English*: Converts subtree to the *vine* (every node has only right child)
+	/// with right rotations. Returns number of nodes. O(n) time, O(1) memory.
+	///
+	/// *Russian*: Превращает поддерево в *лозу* (у каждого узла есть только правый
+	/// потомок) правыми поворотами. Возвращает количество узлов. O(n) времени, O(1) памяти.
+	
+	pub(crate) fn make_vine(&mut self) -> usize {
+		let mut size = 0;
+		let mut cur = self;
+		while *cur != Node::Empty {
+			if cur.ignore().left != Node::Empty {
+				cur.rotate_right();
+			} else {
+				size += 1;
+				cur = &mut cur.ignore_mut().right;
+			}
+		}
+		size
+	}
+	
+	/// *English*: Makes *count* left rotations along the vine (DSW compression)
+	///
+	/// *Russian*: Делает *count* левых поворотов вдоль лозы (сжатие DSW)
+	
+	fn compress(&mut self, count: usize) {
+		let mut cur = self;
+		for _ in 0..count {
+			cur.rotate_left();
+			cur = &mut cur.ignore_mut().right;
+		}
+	}
+	
+	/// *English*: *Scapegoat* search after insertion of *val*, which made the tree too high.
+	/// Goes back from the new node to the top and rebalances the lowest subtree,
+	/// which path to the new node is longer than *factor × (⌊log2(size)⌋ + 1)*.
+	/// Returns *true* if something was rebuilt. Path is kept in a vector (not on the call stack),
+	/// so degenerate trees are fine too. Only siblings of the path are counted,
+	/// so it's amortized O(log n).
+	///
+	/// *Russian*: Поиск *козла отпущения* после вставки *val*, сделавшей дерево слишком высоким.
+	/// Идёт от нового узла к вершине и балансирует самое нижнее поддерево,
+	/// путь от которого до нового узла длиннее *factor × (⌊log2(size)⌋ + 1)*.
+	/// Возвращает *true*, если что-то было перестроено. Путь хранится в векторе (а не на стеке
+	/// вызовов), так что вырожденные деревья тоже в порядке. Считаются только соседи пути,
+	/// так что это амортизированное O(log n).
+	
+	pub(crate) fn rebalance_path(&mut self, val: &T, factor: f64) -> bool {
+		let mut siblings = vec![];
+		let mut cur = &*self;
+		while let Node::NonEmpty(ref branch) = *cur {
+			if branch.key <= *val {
+				siblings.push(&branch.left);
+				cur = &branch.right;
+			} else {
+				siblings.push(&branch.right);
+				cur = &branch.left;
+			}
+		}
+		
+		let mut size = 0;
+		let mut scapegoat = None;
+		for (path, sibling) in siblings.iter().rev().enumerate() {
+			size += sibling.count() + 1;
+			if (path + 1) as f64 > factor * min_height(size) as f64 {
+				scapegoat = Some(siblings.len() - path - 1);
+				break;
+			}
+		}
+		
+		let depth = match scapegoat {
+			Some(depth) => depth,
+			None => return false,
+		};
+		
+		let mut cur = self;
+		for _ in 0..depth {
+			cur = if cur.ignore().key <= *val {
+				&mut cur.ignore_mut().right
+			} else {
+				&mut cur.ignore_mut().left
+			};
+		}
+		cur.rebalance();
+		true
+	}
+	
+	/// *English*: *Day-Stout-Warren* algorithm. Rebuilds subtree to
+	/// the perfectly balanced one (all levels except the last are full).
+	/// O(n) time, O(1) memory. Order of keys isn't changed.
+	///
+	/// *Russian*: *Алгоритм Дэя-Стаута-Уоррена*. Перестраивает поддерево
+	/// в идеально сбалансированное (все уровни, кроме последнего, заполнены).
+	/// O(n) времени, O(1) памяти. Порядок ключей не меняется.
+	
+	pub(crate) fn rebalance(&mut self) {
+		let size = self.make_vine();
+		let full = (1 << (usize::BITS - (size + 1).leading_zeros() - 1)) - 1;
+		self.compress(size - full);
+		
+		let mut rest = full;
+		while rest > 1 {
+			rest /= 2;
+			self.compress(rest);
+		}
+	}
+	
+	/// *English*: Builds *balanced* subtree from sorted slice in O(n):
+	/// middle element becomes the key, halves become the children.
+	///
+	/// *Russian*: Строит *сбалансированное* поддерево из отсортированного среза за O(n):
+	/// средний элемент становится ключом, половины становятся потомками.
+	
+	pub(crate) fn from_sorted(src: &[T]) -> Self {
+		if src.is_empty() {
+			return Node::Empty;
+		}
+		
+		let mid = src.len() / 2;
+		Node::NonEmpty(Box::new(Branch {
+			key: src[mid],
+			right: Node::from_sorted(&src[mid + 1..]),
+			left: Node::from_sorted(&src[..mid]),
+		}))
+	}
+	
+	/// *English*: Removes min node of subtree in O(height): it's replaced by it's right child.
+	/// Returns removed key or *None* if subtree is empty.
+	///
+	/// *Russian*: Удаляет минимальный узел поддерева за O(высоты): его место занимает
+	/// правый потомок. Возвращает удалённый ключ или *None*, если поддерево пусто.
+	
+	pub(crate) fn pop_min(&mut self) -> Option<T> {
+		let mut min = self;
+		while min.branch().is_some_and(|branch| branch.left != Node::Empty) {
+			min = &mut min.ignore_mut().left;
+		}
+		
+		match mem::take(min) {
+			Node::Empty => None,
+			Node::NonEmpty(branch) => {
+				let branch = *branch;
+				*min = branch.right;
+				Some(branch.key)
+			}
+		}
+	}
+	
+	/// *English*: Removes max node of subtree in O(height): it's replaced by it's left child.
+	/// Returns removed key or *None* if subtree is empty.
+	///
+	/// *Russian*: Удаляет максимальный узел поддерева за O(высоты): его место занимает
+	/// левый потомок. Возвращает удалённый ключ или *None*, если поддерево пусто.
+	
+	pub(crate) fn pop_max(&mut self) -> Option<T> {
+		let mut max = self;
+		while max.branch().is_some_and(|branch| branch.right != Node::Empty) {
+			max = &mut max.ignore_mut().right;
+		}
+		
+		match mem::take(max) {
+			Node::Empty => None,
+			Node::NonEmpty(branch) => {
+				let branch = *branch;
+				*max = branch.left;
+				Some(branch.key)
+			}
+		}
+	}
+	
+	/// *English*: Removes key of this node in O(height), moving only one node:
+	/// if one of children is empty, node is replaced with another child,
+	/// else it takes the key of the min node in the right subtree.
+	/// In-order sequence of other keys stays the same. Returns removed key or *None* for empty node.
+	///
+	/// *Russian*: Удаляет ключ этого узла за O(высоты), перемещая только один узел:
+	/// если один из потомков пуст, узел заменяется другим потомком,
+	/// иначе он забирает ключ наименьшего узла правого поддерева.
+	/// Порядок остальных ключей не меняется. Возвращает удалённый ключ или *None* для пустого узла.
+	
+	pub(crate) fn remove_here(&mut self) -> Option<T> {
+		let branch = match *self {
+			Node::Empty => return None,
+			Node::NonEmpty(ref mut branch) => branch,
+		};
+		
+		if branch.left != Node::Empty && branch.right != Node::Empty {
+			let next = branch.right.pop_min()?;
+			return Some(mem::replace(&mut branch.key, next));
+		}
+		
+		let branch = match mem::take(self) {
+			Node::Empty => return None,
+			Node::NonEmpty(branch) => *branch,
+		};
+		*self = if branch.left == Node::Empty { branch.right } else { branch.left };
+		Some(branch.key)
+	}
+	
+	/// *English*: Path (*false* - left, *true* - right) to the node, which goes in *in-order*
+	/// walk after *skip* keys equal to *last* (or to the first node if *last* is *None*).
+	/// All the keys before *last* are skipped too. Returns *None* if there is no such node.
+	/// Takes O(height + skip).
+	///
+	/// *Russian*: Путь (*false* - влево, *true* - вправо) к узлу, который идёт при
+	/// *центрированном* обходе после *skip* ключей, равных *last* (или к первому узлу,
+	/// если *last* - *None*). Все ключи перед *last* тоже пропускаются.
+	/// Возвращает *None*, если такого узла нет. Занимает O(высоты + skip).
+	
+	pub(crate) fn path_after(&self, last: Option<&T>, mut skip: usize) -> Option<Vec<bool>> {
+		let mut path = Vec::new();
+		let mut stack = Vec::new();
+		let mut cur = self;
+		
+		loop {
+			while let Node::NonEmpty(ref branch) = *cur {
+				if last.is_some_and(|last| branch.key < *last) {
+					path.push(true);
+					cur = &branch.right;
+				} else {
+					stack.push((branch, path.len()));
+					path.push(false);
+					cur = &branch.left;
+				}
+			}
+			
+			let (branch, depth) = stack.pop()?;
+			path.truncate(depth);
+			if skip == 0 || last != Some(&branch.key) {
+				return Some(path);
+			}
+			
+			skip -= 1;
+			path.push(true);
+			cur = &branch.right;
+		}
+	}
+	
+	/// *English*: Node at path from **path_after()**
+	///
+	/// *Russian*: Узел по пути из **path_after()**
+	
+	pub(crate) fn at_path_mut(&mut self, path: &[bool]) -> &mut Self {
+		let mut cur = self;
+		for &right in path {
+			cur = if right {
+				&mut cur.ignore_mut().right
+			} else {
+				&mut cur.ignore_mut().left
+			};
+		}
+		cur
+	}
+	
+	/// *English*: Number of nodes in subtree
+	///
+	/// *Russian*: Количество узлов в поддереве
+	
+	pub(crate) fn count(&self) -> usize {
+		let mut count = 0;
+		let mut stack = vec![self];
+		while let Some(node) = stack.pop() {
+			if let Node::NonEmpty(ref branch) = *node {
+				count += 1;
+				stack.push(&branch.left);
+				stack.push(&branch.right);
+			}
+		}
+		count
+	}
+	
 	/// *English*: *Deep-First-Search (DFS)* realisation.
 	/// Returns VecDeque with keys.
 	///
@@ -213,11 +809,13 @@ impl<T> Node<T>
 	///
 	/// *Russian*: Удаление узла и сохранение ключей его подузлов.
 	
-	pub(crate) fn remove(&mut self, val: &T) -> (bool, Vec<T>) {
+	pub(crate) fn remove<Q>(&mut self, val: &Q) -> (bool, Vec<T>)
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
 		let mut find = self;
 		let mut search = false;
 		while *find != Node::Empty {
-			match val.cmp(find.get_key()) {
+			match val.cmp(find.get_key().borrow()) {
 				Ordering::Less => find = &mut find.ignore_mut().left,
 				Ordering::Greater => find = &mut find.ignore_mut().right,
 				Ordering::Equal => {
@@ -234,7 +832,7 @@ impl<T> Node<T>
 			find.rec_drop();
 		} else {
 			if let Node::NonEmpty(ref node) = *find {
-				if node.key == *val {
+				if node.key.borrow() == val {
 					search = true;
 					safe.extend(node.left.walk());
 					safe.extend(node.right.walk());
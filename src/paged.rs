@@ -0,0 +1,1022 @@
+use crate::codec::{checksum, FixedKey};
+use crate::error::FormatError;
+use crate::tree::DuplicatePolicy;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::mem;
+use std::ops::{Bound, RangeBounds};
+use std::path::Path;
+
+/// *English*: Magic bytes of paged tree file
+///
+/// *Russian*: Магические байты файла страничного дерева
+
+pub const PAGED_MAGIC: [u8; 4] = *b"BNPG";
+
+/// *English*: Default size of one page (and of one node) in bytes
+///
+/// *Russian*: Размер одной страницы (и одного узла) в байтах по-умолчанию
+
+pub const DEFAULT_PAGE_SIZE: usize = 4096;
+
+/// *English*: Default number of pages, which are kept in memory
+///
+/// *Russian*: Количество страниц, хранимых в памяти, по-умолчанию
+
+pub const DEFAULT_CACHE_PAGES: usize = 256;
+
+/// *English*: Page starts with kind (1 byte), key count (2 bytes) and padding,
+/// and ends with checksum (8 bytes)
+///
+/// *Russian*: Страница начинается с вида (1 байт), количества ключей (2 байта) и
+/// выравнивания, а заканчивается контрольной суммой (8 байт)
+
+const PAGE_HEADER: usize = 8;
+const PAGE_CHECKSUM: usize = 8;
+
+/// *English*: Length of tree header at the start of meta page
+///
+/// *Russian*: Длина заголовка дерева в начале мета-страницы
+
+const META_LEN: usize = 48;
+
+/// *English*: Kinds of pages
+///
+/// *Russian*: Виды страниц
+
+const KIND_FREE: u8 = 0;
+const KIND_LEAF: u8 = 1;
+const KIND_INNER: u8 = 2;
+const KIND_META: u8 = 3;
+
+/// *English*: Node of paged tree. It's a copy of page, changes must be written back.
+/// Node is a leaf, if it has no children, otherwise it has one child more than keys.
+///
+/// *Russian*: Узел страничного дерева. Это копия страницы, изменения нужно записать обратно.
+/// Узел - лист, если у него нет потомков, иначе потомков на один больше, чем ключей.
+
+#[derive(Debug, Clone)]
+struct PageNode<T> {
+	keys: Vec<T>,
+	children: Vec<u64>,
+}
+
+impl<T> PageNode<T> {
+	#[inline]
+	fn is_leaf(&self) -> bool {
+		self.children.is_empty()
+	}
+}
+
+/// *English*: Cached page and its state
+///
+/// *Russian*: Страница в кэше и её состояние
+
+#[derive(Debug)]
+struct Cached<T> {
+	node: PageNode<T>,
+	dirty: bool,
+	tick: u64,
+}
+
+/// *English*: **Pager** reads and writes pages of file and keeps at most *capacity*
+/// of them in memory. The least recently used page is evicted (and written,
+/// if it was changed). Page 0 keeps meta data of the tree.
+///
+/// *Russian*: **Pager** читает и пишет страницы файла и держит в памяти не больше
+/// *capacity* из них. Вытесняется страница, которая использовалась раньше всех
+/// (и записывается, если она была изменена). Страница 0 хранит метаданные дерева.
+
+#[derive(Debug)]
+struct Pager<T> {
+	file: File,
+	page_size: usize,
+	capacity: usize,
+	cache: HashMap<u64, Cached<T>>,
+	lru: BTreeMap<u64, u64>,
+	tick: u64,
+	page_count: u64,
+	free_head: u64,
+}
+
+impl<T: FixedKey> Pager<T> {
+	/// *English*: Max keys in one page
+	///
+	/// *Russian*: Наибольшее количество ключей в одной странице
+	
+	fn max_keys(page_size: usize) -> usize {
+		// keys * width + (keys + 1) * 8 must fit
+		let room = page_size.saturating_sub(PAGE_HEADER + PAGE_CHECKSUM + 8);
+		let keys = (room / (T::WIDTH + 8)).min(u16::MAX as usize);
+		// odd number of keys splits into two equal halves and the middle one
+		if keys & 1 == 0 { keys.saturating_sub(1) } else { keys }
+	}
+	
+	fn seal(page: &mut [u8]) {
+		let end = page.len() - PAGE_CHECKSUM;
+		let sum = checksum(&page[..end]);
+		page[end..].copy_from_slice(&sum.to_le_bytes());
+	}
+	
+	fn read_raw(&mut self, id: u64) -> Result<Vec<u8>, FormatError> {
+		if id >= self.page_count {
+			return Err(FormatError::Layout);
+		}
+		
+		let mut page = vec![0; self.page_size];
+		self.file.seek(SeekFrom::Start(id * self.page_size as u64))?;
+		self.file.read_exact(&mut page)?;
+		
+		let end = page.len() - PAGE_CHECKSUM;
+		let mut sum = [0; 8];
+		sum.copy_from_slice(&page[end..]);
+		if u64::from_le_bytes(sum) != checksum(&page[..end]) {
+			return Err(FormatError::Checksum);
+		}
+		Ok(page)
+	}
+	
+	fn write_raw(&mut self, id: u64, page: &mut [u8]) -> Result<(), FormatError> {
+		Pager::<T>::seal(page);
+		self.file.seek(SeekFrom::Start(id * self.page_size as u64))?;
+		self.file.write_all(page)?;
+		Ok(())
+	}
+	
+	fn encode(&self, node: &PageNode<T>) -> Vec<u8> {
+		let mut page = vec![0; self.page_size];
+		page[0] = if node.is_leaf() { KIND_LEAF } else { KIND_INNER };
+		page[1..3].copy_from_slice(&(node.keys.len() as u16).to_le_bytes());
+		
+		let mut at = PAGE_HEADER;
+		for key in node.keys.iter() {
+			key.encode(&mut page[at..at + T::WIDTH]);
+			at += T::WIDTH;
+		}
+		for child in node.children.iter() {
+			page[at..at + 8].copy_from_slice(&child.to_le_bytes());
+			at += 8;
+		}
+		page
+	}
+	
+	fn decode(&self, page: &[u8]) -> Result<PageNode<T>, FormatError> {
+		let count = usize::from(u16::from_le_bytes([page[1], page[2]]));
+		if count > Pager::<T>::max_keys(self.page_size) {
+			return Err(FormatError::Layout);
+		}
+		
+		let mut at = PAGE_HEADER;
+		let mut keys = Vec::with_capacity(count);
+		for _ in 0..count {
+			keys.push(T::decode(&page[at..at + T::WIDTH]));
+			at += T::WIDTH;
+		}
+		
+		let mut children = Vec::new();
+		match page[0] {
+			KIND_LEAF => (),
+			KIND_INNER => for _ in 0..=count {
+				let mut id = [0; 8];
+				id.copy_from_slice(&page[at..at + 8]);
+				children.push(u64::from_le_bytes(id));
+				at += 8;
+			},
+			_ => return Err(FormatError::Layout),
+		}
+		Ok(PageNode { keys, children })
+	}
+	
+	fn touch(&mut self, id: u64) {
+		self.tick += 1;
+		if let Some(cached) = self.cache.get_mut(&id) {
+			self.lru.remove(&cached.tick);
+			cached.tick = self.tick;
+			self.lru.insert(self.tick, id);
+		}
+	}
+	
+	/// *English*: Makes room for one more page in cache
+	///
+	/// *Russian*: Освобождает в кэше место для ещё одной страницы
+	
+	fn evict(&mut self) -> Result<(), FormatError> {
+		while self.cache.len() >= self.capacity {
+			let (tick, id) = match self.lru.iter().next() {
+				Some((tick, id)) => (*tick, *id),
+				None => break,
+			};
+			self.lru.remove(&tick);
+			if let Some(cached) = self.cache.remove(&id) {
+				if cached.dirty {
+					let mut page = self.encode(&cached.node);
+					self.write_raw(id, &mut page)?;
+				}
+			}
+		}
+		Ok(())
+	}
+	
+	/// *English*: Copy of node at page *id*
+	///
+	/// *Russian*: Копия узла на странице *id*
+	
+	fn read(&mut self, id: u64) -> Result<PageNode<T>, FormatError> {
+		if self.cache.contains_key(&id) {
+			self.touch(id);
+			return Ok(self.cache[&id].node.clone());
+		}
+		
+		let page = self.read_raw(id)?;
+		let node = self.decode(&page)?;
+		self.evict()?;
+		self.cache.insert(id, Cached { node: node.clone(), dirty: false, tick: 0 });
+		self.touch(id);
+		Ok(node)
+	}
+	
+	/// *English*: Puts node to page *id*. It reaches file on eviction or flush.
+	///
+	/// *Russian*: Кладёт узел на страницу *id*. В файл он попадёт при вытеснении или сбросе.
+	
+	fn write(&mut self, id: u64, node: PageNode<T>) -> Result<(), FormatError> {
+		if !self.cache.contains_key(&id) {
+			self.evict()?;
+		}
+		let tick = self.cache.get(&id).map_or(0, |cached| cached.tick);
+		self.cache.insert(id, Cached { node, dirty: true, tick });
+		self.touch(id);
+		Ok(())
+	}
+	
+	/// *English*: New page from free list or from the end of file
+	///
+	/// *Russian*: Новая страница из списка свободных или с конца файла
+	
+	fn alloc(&mut self) -> Result<u64, FormatError> {
+		if self.free_head == 0 {
+			self.page_count += 1;
+			return Ok(self.page_count - 1);
+		}
+		
+		let id = self.free_head;
+		let page = self.read_raw(id)?;
+		if page[0] != KIND_FREE {
+			return Err(FormatError::Layout);
+		}
+		let mut next = [0; 8];
+		next.copy_from_slice(&page[PAGE_HEADER..PAGE_HEADER + 8]);
+		self.free_head = u64::from_le_bytes(next);
+		Ok(id)
+	}
+	
+	/// *English*: Returns page to free list
+	///
+	/// *Russian*: Возвращает страницу в список свободных
+	
+	fn free(&mut self, id: u64) -> Result<(), FormatError> {
+		if let Some(cached) = self.cache.remove(&id) {
+			self.lru.remove(&cached.tick);
+		}
+		
+		let mut page = vec![0; self.page_size];
+		page[0] = KIND_FREE;
+		page[PAGE_HEADER..PAGE_HEADER + 8].copy_from_slice(&self.free_head.to_le_bytes());
+		self.write_raw(id, &mut page)?;
+		self.free_head = id;
+		Ok(())
+	}
+	
+	/// *English*: Writes all changed pages
+	///
+	/// *Russian*: Записывает все изменённые страницы
+	
+	fn flush(&mut self) -> Result<(), FormatError> {
+		let mut dirty = self.cache.iter()
+			.filter(|(_, cached)| cached.dirty)
+			.map(|(id, _)| *id)
+			.collect::<Vec<u64>>();
+		dirty.sort_unstable();
+		
+		for id in dirty {
+			let mut page = self.encode(&self.cache[&id].node);
+			self.write_raw(id, &mut page)?;
+			if let Some(cached) = self.cache.get_mut(&id) {
+				cached.dirty = false;
+			}
+		}
+		Ok(())
+	}
+	
+	/// *English*: Forgets all pages except meta
+	///
+	/// *Russian*: Забывает все страницы, кроме метаданных
+	
+	fn reset(&mut self) -> Result<(), FormatError> {
+		self.cache.clear();
+		self.lru.clear();
+		self.free_head = 0;
+		self.page_count = 1;
+		self.file.set_len(self.page_size as u64)?;
+		Ok(())
+	}
+}
+
+/// **Paged Tree**
+/// --------------
+///
+/// *English*: **PagedTree** is a B-tree in a file for sets, which don't fit in memory.
+/// Every node takes one page of fixed size, only *cache_pages* pages are kept
+/// in memory at once (the least recently used one is evicted). It has the same
+/// semantics as **BinaryTree**: duplicates are kept after equal keys or handled
+/// by **DuplicatePolicy**, **remove()** deletes one occurrence, iteration is sorted.
+/// Every page has a checksum, so damaged file gives *FormatError* instead of wrong
+/// answers. Changes reach the file on eviction, **flush()** and drop; it's not
+/// a crash-safe storage (use **DurableTree** for that).
+///
+/// *Russian*: **PagedTree** - это B-дерево в файле для множеств, которые не помещаются
+/// в память. Каждый узел занимает одну страницу фиксированного размера, в памяти
+/// одновременно хранится лишь *cache_pages* страниц (вытесняется та, которая
+/// использовалась раньше всех). Семантика такая же, как у **BinaryTree**: повторы
+/// хранятся после равных ключей или обрабатываются **DuplicatePolicy**, **remove()**
+/// удаляет одно вхождение, обход отсортирован. У каждой страницы есть контрольная
+/// сумма, так что повреждённый файл даёт *FormatError*, а не неверные ответы.
+/// Изменения попадают в файл при вытеснении, **flush()** и удалении дерева;
+/// это не хранилище, устойчивое к сбоям (для этого есть **DurableTree**).
+///
+/// # Example
+///
+/// ```
+/// use binartree::paged::PagedTree;
+///
+/// let path = std::env::temp_dir().join(format!("binartree-doc-paged-{}", std::process::id()));
+/// {
+///     let mut tree = PagedTree::<u64>::create(&path, 512, 4).unwrap();
+///     for key in (0..1000).rev() {
+///         tree.insert(&key).unwrap();
+///     }
+///     tree.remove(&500).unwrap();
+///     assert!(tree.cached_pages() <= 4);
+/// }
+///
+/// let tree = PagedTree::<u64>::open(&path, 4).unwrap();
+/// assert_eq!(tree.len(), 999);
+/// assert_eq!(tree.range(498..503).collect::<Result<Vec<u64>, _>>(), Ok(vec![498, 499, 501, 502]));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+
+#[derive(Debug)]
+pub struct PagedTree<T>
+	where T: Copy + Clone + Ord + Eq + FixedKey
+{
+	pager: RefCell<Pager<T>>,
+	root: u64,
+	size: usize,
+	degree: usize,
+	policy: DuplicatePolicy,
+}
+
+impl<T> PagedTree<T>
+	where T: Copy + Clone + Ord + Eq + FixedKey
+{
+	/// *English*: Method **create()** creates empty tree in new file (old file is overwritten).
+	/// Page must hold at least 3 keys and tree header (56 bytes) and fit in *u32*,
+	/// otherwise *FormatError::Layout* is returned and file isn't touched.
+	///
+	/// *Russian*: Метод **create()** создаёт пустое дерево в новом файле (старый файл
+	/// перезаписывается). Страница должна вмещать хотя бы 3 ключа и заголовок дерева (56 байт)
+	/// и помещаться в *u32*, иначе возвращается *FormatError::Layout*, а файл не трогается.
+	
+	#[inline]
+	pub fn create<P: AsRef<Path>>(path: P, page_size: usize, cache_pages: usize) -> Result<Self, FormatError> {
+		PagedTree::create_with_policy(path, page_size, cache_pages, DuplicatePolicy::KeepAll)
+	}
+	
+	/// *English*: Method **create_with_policy()** works like **create()**,
+	/// but tree gets given policy for duplicates
+	///
+	/// *Russian*: Метод **create_with_policy()** работает как **create()**,
+	/// но дерево получает данную политику для повторов
+	
+	pub fn create_with_policy<P: AsRef<Path>>(path: P, page_size: usize, cache_pages: usize, policy: DuplicatePolicy) -> Result<Self, FormatError> {
+		let max_keys = Pager::<T>::max_keys(page_size);
+		if max_keys < 3 || page_size < META_LEN + PAGE_CHECKSUM || page_size > u32::MAX as usize {
+			return Err(FormatError::Layout);
+		}
+		
+		let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+		let mut tree = PagedTree {
+			pager: RefCell::new(Pager {
+				file,
+				page_size,
+				capacity: cache_pages.max(1),
+				cache: HashMap::new(),
+				lru: BTreeMap::new(),
+				tick: 0,
+				page_count: 1,
+				free_head: 0,
+			}),
+			root: 0,
+			size: 0,
+			degree: max_keys / 2 + 1,
+			policy,
+		};
+		
+		let pager = tree.pager.get_mut();
+		tree.root = pager.alloc()?;
+		pager.write(tree.root, PageNode { keys: vec![], children: vec![] })?;
+		tree.flush()?;
+		Ok(tree)
+	}
+	
+	/// *English*: Method **open()** opens tree from file, which was made by **create()**
+	///
+	/// *Russian*: Метод **open()** открывает дерево из файла, созданного **create()**
+	
+	pub fn open<P: AsRef<Path>>(path: P, cache_pages: usize) -> Result<Self, FormatError> {
+		let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+		let mut head = [0; META_LEN];
+		file.read_exact(&mut head)?;
+		if head[..4] != PAGED_MAGIC {
+			return Err(FormatError::Magic);
+		}
+		if head[4] != 1 {
+			return Err(FormatError::Version { major: head[4] });
+		}
+		
+		let word = |at: usize| {
+			let mut bytes = [0; 8];
+			bytes.copy_from_slice(&head[at..at + 8]);
+			u64::from_le_bytes(bytes)
+		};
+		let width = u32::from_le_bytes([head[8], head[9], head[10], head[11]]);
+		if head[5] != T::TAG || width as usize != T::WIDTH {
+			return Err(FormatError::KeyType { tag: head[5], width });
+		}
+		
+		let page_size = u32::from_le_bytes([head[12], head[13], head[14], head[15]]) as usize;
+		let max_keys = Pager::<T>::max_keys(page_size);
+		let policy = match head[6] {
+			0 => DuplicatePolicy::KeepAll,
+			1 => DuplicatePolicy::Reject,
+			2 => DuplicatePolicy::Replace,
+			_ => return Err(FormatError::Layout),
+		};
+		if max_keys < 3 || page_size < head.len() + PAGE_CHECKSUM {
+			return Err(FormatError::Layout);
+		}
+		
+		let mut pager = Pager {
+			file,
+			page_size,
+			capacity: cache_pages.max(1),
+			cache: HashMap::new(),
+			lru: BTreeMap::new(),
+			tick: 0,
+			page_count: word(32),
+			free_head: word(40),
+		};
+		
+		let meta = pager.read_raw(0)?;
+		if meta[..head.len()] != head[..] || meta[7] != KIND_META {
+			return Err(FormatError::Layout);
+		}
+		
+		let size = usize::try_from(word(24)).map_err(|_| FormatError::Layout)?;
+		Ok(PagedTree { pager: RefCell::new(pager), root: word(16), size, degree: max_keys / 2 + 1, policy })
+	}
+	
+	/// *English*: Method **flush()** writes all changed pages and meta data to file
+	///
+	/// *Russian*: Метод **flush()** записывает в файл все изменённые страницы и метаданные
+	
+	pub fn flush(&mut self) -> Result<(), FormatError> {
+		let pager = self.pager.get_mut();
+		pager.flush()?;
+		
+		let mut meta = vec![0; pager.page_size];
+		meta[..4].copy_from_slice(&PAGED_MAGIC);
+		meta[4] = 1;
+		meta[5] = T::TAG;
+		meta[6] = match self.policy {
+			DuplicatePolicy::KeepAll => 0,
+			DuplicatePolicy::Reject => 1,
+			DuplicatePolicy::Replace => 2,
+		};
+		meta[7] = KIND_META;
+		meta[8..12].copy_from_slice(&(T::WIDTH as u32).to_le_bytes());
+		meta[12..16].copy_from_slice(&(pager.page_size as u32).to_le_bytes());
+		meta[16..24].copy_from_slice(&self.root.to_le_bytes());
+		meta[24..32].copy_from_slice(&(self.size as u64).to_le_bytes());
+		meta[32..40].copy_from_slice(&pager.page_count.to_le_bytes());
+		meta[40..META_LEN].copy_from_slice(&pager.free_head.to_le_bytes());
+		pager.write_raw(0, &mut meta)?;
+		pager.file.flush()?;
+		Ok(())
+	}
+	
+	/// *English*: Method **len()** returns number of elements
+	///
+	/// *Russian*: Метод **len()** возвращает количество элементов
+	
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.size
+	}
+	
+	/// *English*: Method **is_empty()** checks that tree has no elements
+	///
+	/// *Russian*: Метод **is_empty()** проверяет, что в дереве нет элементов
+	
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.size == 0
+	}
+	
+	/// *English*: Method **policy()** returns policy for duplicates
+	///
+	/// *Russian*: Метод **policy()** возвращает политику для повторов
+	
+	#[inline]
+	pub fn policy(&self) -> DuplicatePolicy {
+		self.policy
+	}
+	
+	/// *English*: Method **page_size()** returns size of one page in bytes
+	///
+	/// *Russian*: Метод **page_size()** возвращает размер одной страницы в байтах
+	
+	#[inline]
+	pub fn page_size(&self) -> usize {
+		self.pager.borrow().page_size
+	}
+	
+	/// *English*: Method **cached_pages()** returns number of pages in memory now.
+	/// It's never greater than *cache_pages* of **create()** or **open()**.
+	///
+	/// *Russian*: Метод **cached_pages()** возвращает количество страниц в памяти сейчас.
+	/// Оно никогда не больше *cache_pages* из **create()** или **open()**.
+	
+	#[inline]
+	pub fn cached_pages(&self) -> usize {
+		self.pager.borrow().cache.len()
+	}
+	
+	/// *English*: Method **page_count()** returns number of pages in file (with meta and free ones)
+	///
+	/// *Russian*: Метод **page_count()** возвращает количество страниц в файле
+	/// (вместе с метаданными и свободными)
+	
+	#[inline]
+	pub fn page_count(&self) -> u64 {
+		self.pager.borrow().page_count
+	}
+	
+	#[inline]
+	fn read(&self, id: u64) -> Result<PageNode<T>, FormatError> {
+		self.pager.borrow_mut().read(id)
+	}
+	
+	#[inline]
+	fn write(&mut self, id: u64, node: PageNode<T>) -> Result<(), FormatError> {
+		self.pager.get_mut().write(id, node)
+	}
+	
+	/// *English*: Method **contains()** checks that value is in the tree.
+	/// Reads O(log n) pages.
+	///
+	/// *Russian*: Метод **contains()** проверяет наличие значения в дереве.
+	/// Читает O(log n) страниц.
+	
+	pub fn contains(&self, val: &T) -> Result<bool, FormatError> {
+		let mut id = self.root;
+		loop {
+			let node = self.read(id)?;
+			match node.keys.binary_search(val) {
+				Ok(_) => return Ok(true),
+				Err(_) if node.is_leaf() => return Ok(false),
+				Err(index) => id = node.children[index],
+			}
+		}
+	}
+	
+	/// *English*: Method **first()** returns *minimum value* or *None*, if tree is empty
+	///
+	/// *Russian*: Метод **first()** возвращает *минимальное значение* или *None*,
+	/// если дерево пустое
+	
+	#[inline]
+	pub fn first(&self) -> Result<Option<T>, FormatError> {
+		self.iter().next().transpose()
+	}
+	
+	/// *English*: Method **last()** returns *maximum value* or *None*, if tree is empty
+	///
+	/// *Russian*: Метод **last()** возвращает *максимальное значение* или *None*,
+	/// если дерево пустое
+	
+	pub fn last(&self) -> Result<Option<T>, FormatError> {
+		let mut node = self.read(self.root)?;
+		while let Some(child) = node.children.last() {
+			node = self.read(*child)?;
+		}
+		Ok(node.keys.last().copied())
+	}
+	
+	/// *English*: Method **iter()** returns *lazy sorted* iterator over keys.
+	/// It keeps only the path from root in memory, pages are read through cache.
+	///
+	/// *Russian*: Метод **iter()** возвращает *ленивый отсортированный* итератор по ключам.
+	/// В памяти он хранит лишь путь от корня, страницы читаются через кэш.
+	
+	#[inline]
+	pub fn iter(&self) -> Iter<'_, T> {
+		self.range(..)
+	}
+	
+	/// *English*: Method **range()** returns *lazy sorted* iterator over keys from range.
+	/// Start is found in O(log n) pages. Empty or inverted range gives nothing.
+	///
+	/// *Russian*: Метод **range()** возвращает *ленивый отсортированный* итератор по ключам
+	/// из диапазона. Начало ищется за O(log n) страниц. Пустой или перевёрнутый
+	/// диапазон ничего не даёт.
+	
+	pub fn range<R: RangeBounds<T>>(&self, range: R) -> Iter<'_, T> {
+		let start = range.start_bound().cloned();
+		let mut iter = Iter { tree: self, stack: vec![], end: range.end_bound().cloned(), error: None };
+		
+		let mut id = self.root;
+		loop {
+			let node = match self.read(id) {
+				Ok(node) => node,
+				Err(error) => return iter.fail(error),
+			};
+			
+			let index = match start {
+				Bound::Included(ref val) => node.keys.partition_point(|key| key < val),
+				Bound::Excluded(ref val) => node.keys.partition_point(|key| key <= val),
+				Bound::Unbounded => 0,
+			};
+			let child = node.children.get(index).copied();
+			iter.stack.push((node, index));
+			
+			match child {
+				Some(child) => id = child,
+				None => return iter,
+			}
+		}
+	}
+	
+	/// *English*: Method **to_vec()** returns sorted vector of all keys
+	///
+	/// *Russian*: Метод **to_vec()** возвращает отсортированный вектор всех ключей
+	
+	#[inline]
+	pub fn to_vec(&self) -> Result<Vec<T>, FormatError> {
+		self.iter().collect()
+	}
+	
+	/// *English*: Method **insert()** adds value like **BinaryTree::insert()**.
+	/// Full nodes are split on the way down, so it writes O(log n) pages.
+	///
+	/// *Russian*: Метод **insert()** добавляет значение как **BinaryTree::insert()**.
+	/// Заполненные узлы делятся по пути вниз, так что записывается O(log n) страниц.
+	
+	pub fn insert(&mut self, val: &T) -> Result<bool, FormatError> {
+		match self.policy {
+			DuplicatePolicy::KeepAll => (),
+			DuplicatePolicy::Reject => if self.contains(val)? {
+				return Ok(false);
+			},
+			DuplicatePolicy::Replace => if self.replace(val)? {
+				return Ok(true);
+			},
+		}
+		
+		let max_keys = 2 * self.degree - 1;
+		let root = self.read(self.root)?;
+		if root.keys.len() == max_keys {
+			let id = self.pager.get_mut().alloc()?;
+			let mut top = PageNode { keys: vec![], children: vec![self.root] };
+			self.split_child(&mut top, 0)?;
+			self.write(id, top)?;
+			self.root = id;
+		}
+		
+		let mut id = self.root;
+		loop {
+			let mut node = self.read(id)?;
+			let mut index = node.keys.partition_point(|key| key <= val);
+			if node.is_leaf() {
+				node.keys.insert(index, *val);
+				self.write(id, node)?;
+				break;
+			}
+			
+			if self.read(node.children[index])?.keys.len() == max_keys {
+				self.split_child(&mut node, index)?;
+				if node.keys[index] <= *val {
+					index += 1;
+				}
+				let next = node.children[index];
+				self.write(id, node)?;
+				id = next;
+			} else {
+				id = node.children[index];
+			}
+		}
+		
+		self.size += 1;
+		Ok(true)
+	}
+	
+	/// *English*: Overwrites key, which is equal to value. Returns *false*, if there is none.
+	///
+	/// *Russian*: Перезаписывает ключ, равный значению. Возвращает *false*, если такого нет.
+	
+	fn replace(&mut self, val: &T) -> Result<bool, FormatError> {
+		let mut id = self.root;
+		loop {
+			let mut node = self.read(id)?;
+			match node.keys.binary_search(val) {
+				Ok(index) => {
+					node.keys[index] = *val;
+					self.write(id, node)?;
+					return Ok(true);
+				},
+				Err(_) if node.is_leaf() => return Ok(false),
+				Err(index) => id = node.children[index],
+			}
+		}
+	}
+	
+	/// *English*: Splits full child *i* of node, middle key goes up.
+	/// Parent isn't written, it's done by caller.
+	///
+	/// *Russian*: Делит заполненного потомка *i* узла, средний ключ поднимается.
+	/// Родитель не записывается, это делает вызывающий.
+	
+	fn split_child(&mut self, parent: &mut PageNode<T>, i: usize) -> Result<(), FormatError> {
+		let child_id = parent.children[i];
+		let mut child = self.read(child_id)?;
+		let right_keys = child.keys.split_off(self.degree);
+		let middle = child.keys.pop().unwrap();
+		let right_children = if child.is_leaf() { vec![] } else { child.children.split_off(self.degree) };
+		
+		let right_id = self.pager.get_mut().alloc()?;
+		self.write(child_id, child)?;
+		self.write(right_id, PageNode { keys: right_keys, children: right_children })?;
+		parent.keys.insert(i, middle);
+		parent.children.insert(i + 1, right_id);
+		Ok(())
+	}
+	
+	/// *English*: Makes sure, that child *i* has at least *degree* keys (like in **BTree**).
+	/// Writes all changed nodes including parent. Returns new index of the child.
+	///
+	/// *Russian*: Гарантирует, что у потомка *i* хотя бы *degree* ключей (как в **BTree**).
+	/// Записывает все изменённые узлы, включая родителя. Возвращает новый индекс потомка.
+	
+	fn fill(&mut self, id: u64, node: &mut PageNode<T>, i: usize) -> Result<usize, FormatError> {
+		let degree = self.degree;
+		let mut child = self.read(node.children[i])?;
+		if child.keys.len() >= degree {
+			return Ok(i);
+		}
+		
+		if i > 0 {
+			let mut left = self.read(node.children[i - 1])?;
+			if left.keys.len() >= degree {
+				let key = mem::replace(&mut node.keys[i - 1], left.keys.pop().unwrap());
+				child.keys.insert(0, key);
+				if let Some(last) = left.children.pop() {
+					child.children.insert(0, last);
+				}
+				self.write(node.children[i - 1], left)?;
+				self.write(node.children[i], child)?;
+				self.write(id, node.clone())?;
+				return Ok(i);
+			}
+		}
+		
+		if i + 1 < node.children.len() {
+			let mut right = self.read(node.children[i + 1])?;
+			if right.keys.len() >= degree {
+				let key = mem::replace(&mut node.keys[i], right.keys.remove(0));
+				child.keys.push(key);
+				if !right.is_leaf() {
+					child.children.push(right.children.remove(0));
+				}
+				self.write(node.children[i + 1], right)?;
+				self.write(node.children[i], child)?;
+				self.write(id, node.clone())?;
+				return Ok(i);
+			}
+		}
+		
+		let i = if i + 1 < node.children.len() { i } else { i - 1 };
+		self.merge(id, node, i)?;
+		Ok(i)
+	}
+	
+	/// *English*: Merges children *i* and *i + 1* with key *i* between them, frees right page
+	///
+	/// *Russian*: Сливает потомков *i* и *i + 1* с ключом *i* между ними, освобождает правую страницу
+	
+	fn merge(&mut self, id: u64, node: &mut PageNode<T>, i: usize) -> Result<(), FormatError> {
+		let right_id = node.children.remove(i + 1);
+		let right = self.read(right_id)?;
+		let mut left = self.read(node.children[i])?;
+		
+		left.keys.push(node.keys.remove(i));
+		left.keys.extend(right.keys);
+		left.children.extend(right.children);
+		self.write(node.children[i], left)?;
+		self.write(id, node.clone())?;
+		self.pager.get_mut().free(right_id)
+	}
+	
+	/// *English*: Removes min (or max) key of subtree
+	///
+	/// *Russian*: Удаляет наименьший (или наибольший) ключ поддерева
+	
+	fn pop_edge(&mut self, mut id: u64, last: bool) -> Result<T, FormatError> {
+		loop {
+			let mut node = self.read(id)?;
+			if node.is_leaf() {
+				let key = if last { node.keys.pop() } else if node.keys.is_empty() { None } else { Some(node.keys.remove(0)) };
+				self.write(id, node)?;
+				return key.ok_or(FormatError::Layout);
+			}
+			
+			let index = if last { node.children.len() - 1 } else { 0 };
+			let index = self.fill(id, &mut node, index)?;
+			id = node.children[index];
+		}
+	}
+	
+	/// *English*: Method **remove()** removes *one occurrence* of value.
+	/// Returns *false*, if there is no such value.
+	///
+	/// *Russian*: Метод **remove()** удаляет *одно вхождение* значения.
+	/// Возвращает *false*, если такого значения нет.
+	
+	pub fn remove(&mut self, val: &T) -> Result<bool, FormatError> {
+		let degree = self.degree;
+		let mut id = self.root;
+		let removed = loop {
+			let mut node = self.read(id)?;
+			let index = node.keys.partition_point(|key| key < val);
+			let here = index < node.keys.len() && node.keys[index] == *val;
+			
+			if node.is_leaf() {
+				if here {
+					node.keys.remove(index);
+					self.write(id, node)?;
+				}
+				break here;
+			}
+			
+			if here {
+				if self.read(node.children[index])?.keys.len() >= degree {
+					let pred = self.pop_edge(node.children[index], true)?;
+					node = self.read(id)?;
+					node.keys[index] = pred;
+					self.write(id, node)?;
+					break true;
+				}
+				if self.read(node.children[index + 1])?.keys.len() >= degree {
+					let succ = self.pop_edge(node.children[index + 1], false)?;
+					node = self.read(id)?;
+					node.keys[index] = succ;
+					self.write(id, node)?;
+					break true;
+				}
+				self.merge(id, &mut node, index)?;
+				id = node.children[index];
+				continue;
+			}
+			
+			let index = self.fill(id, &mut node, index)?;
+			id = node.children[index];
+		};
+		
+		let root = self.read(self.root)?;
+		if root.keys.is_empty() && !root.is_leaf() {
+			let old = self.root;
+			self.root = root.children[0];
+			self.pager.get_mut().free(old)?;
+		}
+		
+		if removed {
+			self.size -= 1;
+		}
+		Ok(removed)
+	}
+	
+	/// *English*: Method **clear()** removes all elements and shrinks file to two pages
+	///
+	/// *Russian*: Метод **clear()** удаляет все элементы и сжимает файл до двух страниц
+	
+	pub fn clear(&mut self) -> Result<(), FormatError> {
+		let pager = self.pager.get_mut();
+		pager.reset()?;
+		self.root = pager.alloc()?;
+		pager.write(self.root, PageNode { keys: vec![], children: vec![] })?;
+		self.size = 0;
+		self.flush()
+	}
+}
+
+/// *English*: Changes are written to file, errors are ignored (call **flush()** to see them)
+///
+/// *Russian*: Изменения записываются в файл, ошибки игнорируются (вызовите **flush()**,
+/// чтобы их увидеть)
+
+impl<T> Drop for PagedTree<T>
+	where T: Copy + Clone + Ord + Eq + FixedKey
+{
+	fn drop(&mut self) {
+		let _ = self.flush();
+	}
+}
+
+/// *English*: Lazy sorted iterator of **PagedTree**. I/O error or damaged page
+/// is returned once, then iterator ends.
+///
+/// *Russian*: Ленивый отсортированный итератор **PagedTree**. Ошибка ввода-вывода
+/// или повреждённая страница возвращается один раз, затем итератор заканчивается.
+
+pub struct Iter<'a, T>
+	where T: Copy + Clone + Ord + Eq + FixedKey
+{
+	tree: &'a PagedTree<T>,
+	stack: Vec<(PageNode<T>, usize)>,
+	end: Bound<T>,
+	error: Option<FormatError>,
+}
+
+impl<T> Iter<'_, T>
+	where T: Copy + Clone + Ord + Eq + FixedKey
+{
+	fn fail(mut self, error: FormatError) -> Self {
+		self.stack.clear();
+		self.error = Some(error);
+		self
+	}
+	
+	fn descend(&mut self, mut id: u64) -> Result<(), FormatError> {
+		loop {
+			let node = self.tree.read(id)?;
+			let child = node.children.first().copied();
+			self.stack.push((node, 0));
+			match child {
+				Some(child) => id = child,
+				None => return Ok(()),
+			}
+		}
+	}
+}
+
+impl<T> Iterator for Iter<'_, T>
+	where T: Copy + Clone + Ord + Eq + FixedKey
+{
+	type Item = Result<T, FormatError>;
+	
+	fn next(&mut self) -> Option<Result<T, FormatError>> {
+		if let Some(error) = self.error.take() {
+			return Some(Err(error));
+		}
+		
+		loop {
+			let (node, index) = self.stack.last_mut()?;
+			if *index < node.keys.len() {
+				let key = node.keys[*index];
+				*index += 1;
+				let child = node.children.get(*index).copied();
+				
+				let inside = match self.end {
+					Bound::Included(ref end) => key <= *end,
+					Bound::Excluded(ref end) => key < *end,
+					Bound::Unbounded => true,
+				};
+				if !inside {
+					self.stack.clear();
+					return None;
+				}
+				
+				if let Some(child) = child {
+					if let Err(error) = self.descend(child) {
+						self.stack.clear();
+						self.error = Some(error);
+					}
+				}
+				return Some(Ok(key));
+			}
+			self.stack.pop();
+		}
+	}
+}
@@ -0,0 +1,397 @@
+use crate::node::Node;
+use crate::branch::Branch;
+use crate::seq::SortedSeq;
+use crate::tree::{BinaryTree, filter_present};
+use std::collections::VecDeque;
+use std::thread;
+
+/// *English*: Trees (or sorted slices) smaller than this are processed in one thread:
+/// spawning threads for them costs more than the work itself.
+///
+/// *Russian*: Деревья (или отсортированные срезы) меньше этого размера обрабатываются
+/// в одном потоке: создание потоков стоит дороже самой работы.
+
+pub const PAR_THRESHOLD: usize = 1 << 12;
+
+/// *English*: How many times work can be split in halves,
+/// so that every available core gets at least one part.
+///
+/// *Russian*: Сколько раз работу можно поделить пополам,
+/// чтобы каждому доступному ядру досталась хотя бы одна часть.
+
+fn split_depth() -> usize {
+	let threads = thread::available_parallelism().map_or(1, |n| n.get());
+	(usize::BITS - (threads - 1).leading_zeros()) as usize + 1
+}
+
+/// *English*: Runs *first* in separate thread and *second* in this one at the same time,
+/// returns both results.
+///
+/// *Russian*: Выполняет *first* в отдельном потоке и *second* в этом одновременно,
+/// возвращает оба результата.
+
+pub(crate) fn join<A, B, FA, FB>(first: FA, second: FB) -> (A, B)
+	where FA: FnOnce() -> A + Send, FB: FnOnce() -> B, A: Send
+{
+	thread::scope(|scope| {
+		let first = scope.spawn(first);
+		let second = second();
+		(first.join().unwrap(), second)
+	})
+}
+
+/// *English*: Builds *balanced* subtree from sorted slice.
+/// Middle element becomes the key, halves are built in separate threads
+/// while *depth* isn't exhausted.
+///
+/// *Russian*: Строит *сбалансированное* поддерево из отсортированного среза.
+/// Средний элемент становится ключом, половины строятся в разных потоках,
+/// пока не исчерпана *depth*.
+
+fn build<T>(src: &[T], depth: usize) -> Node<T>
+	where T: Copy + Clone + Ord + Eq + Send + Sync
+{
+	if depth == 0 || src.len() < PAR_THRESHOLD {
+		return Node::from_sorted(src);
+	}
+	
+	let mid = src.len() / 2;
+	let (left, right) = thread::scope(|scope| {
+		let left = scope.spawn(|| build(&src[..mid], depth - 1));
+		let right = build(&src[mid + 1..], depth - 1);
+		(left.join().unwrap(), right)
+	});
+	
+	Node::NonEmpty(Box::new(Branch {
+		key: src[mid],
+		right,
+		left,
+	}))
+}
+
+/// *English*: In-order keys of subtree. Left subtree is walked in separate thread
+/// while *depth* isn't exhausted.
+///
+/// *Russian*: Ключи поддерева по порядку. Левое поддерево обходится в отдельном потоке,
+/// пока не исчерпана *depth*.
+
+fn walk<T>(node: &Node<T>, depth: usize) -> Vec<T>
+	where T: Copy + Clone + Ord + Eq + Send + Sync
+{
+	match node {
+		Node::NonEmpty(branch) if depth > 0 => thread::scope(|scope| {
+			let left = scope.spawn(|| walk(&branch.left, depth - 1));
+			let right = walk(&branch.right, depth - 1);
+			let mut result = left.join().unwrap();
+			result.push(branch.key);
+			result.extend(right);
+			result
+		}),
+		_ => {
+			let mut result = Vec::new();
+			for_each_seq(node, &mut |key| result.push(*key));
+			result
+		}
+	}
+}
+
+/// *English*: Sequential in-order traversal with explicit stack,
+/// so long (list-like) trees don't overflow the call stack.
+///
+/// *Russian*: Последовательный обход по порядку с явным стеком,
+/// чтобы длинные (похожие на список) деревья не переполняли стек вызовов.
+
+fn for_each_seq<'a, T, F>(node: &'a Node<T>, fun: &mut F)
+	where T: Copy + Clone + Ord + Eq, F: FnMut(&'a T)
+{
+	let mut stack = Vec::new();
+	let mut cur = node;
+	
+	loop {
+		while let Node::NonEmpty(branch) = cur {
+			stack.push(branch);
+			cur = &branch.left;
+		}
+		
+		match stack.pop() {
+			None => return,
+			Some(branch) => {
+				fun(&branch.key);
+				cur = &branch.right;
+			}
+		}
+	}
+}
+
+/// *English*: Folds subtree in order. Left subtree is folded in separate thread
+/// while *depth* isn't exhausted, then results are combined left to right.
+///
+/// *Russian*: Сворачивает поддерево по порядку. Левое поддерево сворачивается
+/// в отдельном потоке, пока не исчерпана *depth*, затем результаты объединяются слева направо.
+
+fn fold<T, A, I, F, C>(node: &Node<T>, depth: usize, init: &I, fold_fn: &F, combine: &C) -> A
+	where T: Copy + Clone + Ord + Eq + Send + Sync,
+		A: Send,
+		I: Fn() -> A + Sync,
+		F: Fn(A, &T) -> A + Sync,
+		C: Fn(A, A) -> A + Sync
+{
+	match node {
+		Node::NonEmpty(branch) if depth > 0 => thread::scope(|scope| {
+			let left = scope.spawn(|| fold(&branch.left, depth - 1, init, fold_fn, combine));
+			let right = fold(&branch.right, depth - 1, init, fold_fn, combine);
+			let middle = fold_fn(left.join().unwrap(), &branch.key);
+			combine(middle, right)
+		}),
+		_ => {
+			let mut acc = Some(init());
+			for_each_seq(node, &mut |key| acc = Some(fold_fn(acc.take().unwrap(), key)));
+			acc.unwrap()
+		}
+	}
+}
+
+/// *English*: Calls function for every key of subtree.
+/// Left subtree is processed in separate thread while *depth* isn't exhausted.
+///
+/// *Russian*: Вызывает функцию для каждого ключа поддерева.
+/// Левое поддерево обрабатывается в отдельном потоке, пока не исчерпана *depth*.
+
+fn for_each<T, F>(node: &Node<T>, depth: usize, fun: &F)
+	where T: Copy + Clone + Ord + Eq + Send + Sync, F: Fn(&T) + Sync
+{
+	match node {
+		Node::NonEmpty(branch) if depth > 0 => thread::scope(|scope| {
+			scope.spawn(|| for_each(&branch.left, depth - 1, fun));
+			fun(&branch.key);
+			for_each(&branch.right, depth - 1, fun);
+		}),
+		_ => for_each_seq(node, &mut |key| fun(key)),
+	}
+}
+
+/// *English*: Splits two sorted slices into matching parts and
+/// applies *fun* to every pair in separate threads.
+/// Equal elements always get into the same part,
+/// so concatenation of results is equal to *fun(first, second)*.
+///
+/// *Russian*: Делит два отсортированных среза на соответствующие части и
+/// применяет *fun* к каждой паре в отдельных потоках.
+/// Равные элементы всегда попадают в одну часть,
+/// так что объединение результатов равно *fun(first, second)*.
+
+fn merge_parts<T, F>(first: &[T], second: &[T], fun: F) -> VecDeque<T>
+	where T: Copy + Clone + Ord + Eq + Send + Sync,
+		F: Fn(&[T], &[T]) -> VecDeque<T> + Sync
+{
+	let parts = 1 << (split_depth() - 1);
+	if parts == 1 || first.len() + second.len() < PAR_THRESHOLD || first.is_empty() {
+		return fun(first, second);
+	}
+	
+	let mut bounds = vec![(0, 0)];
+	for i in 1..parts {
+		let pivot = &first[first.len() * i / parts];
+		bounds.push((
+			first.partition_point(|x| x < pivot),
+			second.partition_point(|x| x < pivot),
+		));
+	}
+	bounds.push((first.len(), second.len()));
+	
+	thread::scope(|scope| {
+		let handles = bounds
+			.windows(2)
+			.map(|pair| {
+				let (a, b) = (pair[0], pair[1]);
+				let fun = &fun;
+				scope.spawn(move || fun(&first[a.0..b.0], &second[a.1..b.1]))
+			})
+			.collect::<Vec<_>>();
+		
+		let mut result = VecDeque::new();
+		for handle in handles {
+			result.extend(handle.join().unwrap());
+		}
+		result
+	})
+}
+
+/// *English*: Parallel methods of **BinaryTree**. They are available only with *std* feature
+/// and use **std::thread::scope()**, so no extra crates are needed.
+/// Work is split across subtrees (or parts of sorted data), small trees are
+/// processed in one thread. Results are the same as of sequential methods.
+///
+/// *Russian*: Параллельные методы **BinaryTree**. Доступны только с фичей *std*
+/// и используют **std::thread::scope()**, так что другие крейты не нужны.
+/// Работа делится между поддеревьями (или частями отсортированных данных), маленькие деревья
+/// обрабатываются в одном потоке. Результаты такие же, как у последовательных методов.
+
+impl<T> BinaryTree<T>
+	where T: Copy + Clone + Ord + Eq + Send + Sync
+{
+	/// *English*: Method **par_from_sorted()** builds *balanced* tree from sorted slice.
+	/// Tree has the same elements as **BinaryTree::from(src.to_vec())**,
+	/// but its height is minimal. Halves are built in parallel.
+	/// Slice must be sorted (checked in debug builds).
+	///
+	/// *Russian*: Метод **par_from_sorted()** строит *сбалансированное* дерево из отсортированного среза.
+	/// В дереве те же элементы, что и в **BinaryTree::from(src.to_vec())**,
+	/// но высота минимальна. Половины строятся параллельно.
+	/// Срез должен быть отсортирован (проверяется в отладочных сборках).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	///
+	/// let src = (0..100_000).collect::<Vec<i32>>();
+	/// let tree = BinaryTree::par_from_sorted(&src);
+	///
+	/// assert_eq!(tree.len(), 100_000);
+	/// assert_eq!(tree.height(), 17);
+	/// assert_eq!(tree.to_vec(), src);
+	/// ```
+	
+	pub fn par_from_sorted(src: &[T]) -> Self {
+		debug_assert!(src.windows(2).all(|pair| pair[0] <= pair[1]));
+		let mut tree = BinaryTree::new();
+		tree.top = build(src, split_depth());
+		tree.size = src.len();
+		tree.check();
+		tree
+	}
+	
+	/// *English*: Method **par_to_vec()** returns sorted vector of elements.
+	/// Subtrees are walked in parallel.
+	///
+	/// *Russian*: Метод **par_to_vec()** возвращает отсортированный вектор элементов.
+	/// Поддеревья обходятся параллельно.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	///
+	/// let tree = BinaryTree::from(vec![3, 1, 2]);
+	/// assert_eq!(tree.par_to_vec(), tree.to_vec());
+	/// ```
+	
+	pub fn par_to_vec(&self) -> Vec<T> {
+		if self.size < PAR_THRESHOLD {
+			return walk(&self.top, 0);
+		}
+		walk(&self.top, split_depth())
+	}
+	
+	/// *English*: Method **par_union()** works like **union()**,
+	/// but both trees are walked and merged in parallel.
+	///
+	/// *Russian*: Метод **par_union()** работает как **union()**,
+	/// но оба дерева обходятся и сливаются параллельно.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	///
+	/// let tree1 = BinaryTree::par_from_sorted(&(0..50_000).collect::<Vec<i32>>());
+	/// let tree2 = BinaryTree::par_from_sorted(&(25_000..75_000).collect::<Vec<i32>>());
+	///
+	/// assert_eq!(tree1.par_union(&tree2), tree1.union(&tree2));
+	/// ```
+	
+	pub fn par_union(&self, other: &Self) -> SortedSeq<T> {
+		let (first, second) = join(|| self.par_to_vec(), || other.par_to_vec());
+		
+		let result = merge_parts(&first, &second, |first, second| {
+			let mut seq = SortedSeq::from_sorted(VecDeque::from(first.to_vec()));
+			seq.merge(SortedSeq::from_sorted(filter_present(second, first, false)));
+			seq.to_deque()
+		});
+		self.set_result(result)
+	}
+	
+	/// *English*: Method **par_intersection()** works like **intersection()**,
+	/// but both trees are walked and filtered in parallel.
+	///
+	/// *Russian*: Метод **par_intersection()** работает как **intersection()**,
+	/// но оба дерева обходятся и фильтруются параллельно.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	///
+	/// let tree1 = BinaryTree::par_from_sorted(&(0..50_000).collect::<Vec<i32>>());
+	/// let tree2 = BinaryTree::par_from_sorted(&(25_000..75_000).collect::<Vec<i32>>());
+	///
+	/// assert_eq!(tree1.par_intersection(&tree2), tree1.intersection(&tree2));
+	/// ```
+	
+	pub fn par_intersection(&self, other: &Self) -> SortedSeq<T> {
+		let (first, second) = join(|| self.par_to_vec(), || other.par_to_vec());
+		
+		let result = merge_parts(&first, &second, |first, second| {
+			filter_present(first, second, true)
+		});
+		self.set_result(result)
+	}
+	
+	/// *English*: Method **par_fold()** folds all elements in order.
+	/// Every part of the tree is folded from *init()* in its own thread,
+	/// then results are joined left to right with *combine*.
+	/// Result is equal to sequential **iter().fold(init(), fold_fn)**, if *combine*
+	/// is associative, *init()* is neutral for it and
+	/// *fold_fn(a, x) == combine(a, fold_fn(init(), x))* (true for sums, counts, min/max, etc.).
+	///
+	/// *Russian*: Метод **par_fold()** сворачивает все элементы по порядку.
+	/// Каждая часть дерева сворачивается от *init()* в своём потоке,
+	/// затем результаты объединяются слева направо с помощью *combine*.
+	/// Результат равен последовательному **iter().fold(init(), fold_fn)**, если *combine*
+	/// ассоциативен, *init()* для него нейтрален и
+	/// *fold_fn(a, x) == combine(a, fold_fn(init(), x))* (верно для сумм, подсчётов, min/max и т.д.).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	///
+	/// let tree = BinaryTree::par_from_sorted(&(1..=100_000).collect::<Vec<u64>>());
+	/// let sum = tree.par_fold(|| 0, |acc, x| acc + x, |a, b| a + b);
+	/// assert_eq!(sum, 5_000_050_000);
+	/// ```
+	
+	pub fn par_fold<A, I, F, C>(&self, init: I, fold_fn: F, combine: C) -> A
+		where A: Send, I: Fn() -> A + Sync, F: Fn(A, &T) -> A + Sync, C: Fn(A, A) -> A + Sync
+	{
+		let depth = if self.size < PAR_THRESHOLD { 0 } else { split_depth() };
+		fold(&self.top, depth, &init, &fold_fn, &combine)
+	}
+	
+	/// *English*: Method **par_for_each()** calls function for every element.
+	/// Subtrees are processed in parallel, so order of calls isn't specified.
+	///
+	/// *Russian*: Метод **par_for_each()** вызывает функцию для каждого элемента.
+	/// Поддеревья обрабатываются параллельно, так что порядок вызовов не определён.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::sync::atomic::{AtomicU64, Ordering};
+	///
+	/// let tree = BinaryTree::par_from_sorted(&(1..=100_000).collect::<Vec<u64>>());
+	/// let sum = AtomicU64::new(0);
+	///
+	/// tree.par_for_each(|x| { sum.fetch_add(*x, Ordering::Relaxed); });
+	/// assert_eq!(sum.into_inner(), 5_000_050_000);
+	/// ```
+	
+	pub fn par_for_each<F>(&self, fun: F)
+		where F: Fn(&T) + Sync
+	{
+		let depth = if self.size < PAR_THRESHOLD { 0 } else { split_depth() };
+		for_each(&self.top, depth, &fun)
+	}
+}
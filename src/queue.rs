@@ -0,0 +1,466 @@
+use crate::iter::TreeIter;
+use crate::tree::BinaryTree;
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+
+/// *English*: Balance factor of the tree inside queue
+///
+/// *Russian*: Коэффициент балансировки дерева внутри очереди
+
+const QUEUE_BALANCE_FACTOR: f64 = 2.0;
+
+/// *English*: **TreePriorityQueue** is a *double-ended priority queue* over **BinaryTree**.
+/// Unlike **BinaryHeap** it gives both min and max elements. The tree inside
+/// rebalances itself on insertion and is rebuilt, when pops leave less than half
+/// of the elements, so **push()**, **pop_min()** and **pop_max()** take O(log n)
+/// (amortized), while **peek_min()** and **peek_max()** take O(log n).
+/// Equal elements are kept, like in **BinaryHeap**.
+///
+/// *Russian*: **TreePriorityQueue** - *двусторонняя очередь с приоритетом* на **BinaryTree**.
+/// В отличие от **BinaryHeap** она выдаёт и наименьший, и наибольший элементы.
+/// Дерево внутри само себя балансирует при вставке и перестраивается, когда после
+/// удалений остаётся меньше половины элементов, так что **push()**, **pop_min()** и
+/// **pop_max()** занимают O(log n) (амортизированно), а **peek_min()** и **peek_max()** - O(log n).
+/// Равные элементы сохраняются, как и в **BinaryHeap**.
+///
+/// # Example
+///
+/// ```
+/// use binartree::queue::TreePriorityQueue;
+///
+/// let mut queue = TreePriorityQueue::new();
+/// queue.push(&3);
+/// queue.push(&1);
+/// queue.push(&2);
+///
+/// assert_eq!(queue.peek_min(), Some(&1));
+/// assert_eq!(queue.peek_max(), Some(&3));
+/// assert_eq!(queue.pop_max(), Some(3));
+/// assert_eq!(queue.pop_min(), Some(1));
+/// assert_eq!(queue.into_sorted_vec(), vec![2]);
+/// ```
+
+#[derive(Debug, Clone)]
+pub struct TreePriorityQueue<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	pub(crate) tree: BinaryTree<T>,
+	peak: usize,
+}
+
+/// *English*: Queues are equal, if they have equal elements
+///
+/// *Russian*: Очереди равны, если у них равные элементы
+
+impl<T> PartialEq for TreePriorityQueue<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn eq(&self, other: &Self) -> bool {
+		self.tree == other.tree
+	}
+}
+
+impl<T> Default for TreePriorityQueue<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> TreePriorityQueue<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	/// *English*: Method **new()** creates *empty* queue
+	///
+	/// *Russian*: Метод **new()** создаёт *пустую* очередь
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::queue::TreePriorityQueue;
+	///
+	/// let queue = TreePriorityQueue::<i32>::new();
+	/// assert!(queue.is_empty());
+	/// ```
+	
+	pub fn new() -> Self {
+		TreePriorityQueue {
+			tree: BinaryTree::with_auto_rebalance(QUEUE_BALANCE_FACTOR),
+			peak: 0,
+		}
+	}
+	
+	/// *English*: Method **len()** returns number of elements in queue
+	///
+	/// *Russian*: Метод **len()** возвращает количество элементов в очереди
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::queue::TreePriorityQueue;
+	///
+	/// let queue = TreePriorityQueue::from(vec![1, 1, 2]);
+	/// assert_eq!(queue.len(), 3);
+	/// ```
+	
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.tree.len()
+	}
+	
+	/// *English*: Method **is_empty()** checks if queue is empty
+	///
+	/// *Russian*: Метод **is_empty()** проверяет, пуста ли очередь
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::queue::TreePriorityQueue;
+	///
+	/// let mut queue = TreePriorityQueue::new();
+	/// assert!(queue.is_empty());
+	/// queue.push(&1);
+	/// assert!(!queue.is_empty());
+	/// ```
+	
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.tree.is_empty()
+	}
+	
+	/// *English*: Method **push()** adds element to queue
+	///
+	/// *Russian*: Метод **push()** добавляет элемент в очередь
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::queue::TreePriorityQueue;
+	///
+	/// let mut queue = TreePriorityQueue::new();
+	/// queue.push(&2);
+	/// queue.push(&2);
+	/// assert_eq!(queue.len(), 2);
+	/// ```
+	
+	#[inline]
+	pub fn push(&mut self, val: &T) {
+		self.tree.insert(val);
+		self.peak = self.peak.max(self.tree.len());
+	}
+	
+	/// *English*: Method **peek_min()** returns min element or *None*, if queue is empty
+	///
+	/// *Russian*: Метод **peek_min()** возвращает наименьший элемент
+	/// или *None*, если очередь пуста
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::queue::TreePriorityQueue;
+	///
+	/// let mut queue = TreePriorityQueue::new();
+	/// assert_eq!(queue.peek_min(), None);
+	/// queue.push(&5);
+	/// queue.push(&4);
+	/// assert_eq!(queue.peek_min(), Some(&4));
+	/// ```
+	
+	#[inline]
+	pub fn peek_min(&self) -> Option<&T> {
+		self.tree.try_first().ok()
+	}
+	
+	/// *English*: Method **peek_max()** returns max element or *None*, if queue is empty
+	///
+	/// *Russian*: Метод **peek_max()** возвращает наибольший элемент
+	/// или *None*, если очередь пуста
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::queue::TreePriorityQueue;
+	///
+	/// let mut queue = TreePriorityQueue::new();
+	/// assert_eq!(queue.peek_max(), None);
+	/// queue.push(&4);
+	/// queue.push(&5);
+	/// assert_eq!(queue.peek_max(), Some(&5));
+	/// ```
+	
+	#[inline]
+	pub fn peek_max(&self) -> Option<&T> {
+		self.tree.try_last().ok()
+	}
+	
+	/// *English*: Method **pop_min()** removes and returns min element
+	/// or returns *None*, if queue is empty
+	///
+	/// *Russian*: Метод **pop_min()** удаляет и возвращает наименьший элемент
+	/// или возвращает *None*, если очередь пуста
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::queue::TreePriorityQueue;
+	///
+	/// let mut queue = TreePriorityQueue::from(vec![2, 1, 1]);
+	/// assert_eq!(queue.pop_min(), Some(1));
+	/// assert_eq!(queue.pop_min(), Some(1));
+	/// assert_eq!(queue.pop_min(), Some(2));
+	/// assert_eq!(queue.pop_min(), None);
+	/// ```
+	
+	#[inline]
+	pub fn pop_min(&mut self) -> Option<T> {
+		let min = self.tree.pop_first();
+		self.shrink();
+		min
+	}
+	
+	/// *English*: Method **pop_max()** removes and returns max element
+	/// or returns *None*, if queue is empty
+	///
+	/// *Russian*: Метод **pop_max()** удаляет и возвращает наибольший элемент
+	/// или возвращает *None*, если очередь пуста
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::queue::TreePriorityQueue;
+	///
+	/// let mut queue = TreePriorityQueue::from(vec![1, 2, 2]);
+	/// assert_eq!(queue.pop_max(), Some(2));
+	/// assert_eq!(queue.pop_max(), Some(2));
+	/// assert_eq!(queue.pop_max(), Some(1));
+	/// assert_eq!(queue.pop_max(), None);
+	/// ```
+	
+	#[inline]
+	pub fn pop_max(&mut self) -> Option<T> {
+		let max = self.tree.pop_last();
+		self.shrink();
+		max
+	}
+	
+	/// *English*: Rebuilds the tree, when it has less than half of elements
+	/// since the last rebuild. Height is bounded by the past maximum size,
+	/// so it keeps it O(log n) for the current size. Rebuild takes O(n) after
+	/// at least n pops, so it's amortized O(1) per pop.
+	///
+	/// *Russian*: Перестраивает дерево, когда в нём меньше половины элементов
+	/// с последней перестройки. Высота ограничена прошлым наибольшим размером,
+	/// так что это держит её в O(log n) для текущего размера. Перестройка занимает O(n)
+	/// после хотя бы n удалений, так что это амортизированное O(1) на удаление.
+	
+	fn shrink(&mut self) {
+		if self.tree.len() * 2 < self.peak {
+			self.tree.rebalance();
+			self.peak = self.tree.len();
+		}
+	}
+	
+	/// *English*: Method **clear()** removes all elements
+	///
+	/// *Russian*: Метод **clear()** удаляет все элементы
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::queue::TreePriorityQueue;
+	///
+	/// let mut queue = TreePriorityQueue::from(vec![1, 2, 3]);
+	/// queue.clear();
+	/// assert!(queue.is_empty());
+	/// ```
+	
+	#[inline]
+	pub fn clear(&mut self) {
+		self.tree.clear();
+		self.peak = 0;
+	}
+	
+	/// *English*: Method **iter()** returns *sorted* iterator over copies of elements
+	///
+	/// *Russian*: Метод **iter()** возвращает *отсортированный* итератор по копиям элементов
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::queue::TreePriorityQueue;
+	///
+	/// let queue = TreePriorityQueue::from(vec![3, 1, 2]);
+	/// assert_eq!(queue.iter().collect::<Vec<i32>>(), vec![1, 2, 3]);
+	/// ```
+	
+	#[inline]
+	pub fn iter(&self) -> TreeIter<T> {
+		self.tree.iter()
+	}
+	
+	/// *English*: Method **into_sorted_vec()** converts queue to *ascending* vector
+	///
+	/// *Russian*: Метод **into_sorted_vec()** превращает очередь в вектор *по возрастанию*
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::queue::TreePriorityQueue;
+	///
+	/// let queue = TreePriorityQueue::from(vec![3, 1, 2, 1]);
+	/// assert_eq!(queue.into_sorted_vec(), vec![1, 1, 2, 3]);
+	/// ```
+	
+	#[inline]
+	pub fn into_sorted_vec(self) -> Vec<T> {
+		self.tree.to_vec()
+	}
+	
+	/// *English*: Method **drain_sorted()** returns iterator, which removes elements
+	/// in *ascending* order (or *descending*, if **next_back()** is used).
+	/// Elements, which were not taken, are removed, when iterator is dropped.
+	///
+	/// *Russian*: Метод **drain_sorted()** возвращает итератор, который удаляет
+	/// элементы *по возрастанию* (или *по убыванию*, если вызывать **next_back()**).
+	/// Невзятые элементы удаляются, когда итератор уничтожается.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::queue::TreePriorityQueue;
+	///
+	/// let mut queue = TreePriorityQueue::from(vec![4, 1, 3, 2]);
+	/// let mut drain = queue.drain_sorted();
+	///
+	/// assert_eq!(drain.next(), Some(1));
+	/// assert_eq!(drain.next_back(), Some(4));
+	/// drop(drain);
+	///
+	/// assert!(queue.is_empty());
+	/// ```
+	
+	#[inline]
+	pub fn drain_sorted(&mut self) -> DrainSorted<'_, T> {
+		DrainSorted { queue: self }
+	}
+}
+
+/// *English*: **DrainSorted** is a *draining* iterator returned by
+/// **TreePriorityQueue::drain_sorted()**
+///
+/// *Russian*: **DrainSorted** - *опустошающий* итератор, который возвращает
+/// **TreePriorityQueue::drain_sorted()**
+
+#[derive(Debug)]
+pub struct DrainSorted<'a, T>
+	where T: Copy + Clone + Ord + Eq
+{
+	queue: &'a mut TreePriorityQueue<T>,
+}
+
+impl<'a, T> Iterator for DrainSorted<'a, T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Item = T;
+	
+	#[inline]
+	fn next(&mut self) -> Option<T> {
+		self.queue.pop_min()
+	}
+	
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.queue.len(), Some(self.queue.len()))
+	}
+}
+
+impl<'a, T> DoubleEndedIterator for DrainSorted<'a, T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn next_back(&mut self) -> Option<T> {
+		self.queue.pop_max()
+	}
+}
+
+impl<'a, T> ExactSizeIterator for DrainSorted<'a, T>
+	where T: Copy + Clone + Ord + Eq
+{}
+
+impl<'a, T> Drop for DrainSorted<'a, T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn drop(&mut self) {
+		self.queue.clear();
+	}
+}
+
+/// *English*: **Extend<T>** trait for queue
+///
+/// *Russian*: Трейт **Extend<T>** для очереди
+///
+/// # Example
+///
+/// ```
+/// use binartree::queue::TreePriorityQueue;
+///
+/// let mut queue = TreePriorityQueue::new();
+/// queue.extend(vec![2, 1]);
+/// assert_eq!(queue.pop_min(), Some(1));
+/// ```
+
+impl<T> Extend<T> for TreePriorityQueue<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+		self.tree.extend(iter);
+		self.peak = self.peak.max(self.tree.len());
+	}
+}
+
+/// *English*: **FromIterator<T>** trait for queue
+///
+/// *Russian*: Трейт **FromIterator<T>** для очереди
+///
+/// # Example
+///
+/// ```
+/// use binartree::queue::TreePriorityQueue;
+/// use std::iter::FromIterator;
+///
+/// let queue = TreePriorityQueue::from_iter(0..10);
+/// assert_eq!(queue.peek_max(), Some(&9));
+/// ```
+
+impl<T> FromIterator<T> for TreePriorityQueue<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+		let mut queue = TreePriorityQueue::new();
+		queue.extend(iter);
+		queue
+	}
+}
+
+/// *English*: Converts vector to queue. Takes ownership
+///
+/// *Russian*: Конвертирует вектор в очередь. Принимает владение
+///
+/// # Example
+///
+/// ```
+/// use binartree::queue::TreePriorityQueue;
+///
+/// let queue = TreePriorityQueue::from(vec![10, 30, 20]);
+/// assert_eq!(queue.into_sorted_vec(), vec![10, 20, 30]);
+/// ```
+
+impl<T> From<Vec<T>> for TreePriorityQueue<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn from(s: Vec<T>) -> Self {
+		TreePriorityQueue::from_iter(s)
+	}
+}
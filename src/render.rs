@@ -0,0 +1,376 @@
+use crate::branch::Branch;
+use crate::node::Node;
+use crate::tree::BinaryTree;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter, Write};
+
+/// **Rendering of tree's structure**
+/// ---------------------------------------
+///
+/// *English*: Tree can be drawn as *Graphviz DOT* graph (**to_dot()**)
+/// or as *ASCII art* (**pretty()**). Both are made for debugging
+/// of tree's shape, so keys must implement **Display**.
+///
+/// *Russian*: Дерево можно нарисовать как *граф Graphviz DOT* (**to_dot()**)
+/// или как *ASCII арт* (**pretty()**). Оба способа нужны для отладки
+/// формы дерева, поэтому ключи должны реализовывать **Display**.
+
+/// *English*: How to draw the tree in ASCII
+///
+/// *Russian*: Как рисовать дерево в ASCII
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+	/// *English*: Tree is rotated to the left: top node is in the first column,
+	/// right branch is above, left branch is below.
+	///
+	/// *Russian*: Дерево повёрнуто влево: головной узел в первом столбце,
+	/// правая ветвь сверху, левая снизу.
+	Sideways,
+	
+	/// *English*: Classic picture: top node is in the first line,
+	/// every level is two lines lower than the previous one.
+	/// Picture is a grid of *2 × height* lines and *width of all keys* columns,
+	/// so it's quadratic for degenerate trees: use **Sideways** for them.
+	///
+	/// *Russian*: Классическая картинка: головной узел в первой строке,
+	/// каждый уровень на две строки ниже предыдущего.
+	/// Картинка - это сетка из *2 × высота* строк и *ширина всех ключей* столбцов,
+	/// так что для вырожденных деревьев она квадратична: для них есть **Sideways**.
+	TopDown,
+}
+
+/// *English*: Wrapper, which draws the tree with **Display**.
+/// It's created by **BinaryTree::pretty()**.
+///
+/// *Russian*: Обёртка, рисующая дерево через **Display**.
+/// Создаётся методом **BinaryTree::pretty()**.
+
+#[derive(Debug, Clone, Copy)]
+pub struct Pretty<'a, T>
+	where T: Copy + Clone + Ord + Eq + Display
+{
+	tree: &'a BinaryTree<T>,
+	layout: Layout,
+}
+
+/// *English*: Rendering methods for tree.
+///
+/// *Russian*: Методы для рисования дерева.
+
+impl<T> BinaryTree<T>
+	where T: Copy + Clone + Ord + Eq + Display
+{
+
+	/// *English*: Method **to_dot()** creates *Graphviz DOT* graph of the tree.
+	/// Every node is called *n{index}*, where index is node's number in pre-order.
+	/// If *nil_nodes* is true, empty children are drawn as points.
+	///
+	/// *Russian*: Метод **to_dot()** создаёт *граф Graphviz DOT* для дерева.
+	/// Каждый узел называется *n{index}*, где index - номер узла в прямом обходе.
+	/// Если *nil_nodes* равен true, пустые потомки рисуются точками.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let tree = BinaryTree::from_iter(vec![2, 1, 3]);
+	///
+	/// assert_eq!(tree.to_dot(false), "digraph BinaryTree {
+	///     n0 [label=\"2\"];
+	///     n0 -> n1;
+	///     n1 [label=\"1\"];
+	///     n0 -> n2;
+	///     n2 [label=\"3\"];
+	/// }
+	/// ");
+	/// ```
+	
+	pub fn to_dot(&self, nil_nodes: bool) -> String {
+		let mut dot = String::from("digraph BinaryTree {\n");
+		let mut nodes = 0;
+		let mut nils = 0;
+		let mut stack = vec![(None, &self.top)];
+		
+		while let Some((parent, node)) = stack.pop() {
+			match *node {
+				Node::NonEmpty(ref branch) => {
+					if let Some(parent) = parent {
+						let _ = writeln!(dot, "    n{} -> n{};", parent, nodes);
+					}
+					let _ = writeln!(
+						dot,
+						"    n{} [label=\"{}\"];",
+						nodes,
+						branch.key.to_string().replace('\\', "\\\\").replace('"', "\\\"")
+					);
+					stack.push((Some(nodes), &branch.right));
+					stack.push((Some(nodes), &branch.left));
+					nodes += 1;
+				}
+				
+				Node::Empty => {
+					if let (true, Some(parent)) = (nil_nodes, parent) {
+						let _ = writeln!(dot, "    nil{} [shape=point];", nils);
+						let _ = writeln!(dot, "    n{} -> nil{};", parent, nils);
+						nils += 1;
+					}
+				}
+			}
+		}
+		
+		dot.push_str("}\n");
+		dot
+	}
+	
+	/// *English*: Method **pretty()** returns wrapper, which
+	/// *draws the tree in ASCII* with **Display** (**to_string()**, **format!()** and so on).
+	/// Check **Layout** for the drawing styles.
+	///
+	/// *Russian*: Метод **pretty()** возвращает обёртку, которая
+	/// *рисует дерево в ASCII* через **Display** (**to_string()**, **format!()** и т.д.).
+	/// Стили рисования описаны в **Layout**.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use binartree::render::Layout;
+	/// use std::iter::FromIterator;
+	///
+	/// let tree = BinaryTree::from_iter(vec![4, 2, 6, 1, 3, 7]);
+	///
+	/// assert_eq!(tree.pretty(Layout::Sideways).to_string(), "    /-- 7
+	/// /-- 6
+	/// 4
+	/// |   /-- 3
+	/// \\-- 2
+	///     \\-- 1
+	/// ");
+	///
+	/// assert_eq!(tree.pretty(Layout::TopDown).to_string(), "   ___4_
+	///   /     \\
+	///  _2_    6_
+	/// /   \\     \\
+	/// 1   3     7
+	/// ");
+	/// ```
+	
+	#[inline]
+	pub fn pretty(&self, layout: Layout) -> Pretty<'_, T> {
+		Pretty { tree: self, layout }
+	}
+}
+
+/// *English*: One node of top-down picture
+///
+/// *Russian*: Один узел картинки "сверху вниз"
+
+struct Cell {
+	label: String,
+	depth: usize,
+	column: usize,
+	left: Option<usize>,
+	right: Option<usize>,
+}
+
+impl Cell {
+
+	/// *English*: Column of the label's middle
+	///
+	/// *Russian*: Столбец середины подписи
+	
+	#[inline]
+	fn center(&self) -> usize {
+		self.column + (self.label.chars().count() - 1) / 2
+	}
+}
+
+/// *English*: Where the new cell must be linked: nowhere (top), to the frame
+/// of the parent on the stack (left child) or to the parent's cell (right child)
+///
+/// *Russian*: Куда нужно привязать новую ячейку: никуда (вершина), к кадру
+/// родителя на стеке (левый потомок) или к ячейке родителя (правый потомок)
+
+#[derive(Clone, Copy)]
+enum Link {
+	Top,
+	Left(usize),
+	Right(usize),
+}
+
+/// *English*: Places nodes in in-order, so every node has it's own columns.
+/// Uses explicit stack, so degenerate trees don't overflow the call stack.
+/// Returns width of the picture.
+///
+/// *Russian*: Расставляет узлы в порядке обхода, так что у каждого узла свои столбцы.
+/// Использует явный стек, так что вырожденные деревья не переполняют стек вызовов.
+/// Возвращает ширину картинки.
+
+fn place<T>(top: &Node<T>, cells: &mut Vec<Cell>) -> usize
+	where T: Copy + Clone + Ord + Eq + Display
+{
+	let mut column = 0;
+	let mut stack: Vec<(&Branch<T>, usize, Option<usize>, Link)> = Vec::new();
+	let mut node = top;
+	let mut depth = 0;
+	let mut link = Link::Top;
+	
+	loop {
+		while let Node::NonEmpty(ref branch) = *node {
+			stack.push((branch, depth, None, link));
+			link = Link::Left(stack.len() - 1);
+			node = &branch.left;
+			depth += 1;
+		}
+		
+		let (branch, level, left, parent) = match stack.pop() {
+			Some(frame) => frame,
+			None => return column,
+		};
+		
+		let label = branch.key.to_string();
+		let width = label.chars().count().max(1);
+		let index = cells.len();
+		cells.push(Cell {
+			label: if label.is_empty() { String::from(" ") } else { label },
+			depth: level,
+			column,
+			left,
+			right: None,
+		});
+		column += width + 1;
+		
+		match parent {
+			Link::Top => (),
+			Link::Left(frame) => stack[frame].2 = Some(index),
+			Link::Right(cell) => cells[cell].right = Some(index),
+		}
+		
+		node = &branch.right;
+		depth = level + 1;
+		link = Link::Right(index);
+	}
+}
+
+/// *English*: Side of node relative to it's parent
+///
+/// *Russian*: Сторона узла относительно его родителя
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Side {
+	Top,
+	Right,
+	Left,
+}
+
+/// *English*: Part of child's prefix, which is added under node from *side*
+///
+/// *Russian*: Часть префикса потомка, которая добавляется под узлом со стороны *side*
+
+#[inline]
+fn segment(side: Side, child: Side) -> &'static str {
+	match (side, child) {
+		(Side::Top, _) => "",
+		(Side::Right, Side::Right) | (Side::Left, Side::Left) => "    ",
+		_ => "|   ",
+	}
+}
+
+/// *English*: Draws tree sideways (right branch first). Prefix is one buffer,
+/// it's truncated to node's prefix on the way back, so it takes O(n × height) time
+/// for output only and no recursion.
+///
+/// *Russian*: Рисует дерево боком (сначала правая ветвь). Префикс - один буфер,
+/// на обратном пути он обрезается до префикса узла, так что время O(n × высота)
+/// уходит только на вывод, и рекурсии нет.
+
+fn sideways<T>(top: &Node<T>, f: &mut Formatter<'_>) -> fmt::Result
+	where T: Copy + Clone + Ord + Eq + Display
+{
+	let mut prefix = String::new();
+	let mut stack: Vec<(&Branch<T>, Side, usize)> = Vec::new();
+	let mut node = top;
+	let mut side = Side::Top;
+	
+	loop {
+		while let Node::NonEmpty(ref branch) = *node {
+			stack.push((branch, side, prefix.len()));
+			prefix.push_str(segment(side, Side::Right));
+			node = &branch.right;
+			side = Side::Right;
+		}
+		
+		let (branch, from, len) = match stack.pop() {
+			Some(frame) => frame,
+			None => return Ok(()),
+		};
+		
+		prefix.truncate(len);
+		let connector = match from {
+			Side::Top => "",
+			Side::Right => "/-- ",
+			Side::Left => "\\-- ",
+		};
+		writeln!(f, "{}{}{}", prefix, connector, branch.key)?;
+		
+		prefix.push_str(segment(from, Side::Left));
+		node = &branch.left;
+		side = Side::Left;
+	}
+}
+
+/// *English*: **Display** draws the tree with the chosen **Layout**.
+/// Empty tree is drawn as an empty string.
+///
+/// *Russian*: **Display** рисует дерево в выбранном **Layout**.
+/// Пустое дерево рисуется пустой строкой.
+
+impl<T> Display for Pretty<'_, T>
+	where T: Copy + Clone + Ord + Eq + Display
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self.layout {
+			Layout::Sideways => sideways(&self.tree.top, f),
+			
+			Layout::TopDown => {
+				let mut cells = Vec::with_capacity(self.tree.len());
+				let width = place(&self.tree.top, &mut cells);
+				
+				let height = cells.iter().map(|cell| cell.depth + 1).max().unwrap_or(0);
+				let mut lines = vec![vec![' '; width]; height * 2];
+				
+				for cell in &cells {
+					let keys = cell.depth * 2;
+					let end = cell.column + cell.label.chars().count();
+					
+					for (i, c) in cell.label.chars().enumerate() {
+						lines[keys][cell.column + i] = c;
+					}
+					
+					if let Some(left) = cell.left {
+						let left = cells[left].center();
+						lines[keys][left + 1..cell.column].iter_mut().for_each(|c| *c = '_');
+						lines[keys + 1][left] = '/';
+					}
+					
+					if let Some(right) = cell.right {
+						let right = cells[right].center();
+						lines[keys][end..right].iter_mut().for_each(|c| *c = '_');
+						lines[keys + 1][right] = '\\';
+					}
+				}
+				
+				for line in lines.iter().take((height * 2).saturating_sub(1)) {
+					let line = line.iter().collect::<String>();
+					writeln!(f, "{}", line.trim_end())?;
+				}
+				Ok(())
+			}
+		}
+	}
+}
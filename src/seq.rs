@@ -0,0 +1,543 @@
+use crate::iter::TreeIter;
+use alloc::collections::VecDeque;
+use alloc::collections::vec_deque;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::iter::FromIterator;
+use core::mem;
+use core::ops::{Bound, RangeBounds};
+
+/// **Sorted sequence**
+/// -------------------
+
+/// *English*: **SortedSeq** is a sequence, which is *always sorted*.
+/// It's returned from set operations of tree. Unlike **TreeIter**,
+/// it allows only edits that keep the order: binary search insertion,
+/// merging, removing duplicates, taking ranges and removing elements.
+/// Like **TreeIter**, it's an iterator, which *empties itself*.
+///
+/// *Russian*: **SortedSeq** - последовательность, которая *всегда отсортирована*.
+/// Её возвращают операции над множествами у дерева. В отличие от **TreeIter**,
+/// она допускает только изменения, сохраняющие порядок: вставку двоичным поиском,
+/// слияние, удаление повторов, взятие диапазонов и удаление элементов.
+/// Как и **TreeIter**, это итератор, который *опустошает себя*.
+///
+/// # Example
+///
+/// ```
+/// use binartree::seq::SortedSeq;
+/// use std::iter::FromIterator;
+///
+/// let mut seq = SortedSeq::from_iter(vec![3, 1, 2]);
+/// seq.insert(&2);
+/// assert_eq!(seq.to_vec(), vec![1, 2, 2, 3]);
+///
+/// seq.dedup();
+/// assert_eq!(seq.collect::<Vec<i32>>(), vec![1, 2, 3]);
+/// ```
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SortedSeq<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	seq: VecDeque<T>
+}
+
+impl<T> Default for SortedSeq<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn default() -> Self {
+		SortedSeq::new()
+	}
+}
+
+impl<T> SortedSeq<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	/// *English*: Method **new()** creates *empty* sequence
+	///
+	/// *Russian*: Метод **new()** создаёт *пустую* последовательность
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::seq::SortedSeq;
+	///
+	/// let seq = SortedSeq::<i32>::new();
+	/// assert!(seq.is_empty());
+	/// ```
+	
+	#[inline]
+	pub fn new() -> Self {
+		SortedSeq { seq: VecDeque::new() }
+	}
+	
+	/// *English*: Method **with_capacity()** creates *empty* sequence
+	/// with place for *capacity* elements
+	///
+	/// *Russian*: Метод **with_capacity()** создаёт *пустую* последовательность
+	/// с местом для *capacity* элементов
+	
+	#[inline]
+	pub fn with_capacity(capacity: usize) -> Self {
+		SortedSeq { seq: VecDeque::with_capacity(capacity) }
+	}
+	
+	/// *English*: Wraps deque, which is already sorted
+	///
+	/// *Russian*: Оборачивает дек, который уже отсортирован
+	
+	#[inline]
+	pub(crate) fn from_sorted(seq: VecDeque<T>) -> Self {
+		debug_assert!(seq.iter().zip(seq.iter().skip(1)).all(|(a, b)| a <= b));
+		SortedSeq { seq }
+	}
+	
+	/// *English*: Method **len()** returns number of elements
+	///
+	/// *Russian*: Метод **len()** возвращает количество элементов
+	
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.seq.len()
+	}
+	
+	/// *English*: Method **is_empty()** checks if sequence is empty
+	///
+	/// *Russian*: Метод **is_empty()** проверяет, пуста ли последовательность
+	
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.seq.is_empty()
+	}
+	
+	/// *English*: Method **clear()** removes all elements
+	///
+	/// *Russian*: Метод **clear()** удаляет все элементы
+	
+	#[inline]
+	pub fn clear(&mut self) {
+		self.seq.clear()
+	}
+	
+	/// *English*: Method **first()** returns min element or *None*, if sequence is empty
+	///
+	/// *Russian*: Метод **first()** возвращает наименьший элемент
+	/// или *None*, если последовательность пуста
+	
+	#[inline]
+	pub fn first(&self) -> Option<&T> {
+		self.seq.front()
+	}
+	
+	/// *English*: Method **last()** returns max element or *None*, if sequence is empty
+	///
+	/// *Russian*: Метод **last()** возвращает наибольший элемент
+	/// или *None*, если последовательность пуста
+	
+	#[inline]
+	pub fn last(&self) -> Option<&T> {
+		self.seq.back()
+	}
+	
+	/// *English*: Method **get()** returns element by index or *None*, if index is out of bounds
+	///
+	/// *Russian*: Метод **get()** возвращает элемент по индексу
+	/// или *None*, если индекс за границами
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::seq::SortedSeq;
+	/// use std::iter::FromIterator;
+	///
+	/// let seq = SortedSeq::from_iter(vec![30, 10, 20]);
+	/// assert_eq!(seq.get(1), Some(&20));
+	/// assert_eq!(seq.get(3), None);
+	/// ```
+	
+	#[inline]
+	pub fn get(&self, index: usize) -> Option<&T> {
+		self.seq.get(index)
+	}
+	
+	/// *English*: Method **iter()** returns *borrowing* iterator, which doesn't empty sequence
+	///
+	/// *Russian*: Метод **iter()** возвращает *заимствующий* итератор,
+	/// который не опустошает последовательность
+	
+	#[inline]
+	pub fn iter(&self) -> vec_deque::Iter<'_, T> {
+		self.seq.iter()
+	}
+	
+	/// *English*: Method **binary_search()** searches value like **slice::binary_search()**:
+	/// returns *Ok(index)* of equal element or *Err(index)*, where it may be inserted
+	///
+	/// *Russian*: Метод **binary_search()** ищет значение как **slice::binary_search()**:
+	/// возвращает *Ok(индекс)* равного элемента или *Err(индекс)*, куда его можно вставить
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::seq::SortedSeq;
+	/// use std::iter::FromIterator;
+	///
+	/// let seq = SortedSeq::from_iter(vec![1, 3, 5]);
+	/// assert_eq!(seq.binary_search(&3), Ok(1));
+	/// assert_eq!(seq.binary_search(&4), Err(2));
+	/// ```
+	
+	#[inline]
+	pub fn binary_search<Q>(&self, val: &Q) -> Result<usize, usize>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.seq.binary_search_by(|elem| elem.borrow().cmp(val))
+	}
+	
+	/// *English*: Method **contains()** checks if value is in sequence. Takes O(log n)
+	///
+	/// *Russian*: Метод **contains()** проверяет, есть ли значение в последовательности.
+	/// Занимает O(log n)
+	
+	#[inline]
+	pub fn contains<Q>(&self, val: &Q) -> bool
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.binary_search(val).is_ok()
+	}
+	
+	/// *English*: Method **insert()** puts value after all elements,
+	/// which are not greater than it, and returns it's index
+	///
+	/// *Russian*: Метод **insert()** ставит значение после всех элементов,
+	/// которые не больше него, и возвращает его индекс
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::seq::SortedSeq;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut seq = SortedSeq::from_iter(vec![1, 2, 4]);
+	/// assert_eq!(seq.insert(&3), 2);
+	/// assert_eq!(seq.insert(&1), 1);
+	/// assert_eq!(seq.to_vec(), vec![1, 1, 2, 3, 4]);
+	/// ```
+	
+	pub fn insert(&mut self, val: &T) -> usize {
+		let index = self.seq.partition_point(|elem| elem <= val);
+		self.seq.insert(index, *val);
+		index
+	}
+	
+	/// *English*: Method **remove()** removes one occurrence of value.
+	/// Returns *true*, if value was in sequence
+	///
+	/// *Russian*: Метод **remove()** удаляет одно вхождение значения.
+	/// Возвращает *true*, если значение было в последовательности
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::seq::SortedSeq;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut seq = SortedSeq::from_iter(vec![1, 2, 2]);
+	/// assert!(seq.remove(&2));
+	/// assert!(!seq.remove(&3));
+	/// assert_eq!(seq.to_vec(), vec![1, 2]);
+	/// ```
+	
+	pub fn remove<Q>(&mut self, val: &Q) -> bool
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		match self.binary_search(val) {
+			Ok(index) => self.seq.remove(index).is_some(),
+			Err(_) => false,
+		}
+	}
+	
+	/// *English*: Method **remove_at()** removes element by index or returns *None*,
+	/// if index is out of bounds
+	///
+	/// *Russian*: Метод **remove_at()** удаляет элемент по индексу или возвращает *None*,
+	/// если индекс за границами
+	
+	#[inline]
+	pub fn remove_at(&mut self, index: usize) -> Option<T> {
+		self.seq.remove(index)
+	}
+	
+	/// *English*: Method **pop_first()** removes and returns min element
+	///
+	/// *Russian*: Метод **pop_first()** удаляет и возвращает наименьший элемент
+	
+	#[inline]
+	pub fn pop_first(&mut self) -> Option<T> {
+		self.seq.pop_front()
+	}
+	
+	/// *English*: Method **pop_last()** removes and returns max element
+	///
+	/// *Russian*: Метод **pop_last()** удаляет и возвращает наибольший элемент
+	
+	#[inline]
+	pub fn pop_last(&mut self) -> Option<T> {
+		self.seq.pop_back()
+	}
+	
+	/// *English*: Method **merge()** moves all elements of another sequence
+	/// to this one in O(n + m). Equal elements of this sequence go first.
+	///
+	/// *Russian*: Метод **merge()** переносит все элементы другой последовательности
+	/// в эту за O(n + m). Равные элементы этой последовательности идут первыми.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::seq::SortedSeq;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut seq = SortedSeq::from_iter(vec![1, 3, 5]);
+	/// seq.merge(SortedSeq::from_iter(vec![2, 3, 6]));
+	/// assert_eq!(seq.to_vec(), vec![1, 2, 3, 3, 5, 6]);
+	/// ```
+	
+	pub fn merge(&mut self, other: Self) {
+		let mut merged = VecDeque::with_capacity(self.len() + other.len());
+		let mut left = mem::take(&mut self.seq).into_iter().peekable();
+		let mut right = other.seq.into_iter().peekable();
+		
+		loop {
+			let take_left = match (left.peek(), right.peek()) {
+				(Some(a), Some(b)) => a <= b,
+				(Some(_), None) => true,
+				(None, Some(_)) => false,
+				(None, None) => break,
+			};
+			
+			if take_left {
+				merged.extend(left.next());
+			} else {
+				merged.extend(right.next());
+			}
+		}
+		
+		self.seq = merged;
+	}
+	
+	/// *English*: Method **dedup()** removes all duplicates.
+	/// Sequence is sorted, so equal elements are always neighbours.
+	///
+	/// *Russian*: Метод **dedup()** удаляет все повторы.
+	/// Последовательность отсортирована, так что равные элементы всегда соседи.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::seq::SortedSeq;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut seq = SortedSeq::from_iter(vec![2, 1, 2, 1, 3]);
+	/// seq.dedup();
+	/// assert_eq!(seq.to_vec(), vec![1, 2, 3]);
+	/// ```
+	
+	pub fn dedup(&mut self) {
+		let mut last = None;
+		self.seq.retain(|elem| last.replace(*elem) != Some(*elem));
+	}
+	
+	/// *English*: Method **range()** returns new sequence with elements from range.
+	/// Bounds are found with binary search, so it takes O(log n + number of found elements)
+	///
+	/// *Russian*: Метод **range()** возвращает новую последовательность с элементами из диапазона.
+	/// Границы находятся двоичным поиском, так что это занимает O(log n + количества найденных элементов)
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::seq::SortedSeq;
+	/// use std::iter::FromIterator;
+	///
+	/// let seq = SortedSeq::from_iter(1..10);
+	/// assert_eq!(seq.range(3..6).to_vec(), vec![3, 4, 5]);
+	/// assert_eq!(seq.range(..=2).to_vec(), vec![1, 2]);
+	/// ```
+	
+	pub fn range<Q, R>(&self, range: R) -> Self
+		where T: Borrow<Q>, Q: Ord + ?Sized, R: RangeBounds<Q>
+	{
+		let start = match range.start_bound() {
+			Bound::Included(start) => self.seq.partition_point(|elem| elem.borrow() < start),
+			Bound::Excluded(start) => self.seq.partition_point(|elem| elem.borrow() <= start),
+			Bound::Unbounded => 0,
+		};
+		let end = match range.end_bound() {
+			Bound::Included(end) => self.seq.partition_point(|elem| elem.borrow() <= end),
+			Bound::Excluded(end) => self.seq.partition_point(|elem| elem.borrow() < end),
+			Bound::Unbounded => self.len(),
+		};
+		
+		SortedSeq {
+			seq: self.seq.range(start..end.max(start)).copied().collect()
+		}
+	}
+	
+	/// *English*: Method **retain()** keeps only elements, for which predicate returns *true*
+	///
+	/// *Russian*: Метод **retain()** оставляет только элементы, для которых предикат возвращает *true*
+	
+	#[inline]
+	pub fn retain<F: FnMut(&T) -> bool>(&mut self, fun: F) {
+		self.seq.retain(fun)
+	}
+	
+	/// *English*: Method **truncate()** keeps only *len* smallest elements
+	///
+	/// *Russian*: Метод **truncate()** оставляет только *len* наименьших элементов
+	
+	#[inline]
+	pub fn truncate(&mut self, len: usize) {
+		self.seq.truncate(len)
+	}
+	
+	/// *English*: Method **split_off()** splits sequence at index:
+	/// elements after it are moved to the returned sequence.
+	/// Panics if index is out of bounds
+	///
+	/// *Russian*: Метод **split_off()** разделяет последовательность по индексу:
+	/// элементы после него переносятся в возвращаемую последовательность.
+	/// Паникует, если индекс за границами
+	
+	#[inline]
+	pub fn split_off(&mut self, at: usize) -> Self {
+		SortedSeq { seq: self.seq.split_off(at) }
+	}
+	
+	/// *English*: Method **to_vec()** copies elements to vector
+	///
+	/// *Russian*: Метод **to_vec()** копирует элементы в вектор
+	
+	#[inline]
+	pub fn to_vec(&self) -> Vec<T> {
+		self.seq.iter().copied().collect()
+	}
+	
+	/// *English*: Method **to_deque()** copies elements to deque
+	///
+	/// *Russian*: Метод **to_deque()** копирует элементы в дек
+	
+	#[inline]
+	pub fn to_deque(&self) -> VecDeque<T> {
+		self.seq.clone()
+	}
+}
+
+/// *English*: **SortedSeq** is an iterator, which gives elements in *ascending* order
+///
+/// *Russian*: **SortedSeq** - итератор, который выдаёт элементы *по возрастанию*
+
+impl<T> Iterator for SortedSeq<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Item = T;
+	
+	#[inline]
+	fn next(&mut self) -> Option<T> {
+		self.seq.pop_front()
+	}
+	
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.len(), Some(self.len()))
+	}
+}
+
+impl<T> DoubleEndedIterator for SortedSeq<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn next_back(&mut self) -> Option<T> {
+		self.seq.pop_back()
+	}
+}
+
+impl<T> ExactSizeIterator for SortedSeq<T>
+	where T: Copy + Clone + Ord + Eq
+{}
+
+/// *English*: Sequence is built from any iterator, elements are sorted
+///
+/// *Russian*: Последовательность строится из любого итератора, элементы сортируются
+///
+/// # Example
+///
+/// ```
+/// use binartree::seq::SortedSeq;
+/// use std::iter::FromIterator;
+///
+/// let seq = SortedSeq::from_iter(vec![2, 3, 1]);
+/// assert_eq!(seq.to_vec(), vec![1, 2, 3]);
+/// ```
+
+impl<T> FromIterator<T> for SortedSeq<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+		let mut vec = iter.into_iter().collect::<Vec<T>>();
+		vec.sort();
+		SortedSeq { seq: VecDeque::from(vec) }
+	}
+}
+
+/// *English*: **Extend<T>** sorts new elements and merges them into sequence
+///
+/// *Russian*: **Extend<T>** сортирует новые элементы и сливает их с последовательностью
+///
+/// # Example
+///
+/// ```
+/// use binartree::seq::SortedSeq;
+/// use std::iter::FromIterator;
+///
+/// let mut seq = SortedSeq::from_iter(vec![1, 5]);
+/// seq.extend(vec![4, 2]);
+/// assert_eq!(seq.to_vec(), vec![1, 2, 4, 5]);
+/// ```
+
+impl<T> Extend<T> for SortedSeq<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+		self.merge(SortedSeq::from_iter(iter));
+	}
+}
+
+/// *English*: Converts sequence to **TreeIter**, which allows any edits
+///
+/// *Russian*: Превращает последовательность в **TreeIter**, который позволяет любые изменения
+
+impl<T> From<SortedSeq<T>> for TreeIter<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn from(seq: SortedSeq<T>) -> Self {
+		TreeIter { iter: seq.seq }
+	}
+}
+
+/// *English*: Converts sequence to vector
+///
+/// *Russian*: Превращает последовательность в вектор
+
+impl<T> From<SortedSeq<T>> for Vec<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn from(seq: SortedSeq<T>) -> Self {
+		Vec::from(seq.seq)
+	}
+}
@@ -0,0 +1,427 @@
+use crate::btree::BTree;
+use crate::flat::FlatTree;
+use crate::iter::TreeIter;
+use crate::seq::SortedSeq;
+use crate::tree::BinaryTree;
+use alloc::collections::{BTreeSet, VecDeque};
+use alloc::vec::Vec;
+use core::ops::{Bound, RangeBounds};
+
+/// **Ordered Set**
+/// ---------------
+///
+/// *English*: **OrderedSet** is a common interface of sorted collections, so code
+/// can take any backend: **BinaryTree**, **BTree**, **FlatTree** or std **BTreeSet**.
+/// Methods take and return values, not references, like the rest of the crate.
+/// Duplicates are handled by the backend: trees follow their **DuplicatePolicy**,
+/// **BTreeSet** always rejects them. Use **conformance()** to check a new backend.
+///
+/// *Russian*: **OrderedSet** - общий интерфейс отсортированных коллекций, так что код
+/// может принимать любую реализацию: **BinaryTree**, **BTree**, **FlatTree** или **BTreeSet**
+/// из std. Методы принимают и возвращают значения, а не ссылки, как и весь крейт.
+/// Повторы обрабатывает реализация: деревья следуют своей **DuplicatePolicy**,
+/// **BTreeSet** всегда их отвергает. Для проверки новой реализации есть **conformance()**.
+///
+/// # Example
+///
+/// ```
+/// use binartree::set::OrderedSet;
+/// use binartree::tree::{BinaryTree, DuplicatePolicy};
+/// use std::collections::BTreeSet;
+///
+/// fn middle<S: OrderedSet<i32>>(set: &S) -> Option<i32> {
+///     set.iter().nth(set.len() / 2)
+/// }
+///
+/// let mut tree = BinaryTree::with_policy(DuplicatePolicy::Reject);
+/// let mut std_set = BTreeSet::new();
+/// for val in [5, 1, 4, 2, 3] {
+///     OrderedSet::insert(&mut tree, &val);
+///     OrderedSet::insert(&mut std_set, &val);
+/// }
+///
+/// assert_eq!(middle(&tree), Some(3));
+/// assert_eq!(middle(&std_set), Some(3));
+/// ```
+
+pub trait OrderedSet<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	/// *English*: Number of elements
+	///
+	/// *Russian*: Количество элементов
+	
+	fn len(&self) -> usize;
+	
+	/// *English*: Checks that set has no elements
+	///
+	/// *Russian*: Проверяет, что в множестве нет элементов
+	
+	#[inline]
+	fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+	
+	/// *English*: Adds value. Returns *true*, if set was changed.
+	///
+	/// *Russian*: Добавляет значение. Возвращает *true*, если множество изменилось.
+	
+	fn insert(&mut self, val: &T) -> bool;
+	
+	/// *English*: Removes one occurrence of value. Returns *true*, if it was there.
+	///
+	/// *Russian*: Удаляет одно вхождение значения. Возвращает *true*, если оно было.
+	
+	fn remove(&mut self, val: &T) -> bool;
+	
+	/// *English*: Checks that value is in the set
+	///
+	/// *Russian*: Проверяет наличие значения в множестве
+	
+	fn contains(&self, val: &T) -> bool;
+	
+	/// *English*: Min value or *None*, if set is empty
+	///
+	/// *Russian*: Наименьшее значение или *None*, если множество пустое
+	
+	fn first(&self) -> Option<T>;
+	
+	/// *English*: Max value or *None*, if set is empty
+	///
+	/// *Russian*: Наибольшее значение или *None*, если множество пустое
+	
+	fn last(&self) -> Option<T>;
+	
+	/// *English*: Sorted iterator over all values
+	///
+	/// *Russian*: Отсортированный итератор по всем значениям
+	
+	fn iter(&self) -> TreeIter<T>;
+	
+	/// *English*: Sorted iterator over values from range.
+	/// Empty or inverted range gives empty iterator, never panic.
+	///
+	/// *Russian*: Отсортированный итератор по значениям из диапазона.
+	/// Пустой или перевёрнутый диапазон даёт пустой итератор, а не панику.
+	
+	fn range<R: RangeBounds<T>>(&self, range: R) -> TreeIter<T>;
+	
+	/// *English*: Sorted values of both sets
+	///
+	/// *Russian*: Отсортированные значения обоих множеств
+	
+	fn union(&self, other: &Self) -> SortedSeq<T>;
+	
+	/// *English*: Sorted values, which are in both sets
+	///
+	/// *Russian*: Отсортированные значения, которые есть в обоих множествах
+	
+	fn intersection(&self, other: &Self) -> SortedSeq<T>;
+	
+	/// *English*: Sorted values, which are in this set, but not in other
+	///
+	/// *Russian*: Отсортированные значения, которые есть в этом множестве, но нет в другом
+	
+	fn difference(&self, other: &Self) -> SortedSeq<T>;
+	
+	/// *English*: Sorted values, which are only in one of sets
+	///
+	/// *Russian*: Отсортированные значения, которые есть только в одном из множеств
+	
+	fn symmetric_difference(&self, other: &Self) -> SortedSeq<T>;
+	
+	/// *English*: Checks that sets have no values in common
+	///
+	/// *Russian*: Проверяет, что у множеств нет общих значений
+	
+	#[inline]
+	fn is_disjoint(&self, other: &Self) -> bool {
+		self.intersection(other).is_empty()
+	}
+	
+	/// *English*: Checks that all values of this set are in other
+	///
+	/// *Russian*: Проверяет, что все значения этого множества есть в другом
+	
+	#[inline]
+	fn is_subset(&self, other: &Self) -> bool {
+		self.difference(other).is_empty()
+	}
+}
+
+/// *English*: Implements **OrderedSet** for tree type with inherent methods
+/// of the same names (all trees of the crate have them)
+///
+/// *Russian*: Реализует **OrderedSet** для типа дерева с собственными методами
+/// тех же имён (они есть у всех деревьев крейта)
+
+macro_rules! impl_ordered_set {
+	($tree:ident) => {
+		impl<T> OrderedSet<T> for $tree<T>
+			where T: Copy + Clone + Ord + Eq
+		{
+			#[inline]
+			fn len(&self) -> usize {
+				$tree::len(self)
+			}
+			
+			#[inline]
+			fn insert(&mut self, val: &T) -> bool {
+				$tree::insert(self, val)
+			}
+			
+			#[inline]
+			fn remove(&mut self, val: &T) -> bool {
+				$tree::try_remove(self, val).is_ok()
+			}
+			
+			#[inline]
+			fn contains(&self, val: &T) -> bool {
+				$tree::contains(self, val)
+			}
+			
+			#[inline]
+			fn first(&self) -> Option<T> {
+				$tree::try_first(self).ok().copied()
+			}
+			
+			#[inline]
+			fn last(&self) -> Option<T> {
+				$tree::try_last(self).ok().copied()
+			}
+			
+			#[inline]
+			fn iter(&self) -> TreeIter<T> {
+				$tree::iter(self).collect()
+			}
+			
+			#[inline]
+			fn range<R: RangeBounds<T>>(&self, range: R) -> TreeIter<T> {
+				$tree::range(self, range).collect()
+			}
+			
+			#[inline]
+			fn union(&self, other: &Self) -> SortedSeq<T> {
+				$tree::union(self, other)
+			}
+			
+			#[inline]
+			fn intersection(&self, other: &Self) -> SortedSeq<T> {
+				$tree::intersection(self, other)
+			}
+			
+			#[inline]
+			fn difference(&self, other: &Self) -> SortedSeq<T> {
+				$tree::difference(self, other)
+			}
+			
+			#[inline]
+			fn symmetric_difference(&self, other: &Self) -> SortedSeq<T> {
+				$tree::symmetric_difference(self, other)
+			}
+		}
+	};
+}
+
+impl_ordered_set!(BinaryTree);
+impl_ordered_set!(BTree);
+impl_ordered_set!(FlatTree);
+
+/// *English*: Checks that range is valid for **BTreeSet::range()**, which panics
+/// on inverted ranges and on equal excluded bounds
+///
+/// *Russian*: Проверяет, что диапазон допустим для **BTreeSet::range()**, который паникует
+/// на перевёрнутых диапазонах и на равных исключённых границах
+
+fn is_valid_range<T: Ord, R: RangeBounds<T>>(range: &R) -> bool {
+	match (range.start_bound(), range.end_bound()) {
+		(Bound::Excluded(start), Bound::Excluded(end)) => start < end,
+		(Bound::Included(start), Bound::Included(end))
+		| (Bound::Included(start), Bound::Excluded(end))
+		| (Bound::Excluded(start), Bound::Included(end)) => start <= end,
+		_ => true,
+	}
+}
+
+/// *English*: **BTreeSet** of std as **OrderedSet**
+///
+/// *Russian*: **BTreeSet** из std как **OrderedSet**
+
+impl<T> OrderedSet<T> for BTreeSet<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn len(&self) -> usize {
+		BTreeSet::len(self)
+	}
+	
+	#[inline]
+	fn insert(&mut self, val: &T) -> bool {
+		BTreeSet::insert(self, *val)
+	}
+	
+	#[inline]
+	fn remove(&mut self, val: &T) -> bool {
+		BTreeSet::remove(self, val)
+	}
+	
+	#[inline]
+	fn contains(&self, val: &T) -> bool {
+		BTreeSet::contains(self, val)
+	}
+	
+	#[inline]
+	fn first(&self) -> Option<T> {
+		BTreeSet::first(self).copied()
+	}
+	
+	#[inline]
+	fn last(&self) -> Option<T> {
+		BTreeSet::last(self).copied()
+	}
+	
+	#[inline]
+	fn iter(&self) -> TreeIter<T> {
+		BTreeSet::iter(self).copied().collect()
+	}
+	
+	fn range<R: RangeBounds<T>>(&self, range: R) -> TreeIter<T> {
+		if !is_valid_range(&range) {
+			return TreeIter::default();
+		}
+		BTreeSet::range(self, range).copied().collect()
+	}
+	
+	#[inline]
+	fn union(&self, other: &Self) -> SortedSeq<T> {
+		SortedSeq::from_sorted(BTreeSet::union(self, other).copied().collect::<VecDeque<T>>())
+	}
+	
+	#[inline]
+	fn intersection(&self, other: &Self) -> SortedSeq<T> {
+		SortedSeq::from_sorted(BTreeSet::intersection(self, other).copied().collect::<VecDeque<T>>())
+	}
+	
+	#[inline]
+	fn difference(&self, other: &Self) -> SortedSeq<T> {
+		SortedSeq::from_sorted(BTreeSet::difference(self, other).copied().collect::<VecDeque<T>>())
+	}
+	
+	#[inline]
+	fn symmetric_difference(&self, other: &Self) -> SortedSeq<T> {
+		SortedSeq::from_sorted(BTreeSet::symmetric_difference(self, other).copied().collect::<VecDeque<T>>())
+	}
+}
+
+/// *English*: Function **conformance()** runs random workload against **OrderedSet**
+/// and compares every answer with a simple sorted vector. Sets made by *make* must
+/// reject duplicates (trees with *DuplicatePolicy::Reject*). Panics on the first
+/// mismatch, so it can be called from a test of any backend. *seed* makes
+/// the workload reproducible.
+///
+/// *Russian*: Функция **conformance()** выполняет случайную нагрузку на **OrderedSet**
+/// и сравнивает каждый ответ с простым отсортированным вектором. Множества, созданные
+/// *make*, должны отвергать повторы (деревья с *DuplicatePolicy::Reject*). Паникует
+/// при первом расхождении, так что её можно вызывать из теста любой реализации.
+/// *seed* делает нагрузку воспроизводимой.
+///
+/// # Example
+///
+/// ```
+/// use binartree::set::conformance;
+/// use binartree::tree::{BinaryTree, DuplicatePolicy};
+/// use std::collections::BTreeSet;
+///
+/// conformance(BTreeSet::new, 1);
+/// conformance(|| BinaryTree::with_policy(DuplicatePolicy::Reject), 1);
+/// ```
+
+pub fn conformance<S, F>(make: F, mut seed: u64)
+	where S: OrderedSet<i32>, F: Fn() -> S
+{
+	let mut next = move |modulo: u64| -> i32 {
+		seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+		((seed >> 33) % modulo) as i32
+	};
+	
+	let mut set = make();
+	let mut model: Vec<i32> = Vec::new();
+	assert!(set.is_empty());
+	assert_eq!(set.first(), None);
+	assert_eq!(set.last(), None);
+	assert_eq!(set.iter().len(), 0);
+	
+	for _ in 0..2000 {
+		let val = next(200);
+		let found = model.binary_search(&val);
+		
+		if next(3) < 1 {
+			assert_eq!(set.remove(&val), found.is_ok(), "remove({})", val);
+			if let Ok(index) = found {
+				model.remove(index);
+			}
+		} else {
+			assert_eq!(set.insert(&val), found.is_err(), "insert({})", val);
+			if let Err(index) = found {
+				model.insert(index, val);
+			}
+		}
+		
+		let probe = next(210) - 5;
+		assert_eq!(set.len(), model.len());
+		assert_eq!(set.is_empty(), model.is_empty());
+		assert_eq!(set.contains(&probe), model.binary_search(&probe).is_ok(), "contains({})", probe);
+		assert_eq!(set.first(), model.first().copied());
+		assert_eq!(set.last(), model.last().copied());
+	}
+	assert_eq!(set.iter().collect::<Vec<i32>>(), model);
+	
+	for _ in 0..200 {
+		let (start, end) = (next(210) - 5, next(210) - 5);
+		let filter = |range: &dyn Fn(i32) -> bool| model.iter().copied().filter(|x| range(*x)).collect::<Vec<i32>>();
+		
+		assert_eq!(set.range(start..end).collect::<Vec<i32>>(), filter(&|x| start <= x && x < end), "range({}..{})", start, end);
+		assert_eq!(set.range(start..=end).collect::<Vec<i32>>(), filter(&|x| start <= x && x <= end), "range({}..={})", start, end);
+		assert_eq!(set.range((Bound::Excluded(start), Bound::Excluded(end))).collect::<Vec<i32>>(), filter(&|x| start < x && x < end));
+		assert_eq!(set.range(start..).collect::<Vec<i32>>(), filter(&|x| start <= x));
+		assert_eq!(set.range(..end).collect::<Vec<i32>>(), filter(&|x| x < end));
+	}
+	
+	let mut other = make();
+	let mut other_model = Vec::new();
+	for _ in 0..150 {
+		let val = next(300);
+		if other.insert(&val) {
+			other_model.push(val);
+		}
+	}
+	other_model.sort();
+	
+	let in_other = |x: &i32| other_model.binary_search(x).is_ok();
+	let in_set = |x: &i32| model.binary_search(x).is_ok();
+	let both = model.iter().copied().filter(|x| in_other(x)).collect::<Vec<i32>>();
+	let only_set = model.iter().copied().filter(|x| !in_other(x)).collect::<Vec<i32>>();
+	let only_other = other_model.iter().copied().filter(|x| !in_set(x)).collect::<Vec<i32>>();
+	let mut all = model.iter().chain(other_model.iter()).copied().collect::<Vec<i32>>();
+	all.sort();
+	all.dedup();
+	let mut either = only_set.iter().chain(only_other.iter()).copied().collect::<Vec<i32>>();
+	either.sort();
+	
+	assert_eq!(set.union(&other).to_vec(), all);
+	assert_eq!(set.intersection(&other).to_vec(), both);
+	assert_eq!(set.difference(&other).to_vec(), only_set);
+	assert_eq!(set.symmetric_difference(&other).to_vec(), either);
+	assert_eq!(set.is_disjoint(&other), both.is_empty());
+	assert_eq!(set.is_subset(&other), only_set.is_empty());
+	assert!(set.is_subset(&set));
+	assert_eq!(set.is_disjoint(&set), set.is_empty());
+	
+	while let Some(first) = set.first() {
+		assert_eq!(Some(first), model.first().copied());
+		assert!(set.remove(&first));
+		assert!(!set.contains(&first));
+		model.remove(0);
+	}
+	assert!(set.is_empty() && model.is_empty());
+}
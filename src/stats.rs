@@ -0,0 +1,153 @@
+use crate::node::Node;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+/// *English*: **TreeStats** is a report about the *shape* of the tree.
+/// It's built with one *Breadth-First-Search (BFS)* walk, so it costs O(n).
+/// Depths are counted from the *top node*, which has depth 0.
+///
+/// *Russian*: **TreeStats** - отчёт о *форме* дерева.
+/// Строится одним *обходом в ширину*, так что стоит O(n).
+/// Глубина считается от *головного узла*, глубина которого равна 0.
+///
+/// # Example
+///
+/// ```
+/// use binartree::tree::BinaryTree;
+/// use std::iter::FromIterator;
+///
+/// let tree = BinaryTree::from_iter(vec![2, 1, 3]);
+/// let stats = tree.stats();
+///
+/// assert_eq!(stats.len, 3);
+/// assert_eq!(stats.height, 2);
+/// assert_eq!(stats.leaf_count, 2);
+/// assert_eq!(stats.level_sizes, vec![1, 2]);
+/// assert_eq!(stats.imbalance_ratio, 1.0);
+/// ```
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeStats {
+	/// *English*: Number of nodes
+	///
+	/// *Russian*: Количество узлов
+	pub len: usize,
+	
+	/// *English*: Number of levels (0 for an empty tree)
+	///
+	/// *Russian*: Количество уровней (0 для пустого дерева)
+	pub height: usize,
+	
+	/// *English*: Number of nodes without children
+	///
+	/// *Russian*: Количество узлов без потомков
+	pub leaf_count: usize,
+	
+	/// *English*: Depth of the closest leaf (0 for an empty tree)
+	///
+	/// *Russian*: Глубина ближайшего листа (0 для пустого дерева)
+	pub min_leaf_depth: usize,
+	
+	/// *English*: Depth of the deepest leaf (0 for an empty tree)
+	///
+	/// *Russian*: Глубина самого глубокого листа (0 для пустого дерева)
+	pub max_leaf_depth: usize,
+	
+	/// *English*: Average depth of all leaves (0.0 for an empty tree)
+	///
+	/// *Russian*: Средняя глубина листьев (0.0 для пустого дерева)
+	pub avg_leaf_depth: f64,
+	
+	/// *English*: Number of nodes on every level, starting from the top
+	///
+	/// *Russian*: Количество узлов на каждом уровне, начиная с головного
+	pub level_sizes: Vec<usize>,
+	
+	/// *English*: Height divided by the minimal possible height for this length.
+	/// 1.0 means that the tree is perfectly balanced, *len / (⌊log2(len)⌋ + 1)* means that it's a list.
+	///
+	/// *Russian*: Высота, делённая на минимально возможную высоту для такой длины.
+	/// 1.0 - дерево идеально сбалансировано, *len / (⌊log2(len)⌋ + 1)* - дерево выродилось в список.
+	pub imbalance_ratio: f64,
+}
+
+/// *English*: Minimal height of the tree with *len* nodes: ⌊log2(len)⌋ + 1
+///
+/// *Russian*: Минимальная высота дерева из *len* узлов: ⌊log2(len)⌋ + 1
+
+#[inline]
+pub(crate) fn min_height(len: usize) -> usize {
+	(usize::BITS - len.leading_zeros()) as usize
+}
+
+impl TreeStats {
+	
+	/// *English*: Collects statistics of the subtree with BFS
+	///
+	/// *Russian*: Собирает статистику поддерева обходом в ширину
+	
+	pub(crate) fn from_node<T>(top: &Node<T>) -> Self
+		where T: Copy + Clone + Ord + Eq
+	{
+		let mut len = 0;
+		let mut leaf_count = 0;
+		let mut min_leaf_depth = usize::MAX;
+		let mut max_leaf_depth = 0;
+		let mut leaf_depth_sum = 0;
+		let mut level_sizes = Vec::new();
+		
+		let mut queue = VecDeque::new();
+		if let Node::NonEmpty(ref branch) = *top {
+			queue.push_back((branch, 0));
+		}
+		
+		while let Some((branch, depth)) = queue.pop_front() {
+			if level_sizes.len() == depth {
+				level_sizes.push(0);
+			}
+			level_sizes[depth] += 1;
+			len += 1;
+			
+			match (&branch.left, &branch.right) {
+				(Node::Empty, Node::Empty) => {
+					leaf_count += 1;
+					leaf_depth_sum += depth;
+					min_leaf_depth = min_leaf_depth.min(depth);
+					max_leaf_depth = max_leaf_depth.max(depth);
+				}
+				(left, right) => {
+					if let Node::NonEmpty(ref branch) = *left {
+						queue.push_back((branch, depth + 1));
+					}
+					if let Node::NonEmpty(ref branch) = *right {
+						queue.push_back((branch, depth + 1));
+					}
+				}
+			}
+		}
+		
+		if len == 0 {
+			return TreeStats {
+				len: 0,
+				height: 0,
+				leaf_count: 0,
+				min_leaf_depth: 0,
+				max_leaf_depth: 0,
+				avg_leaf_depth: 0.0,
+				level_sizes,
+				imbalance_ratio: 1.0,
+			};
+		}
+		
+		TreeStats {
+			len,
+			height: level_sizes.len(),
+			leaf_count,
+			min_leaf_depth,
+			max_leaf_depth,
+			avg_leaf_depth: leaf_depth_sum as f64 / leaf_count as f64,
+			imbalance_ratio: level_sizes.len() as f64 / min_height(len) as f64,
+			level_sizes,
+		}
+	}
+}
@@ -1,8 +1,28 @@
+/// Linear congruential generator for reproducible random tests
+
+fn lcg(seed: &mut u64) -> u64 {
+	*seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+	*seed >> 33
+}
+
+/// Tree of *len* random keys below *modulo* with given policy
+
+#[cfg(feature = "std")]
+fn random_tree<T>(seed: &mut u64, len: usize, modulo: u64, policy: crate::tree::DuplicatePolicy) -> crate::tree::BinaryTree<T>
+	where T: Copy + Clone + Ord + Eq + std::convert::TryFrom<u64>, T::Error: std::fmt::Debug
+{
+	let mut tree = crate::tree::BinaryTree::with_policy(policy);
+	for _ in 0..len {
+		tree.insert(&T::try_from(lcg(seed) % modulo).unwrap());
+	}
+	tree
+}
+
 /// Test for node
 
 #[allow(unused_imports)]
 mod node_test {
-	
+
 	use crate::node::Node;
 	use crate::branch::Branch;
 	
@@ -325,6 +345,61 @@ mod node_test {
 			})),
 		})));
 	}
+	
+	#[test]
+	fn node_height_test() {
+		let mut node_test = Node::new();
+		assert_eq!(node_test.height(), 0);
+		
+		node_test.insert(&3);
+		assert_eq!(node_test.height(), 1);
+		
+		node_test.insert(&1);
+		node_test.insert(&4);
+		assert_eq!(node_test.height(), 2);
+		
+		node_test.insert(&5);
+		node_test.insert(&6);
+		assert_eq!(node_test.height(), 4);
+	}
+	
+	#[test]
+	fn node_depth_of_test() {
+		let mut node_test = Node::new();
+		assert_eq!(node_test.depth_of(&3), None);
+		
+		node_test.insert(&3);
+		node_test.insert(&1);
+		node_test.insert(&4);
+		node_test.insert(&3);
+		
+		assert_eq!(node_test.depth_of(&3), Some(0));
+		assert_eq!(node_test.depth_of(&1), Some(1));
+		assert_eq!(node_test.depth_of(&4), Some(1));
+		assert_eq!(node_test.depth_of(&2), None);
+	}
+	
+	#[test]
+	fn node_leaf_count_test() {
+		let mut node_test = Node::new();
+		assert_eq!(node_test.leaf_count(), 0);
+		
+		node_test.insert(&3);
+		assert_eq!(node_test.leaf_count(), 1);
+		
+		node_test.insert(&1);
+		node_test.insert(&4);
+		node_test.insert(&3);
+		node_test.insert(&5);
+		
+		//          3
+		//        /  \
+		//       1   4
+		//          /  \
+		//         3    5
+		
+		assert_eq!(node_test.leaf_count(), 3);
+	}
 }
 
 /// Tests for Iterator
@@ -387,7 +462,7 @@ mod iter_test {
 	fn iter_empty_test() {
 		let not_empty_iter = TreeIter::from_iter(1..1000);
 		assert_eq!(not_empty_iter.is_empty(), false);
-
+		
 		let empty_iter = TreeIter::<i32>::new();
 		assert_eq!(empty_iter.is_empty(), true);
 	}
@@ -585,10 +660,10 @@ mod iter_test {
 	#[test]
 	fn iter_shrink_test() {
 		let mut iter = TreeIter::with_capacity(10);
-
+		
 		iter.extend_from_slice(&[1, 2, 3]);
 		assert!(iter.capacity() >= 10);
-
+		
 		iter.shrink_to_fit();
 		assert!(iter.capacity() >= 3);
 	}
@@ -659,12 +734,17 @@ mod iter_test {
 
 #[allow(unused_imports)]
 mod tree_test {
-	use crate::tree::BinaryTree;
+	use crate::tree::{BinaryTree, DuplicatePolicy};
+	use crate::error::{Error, InvariantError};
 	use crate::node::Node;
 	use crate::iter::TreeIter;
+	use crate::seq::SortedSeq;
 	use crate::branch::Branch;
 	use std::collections::VecDeque;
+	use std::cmp::Ordering;
 	use std::iter::FromIterator;
+	use std::ops::Bound;
+	use super::lcg;
 	
 	#[test]
 	fn tree_default_test() {
@@ -674,6 +754,9 @@ mod tree_test {
 			BinaryTree {
 				top: Node::Empty,
 				size: 0,
+				balance_factor: None,
+				limit: None,
+				policy: DuplicatePolicy::KeepAll,
 			}
 		)
 	}
@@ -686,6 +769,9 @@ mod tree_test {
 			BinaryTree {
 				top: Node::Empty,
 				size: 0,
+				balance_factor: None,
+				limit: None,
+				policy: DuplicatePolicy::KeepAll,
 			}
 		);
 	}
@@ -713,6 +799,9 @@ mod tree_test {
 				left: Node::Empty,
 			})),
 			size: 1,
+			balance_factor: None,
+			limit: None,
+			policy: DuplicatePolicy::KeepAll,
 		});
 	}
 	
@@ -738,6 +827,9 @@ mod tree_test {
 				})),
 			})),
 			size: 3,
+			balance_factor: None,
+			limit: None,
+			policy: DuplicatePolicy::KeepAll,
 		});
 	}
 	
@@ -846,6 +938,9 @@ mod tree_test {
 				left: Node::Empty,
 			})),
 			size: 2,
+			balance_factor: None,
+			limit: None,
+			policy: DuplicatePolicy::KeepAll,
 		});
 		
 		assert_eq!(second, BinaryTree {
@@ -855,6 +950,9 @@ mod tree_test {
 				left: Node::Empty,
 			})),
 			size: 1,
+			balance_factor: None,
+			limit: None,
+			policy: DuplicatePolicy::KeepAll,
 		});
 	}
 	
@@ -865,6 +963,9 @@ mod tree_test {
 		assert_eq!(tree, BinaryTree {
 			top: Node::Empty,
 			size: 0,
+			balance_factor: None,
+			limit: None,
+			policy: DuplicatePolicy::KeepAll,
 		});
 	}
 	
@@ -910,7 +1011,7 @@ mod tree_test {
 	
 	#[test]
 	fn tree_drain_filter_test() {
-		
+	
 		// Eratosthenes's sieve
 		
 		let mut tree = BinaryTree::from_iter(1..1501);
@@ -983,7 +1084,7 @@ mod tree_test {
 		let tree1 = BinaryTree::from_iter(1..1000);
 		let  tree2 = BinaryTree::from_iter(500..1500);
 		
-		assert_eq!(tree1.intersection(&tree2), TreeIter::from_iter(500..1000));
+		assert_eq!(tree1.intersection(&tree2), SortedSeq::from_iter(500..1000));
 	}
 	
 	#[test]
@@ -1063,6 +1164,24 @@ mod tree_test {
 		assert_eq!(tree.len(), 0);
 	}
 	
+	#[test]
+	fn tree_pop_return_test() {
+		let mut tree = BinaryTree::from_iter(vec![5, 3, 8, 3, 1, 9, 7]);
+		
+		assert_eq!(tree.pop_first(), Some(1));
+		assert_eq!(tree.pop_last(), Some(9));
+		assert_eq!(tree.pop_first(), Some(3));
+		assert_eq!(tree.pop_first(), Some(3));
+		assert_eq!(tree.pop_last(), Some(8));
+		assert_eq!(tree.validate(), Ok(()));
+		assert_eq!(tree.to_vec(), vec![5, 7]);
+		
+		tree.clear();
+		assert_eq!(tree.pop_first(), None);
+		assert_eq!(tree.pop_last(), None);
+		assert_eq!(tree.len(), 0);
+	}
+	
 	#[test]
 	fn tree_replace_val_test() {
 		let mut tree = BinaryTree::new();
@@ -1141,4 +1260,2372 @@ mod tree_test {
 		
 		assert_eq!((&tree1 ^ &tree2).to_vec(), check);
 	}
+	
+	#[test]
+	fn tree_shape_test() {
+		let tree = BinaryTree::<i32>::new();
+		assert_eq!(tree.height(), 0);
+		assert_eq!(tree.leaf_count(), 0);
+		assert_eq!(tree.depth_of(&1), None);
+		
+		let list = BinaryTree::from_iter(0..2000);
+		assert_eq!(list.height(), 2000);
+		assert_eq!(list.leaf_count(), 1);
+		assert_eq!(list.depth_of(&1999), Some(1999));
+	}
+	
+	#[test]
+	fn tree_stats_test() {
+		let empty = BinaryTree::<i32>::new().stats();
+		assert_eq!(empty.len, 0);
+		assert_eq!(empty.height, 0);
+		assert_eq!(empty.level_sizes, Vec::<usize>::new());
+		assert_eq!(empty.imbalance_ratio, 1.0);
+		
+		//          4
+		//        /  \
+		//       2    6
+		//      / \    \
+		//     1   3    7
+		//               \
+		//                8
+		
+		let stats = BinaryTree::from_iter(vec![4, 2, 6, 1, 3, 7, 8]).stats();
+		
+		assert_eq!(stats.len, 7);
+		assert_eq!(stats.height, 4);
+		assert_eq!(stats.leaf_count, 3);
+		assert_eq!(stats.min_leaf_depth, 2);
+		assert_eq!(stats.max_leaf_depth, 3);
+		assert_eq!(stats.avg_leaf_depth, 7.0 / 3.0);
+		assert_eq!(stats.level_sizes, vec![1, 2, 3, 1]);
+		assert_eq!(stats.imbalance_ratio, 4.0 / 3.0);
+	}
+	
+	#[test]
+	fn tree_rebalance_test() {
+		let mut tree = BinaryTree::new();
+		tree.rebalance();
+		assert_eq!(tree.height(), 0);
+		
+		tree.extend(0..2000);
+		tree.extend(vec![1000; 47]);
+		assert_eq!(tree.height(), 2000);
+		
+		tree.rebalance();
+		let stats = tree.stats();
+		
+		assert_eq!(tree.len(), 2047);
+		assert_eq!(stats.height, 11);
+		assert_eq!(stats.level_sizes, (0..11).map(|x| 1 << x).collect::<Vec<usize>>());
+		
+		let mut check = (0..2000).collect::<Vec<i32>>();
+		check.extend(vec![1000; 47]);
+		check.sort();
+		assert_eq!(tree.to_vec(), check);
+		
+		tree.remove(&1000);
+		assert_eq!(tree.len(), 2046);
+		assert!(tree.contains(&1000));
+	}
+	
+	#[test]
+	fn tree_auto_rebalance_test() {
+		let mut tree = BinaryTree::with_auto_rebalance(2.0);
+		tree.extend(0..5000);
+		
+		assert_eq!(tree.len(), 5000);
+		assert!(tree.height() <= 26);
+		assert_eq!(tree.to_vec(), (0..5000).collect::<Vec<i32>>());
+		
+		tree.set_auto_rebalance(None);
+		tree.extend(5000..5100);
+		assert!(tree.height() >= 100);
+	}
+	
+	#[test]
+	fn tree_auto_rebalance_partial_test() {
+		let mut tree = BinaryTree::with_auto_rebalance(2.0);
+		tree.extend((0..2000).rev());
+		tree.extend(vec![1000; 100]);
+		
+		assert!(tree.height() <= 2 * 12);
+		assert!(tree.to_vec().windows(2).all(|pair| pair[0] <= pair[1]));
+		
+		while tree.len() > 1000 {
+			tree.pop_first();
+			tree.pop_last();
+		}
+		tree.extend(0..1000);
+		assert!(tree.height() <= 2 * 12);
+		assert_eq!(tree.len(), 2000);
+		assert!(tree.to_vec().windows(2).all(|pair| pair[0] <= pair[1]));
+	}
+	
+	#[test]
+	fn tree_auto_rebalance_degenerate_test() {
+		// chain of 300k nodes: scapegoat search must not recurse along it
+		let mut tree = BinaryTree::new();
+		for key in (0..300_000).rev() {
+			let right = std::mem::take(&mut tree.top);
+			tree.top = Node::NonEmpty(Box::new(Branch { key, left: Node::Empty, right }));
+		}
+		tree.size = 300_000;
+		assert_eq!(tree.height(), 300_000);
+		
+		tree.set_auto_rebalance(Some(2.0));
+		assert_eq!(tree.height(), 19);
+		tree.insert(&300_000);
+		assert!(tree.height() <= 2 * 19);
+		assert_eq!(tree.to_vec(), (0..=300_000).collect::<Vec<i32>>());
+	}
+	
+	#[test]
+	#[should_panic]
+	fn tree_auto_rebalance_panic_test() {
+		BinaryTree::<i32>::with_auto_rebalance(0.5);
+	}
+	
+	#[test]
+	fn tree_try_empty_test() {
+		let mut tree = BinaryTree::<i32>::new();
+		
+		assert_eq!(tree.try_first(), Err(Error::Empty));
+		assert_eq!(tree.try_last(), Err(Error::Empty));
+		assert_eq!(tree.try_pop_first(), Err(Error::Empty));
+		assert_eq!(tree.try_pop_last(), Err(Error::Empty));
+		assert_eq!(tree.try_remove(&1), Err(Error::NotFound));
+		assert_eq!(tree.try_replace_val(&1, &2), Err(Error::NotFound));
+		assert_eq!(tree.validate(), Ok(()));
+		
+		tree.extend(vec![5, 1, 5, 9]);
+		assert_eq!(tree.try_pop_first(), Ok(1));
+		assert_eq!(tree.try_pop_last(), Ok(9));
+		assert_eq!(tree.try_remove(&5), Ok(()));
+		assert_eq!(tree.try_remove(&5), Ok(()));
+		assert_eq!(tree.try_remove(&5), Err(Error::NotFound));
+		assert!(tree.is_empty());
+	}
+	
+	#[test]
+	fn tree_limit_test() {
+		let mut tree = BinaryTree::with_limit(100);
+		
+		for i in 0..100 {
+			assert_eq!(tree.try_insert(&i), Ok(true));
+		}
+		assert_eq!(tree.try_insert(&100), Err(Error::Full));
+		assert_eq!(tree.try_append(&BinaryTree::from_iter(0..1)), Err(Error::Full));
+		
+		tree.remove(&0);
+		tree.remove(&50);
+		assert_eq!(tree.len(), 98);
+		assert_eq!(tree.try_append(&BinaryTree::from_iter(0..2)), Ok(()));
+		assert_eq!(tree.try_insert(&100), Err(Error::Full));
+		assert_eq!(tree.validate(), Ok(()));
+	}
+	
+	#[test]
+	#[should_panic]
+	fn tree_limit_panic_test() {
+		let mut tree = BinaryTree::with_limit(1);
+		tree.extend(vec![1, 2]);
+	}
+	
+	#[test]
+	fn tree_limit_append_test() {
+		// duplicates, which policy rejects or replaces, don't count against limit
+		for &policy in [DuplicatePolicy::Reject, DuplicatePolicy::Replace].iter() {
+			let mut tree = BinaryTree::with_policy(policy);
+			tree.limit = Some(4);
+			tree.extend(vec![1, 2, 3]);
+			assert_eq!(tree.try_append(&BinaryTree::from_iter(vec![1, 2, 3, 3, 4, 4])), Ok(()));
+			assert_eq!(tree.to_vec(), vec![1, 2, 3, 4]);
+			assert_eq!(tree.try_append(&BinaryTree::from_iter(vec![2, 5])), Err(Error::Full));
+			assert_eq!(tree.to_vec(), vec![1, 2, 3, 4]);
+		}
+		
+		let mut tree = BinaryTree::with_limit(4);
+		tree.extend(vec![1, 2, 3]);
+		assert_eq!(tree.try_append(&BinaryTree::from_iter(vec![1, 2])), Err(Error::Full));
+		assert_eq!(tree.to_vec(), vec![1, 2, 3]);
+	}
+	
+	#[test]
+	fn tree_limit_append_panic_test() {
+		let mut tree = BinaryTree::with_limit(3);
+		tree.insert(&1);
+		let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+			tree.append(&BinaryTree::from_iter(vec![2, 3, 4]));
+		}));
+		assert!(result.is_err());
+		// nothing is added before panic
+		assert_eq!(tree.to_vec(), vec![1]);
+	}
+	
+	#[test]
+	fn tree_policy_test() {
+		let mut keep = BinaryTree::new();
+		let mut reject = BinaryTree::with_policy(DuplicatePolicy::Reject);
+		let mut replace = BinaryTree::with_policy(DuplicatePolicy::Replace);
+		
+		for tree in [&mut keep, &mut reject, &mut replace].iter_mut() {
+			assert!(tree.insert(&1));
+			assert!(tree.insert(&2));
+		}
+		
+		assert!(keep.insert(&1));
+		assert!(!reject.insert(&1));
+		assert!(replace.insert(&1));
+		
+		assert_eq!(keep.to_vec(), vec![1, 1, 2]);
+		assert_eq!(reject.to_vec(), vec![1, 2]);
+		assert_eq!(replace.to_vec(), vec![1, 2]);
+		
+		reject.extend(vec![2, 3, 3, 1]);
+		replace.append(&BinaryTree::from_iter(vec![2, 3, 3, 1]));
+		assert_eq!(reject.to_vec(), vec![1, 2, 3]);
+		assert_eq!(replace.to_vec(), vec![1, 2, 3]);
+		assert_eq!(reject.validate(), Ok(()));
+		assert_eq!(replace.validate(), Ok(()));
+		
+		reject.replace_val(&1, &3);
+		assert_eq!(reject.to_vec(), vec![2, 3]);
+		
+		let mut full = BinaryTree::with_policy(DuplicatePolicy::Reject);
+		full.limit = Some(1);
+		assert_eq!(full.try_insert(&1), Ok(true));
+		assert_eq!(full.try_insert(&1), Ok(false));
+		assert_eq!(full.try_insert(&2), Err(Error::Full));
+	}
+	
+	#[test]
+	fn tree_policy_set_ops_test() {
+		let mut set = BinaryTree::with_policy(DuplicatePolicy::Reject);
+		set.extend(vec![1, 2, 3, 4]);
+		let multiset = BinaryTree::from_iter(vec![3, 3, 4, 5, 5, 6]);
+		
+		assert_eq!(set.union(&multiset).collect::<Vec<i32>>(), vec![1, 2, 3, 4, 5, 6]);
+		assert_eq!(set.symmetric_difference(&multiset).collect::<Vec<i32>>(), vec![1, 2, 5, 6]);
+		assert_eq!(set.intersection(&multiset).collect::<Vec<i32>>(), vec![3, 4]);
+		assert_eq!(set.difference(&multiset).collect::<Vec<i32>>(), vec![1, 2]);
+		
+		let union = &set | &multiset;
+		assert_eq!(union.policy(), DuplicatePolicy::Reject);
+		assert_eq!(union.to_vec(), vec![1, 2, 3, 4, 5, 6]);
+		assert_eq!((&multiset | &set).to_vec(), vec![1, 2, 3, 3, 4, 5, 5, 6]);
+		assert_eq!((&set ^ &multiset).to_vec(), vec![1, 2, 5, 6]);
+		assert_eq!((&set & &multiset).to_vec(), vec![3, 4]);
+	}
+	
+	#[test]
+	fn tree_validate_duplicate_test() {
+		let mut set = BinaryTree::with_policy(DuplicatePolicy::Replace);
+		set.top.insert(&1);
+		set.top.insert(&1);
+		set.size = 2;
+		assert_eq!(set.validate(), Err(InvariantError::Duplicate { index: 1 }));
+	}
+	
+	#[test]
+	fn tree_validate_test() {
+		let mut tree = BinaryTree::from_iter(vec![3, 1, 4, 1, 5, 9, 2, 6]);
+		assert_eq!(tree.validate(), Ok(()));
+		
+		tree.rebalance();
+		tree.remove(&1);
+		tree.pop_first();
+		tree.drain_filter(|x| x % 3 == 0);
+		assert_eq!(tree.validate(), Ok(()));
+		
+		let wrong_size = BinaryTree {
+			top: Node::NonEmpty(Box::new(Branch {
+				key: 1,
+				right: Node::Empty,
+				left: Node::Empty,
+			})),
+			size: 2,
+			balance_factor: None,
+			limit: None,
+			policy: DuplicatePolicy::KeepAll,
+		};
+		assert_eq!(wrong_size.validate(), Err(InvariantError::Size { expected: 2, actual: 1 }));
+		
+		//          3
+		//        /  \
+		//       1    2
+		
+		let wrong_order = BinaryTree {
+			top: Node::NonEmpty(Box::new(Branch {
+				key: 3,
+				left: Node::NonEmpty(Box::new(Branch {
+					key: 1,
+					right: Node::Empty,
+					left: Node::Empty,
+				})),
+				right: Node::NonEmpty(Box::new(Branch {
+					key: 2,
+					right: Node::Empty,
+					left: Node::Empty,
+				})),
+			})),
+			size: 3,
+			balance_factor: None,
+			limit: None,
+			policy: DuplicatePolicy::KeepAll,
+		};
+		assert_eq!(wrong_order.validate(), Err(InvariantError::Order { index: 2 }));
+	}
+	
+	#[test]
+	fn tree_borrow_test() {
+		let mut tree = BinaryTree::from(vec!["delta", "alpha", "charlie", "bravo"]);
+		let query = String::from("charlie");
+		
+		assert!(tree.contains(query.as_str()));
+		assert_eq!(tree.get(query.as_str()), Some(&"charlie"));
+		assert_eq!(tree.get("echo"), None);
+		assert_eq!(tree.range::<str, _>((Bound::Included("b"), Bound::Included("charlie"))).collect::<Vec<&str>>(), vec!["bravo", "charlie"]);
+		assert_eq!(tree.lower_bound(Bound::Excluded("bravo")), Some(&"charlie"));
+		assert_eq!(tree.upper_bound(Bound::Excluded("bravo")), Some(&"alpha"));
+		
+		assert_eq!(tree.try_remove(query.as_str()), Ok(()));
+		assert_eq!(tree.try_remove(query.as_str()), Err(Error::NotFound));
+		tree.remove("alpha");
+		assert_eq!(tree.to_vec(), vec!["bravo", "delta"]);
+	}
+	
+	#[test]
+	fn tree_range_test() {
+		let mut seed = 5u64;
+		let mut vals = Vec::new();
+		for _ in 0..500 {
+			vals.push(lcg(&mut seed) as i32 % 100);
+		}
+		
+		let mut tree = BinaryTree::from_iter(vals.clone());
+		tree.rebalance();
+		vals.sort();
+		
+		for start in -1..102 {
+			for end in start..102 {
+				let check = vals.iter().copied().filter(|x| (start..end).contains(x)).collect::<Vec<i32>>();
+				assert_eq!(tree.range(start..end).collect::<Vec<i32>>(), check);
+				
+				let check = vals.iter().copied().filter(|x| (start..=end).contains(x)).collect::<Vec<i32>>();
+				assert_eq!(tree.range(start..=end).collect::<Vec<i32>>(), check);
+			}
+			
+			let bounds = (Bound::Excluded(start), Bound::Unbounded);
+			let check = vals.iter().copied().filter(|x| *x > start).collect::<Vec<i32>>();
+			assert_eq!(tree.range(bounds).collect::<Vec<i32>>(), check);
+			
+			assert_eq!(tree.lower_bound(Bound::Included(&start)), vals.iter().find(|x| **x >= start));
+			assert_eq!(tree.lower_bound(Bound::Excluded(&start)), vals.iter().find(|x| **x > start));
+			assert_eq!(tree.upper_bound(Bound::Included(&start)), vals.iter().rev().find(|x| **x <= start));
+			assert_eq!(tree.upper_bound(Bound::Excluded(&start)), vals.iter().rev().find(|x| **x < start));
+		}
+		
+		assert_eq!(tree.range::<i32, _>(..).collect::<Vec<i32>>(), vals);
+		assert_eq!(BinaryTree::<i32>::new().range(1..5).next(), None);
+	}
+	
+	#[test]
+	fn tree_find_by_test() {
+		// (timestamp, id)
+		let mut records = Vec::new();
+		let mut seed = 9u64;
+		for id in 0..300 {
+			records.push((lcg(&mut seed) as u32 % 60, id));
+		}
+		
+		let mut tree = BinaryTree::with_auto_rebalance(1.5);
+		tree.extend(records.clone());
+		records.sort();
+		
+		for time in 0..62 {
+			match tree.find_by(|&(t, _)| t.cmp(&time)) {
+				Some(&(t, id)) => {
+					assert_eq!(t, time);
+					assert!(records.contains(&(t, id)));
+				}
+				None => assert!(records.iter().all(|&(t, _)| t != time)),
+			}
+			
+			let pos = records.partition_point(|&(t, _)| t <= time);
+			assert_eq!(tree.partition_point(|&(t, _)| t <= time), records.get(pos));
+			
+			let check = records.iter().copied().filter(|&(t, _)| t >= time && t < time + 5).collect::<Vec<(u32, i32)>>();
+			let found = tree.range_by(|&(t, _)| {
+				if t < time {
+					Ordering::Less
+				} else if t >= time + 5 {
+					Ordering::Greater
+				} else {
+					Ordering::Equal
+				}
+			});
+			assert_eq!(found.collect::<Vec<(u32, i32)>>(), check);
+		}
+		
+		assert_eq!(BinaryTree::<i32>::new().find_by(|_| Ordering::Equal), None);
+		assert_eq!(BinaryTree::<i32>::new().partition_point(|_| false), None);
+	}
+	
+	/// Key, which is ordered only by *key*, so equal keys may differ by *id*
+	#[derive(Debug, Clone, Copy)]
+	struct Record {
+		key: u32,
+		id: u32,
+	}
+	
+	impl PartialEq for Record {
+		fn eq(&self, other: &Self) -> bool {
+			self.key == other.key
+		}
+	}
+	
+	impl Eq for Record {}
+	
+	impl PartialOrd for Record {
+		fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+			Some(self.cmp(other))
+		}
+	}
+	
+	impl Ord for Record {
+		fn cmp(&self, other: &Self) -> Ordering {
+			self.key.cmp(&other.key)
+		}
+	}
+	
+	#[test]
+	fn tree_retain_test() {
+		let mut seed = 13u64;
+		let mut records = Vec::new();
+		for id in 0..400 {
+			records.push(Record { key: lcg(&mut seed) as u32 % 40, id });
+		}
+		
+		let mut tree = BinaryTree::from_iter(records.clone());
+		tree.rebalance();
+		records.sort();
+		
+		let mut visited = Vec::new();
+		tree.retain(|r| {
+			visited.push(r.id);
+			r.id % 3 != 0
+		});
+		assert_eq!(visited, records.iter().map(|r| r.id).collect::<Vec<u32>>());
+		
+		records.retain(|r| r.id % 3 != 0);
+		let ids = |v: Vec<Record>| v.iter().map(|r| r.id).collect::<Vec<u32>>();
+		assert_eq!(ids(tree.to_vec()), ids(records.clone()));
+		assert_eq!(tree.len(), records.len());
+		assert_eq!(tree.validate(), Ok(()));
+		
+		let extracted = tree.extract_if(|r| r.id % 2 == 0).collect::<Vec<Record>>();
+		let check = records.iter().copied().filter(|r| r.id % 2 == 0).collect::<Vec<Record>>();
+		assert_eq!(ids(extracted), ids(check));
+		
+		records.retain(|r| r.id % 2 != 0);
+		assert_eq!(ids(tree.to_vec()), ids(records));
+		assert_eq!(tree.validate(), Ok(()));
+	}
+	
+	#[test]
+	fn tree_content_eq_test() {
+		let sorted = BinaryTree::from_iter(1..100);
+		let mut shuffled = BinaryTree::from_iter((1..100).map(|x| x * 37 % 100));
+		shuffled.remove(&0);
+		let mut balanced = BinaryTree::with_policy(DuplicatePolicy::Reject);
+		balanced.extend((1..100).rev());
+		balanced.rebalance();
+		
+		assert_eq!(sorted, shuffled);
+		assert_eq!(sorted, balanced);
+		assert_ne!(sorted.height(), shuffled.height());
+		assert_eq!(sorted.cmp(&shuffled), Ordering::Equal);
+		
+		shuffled.insert(&50);
+		assert_ne!(sorted, shuffled);
+		assert!(shuffled < sorted);
+		assert!(BinaryTree::from_iter(vec![1, 2]) < BinaryTree::from_iter(vec![1, 3]));
+		assert!(BinaryTree::new() < BinaryTree::from_iter(vec![1]));
+	}
+	
+	#[test]
+	fn tree_hash_test() {
+		use std::collections::{BTreeSet, HashMap};
+		
+		let mut map = HashMap::new();
+		map.insert(BinaryTree::from_iter(vec![3, 1, 2]), "first");
+		map.insert(BinaryTree::from_iter(vec![1, 2, 3]), "second");
+		map.insert(BinaryTree::from_iter(vec![1, 2, 2, 3]), "third");
+		
+		assert_eq!(map.len(), 2);
+		assert_eq!(map.get(&BinaryTree::from_iter(vec![2, 3, 1])), Some(&"second"));
+		
+		let set = BTreeSet::from_iter(vec![
+			BinaryTree::from_iter(vec![2]),
+			BinaryTree::from_iter(vec![1, 5]),
+			BinaryTree::from_iter(vec![5, 1]),
+			BinaryTree::new(),
+		]);
+		let order = set.iter().map(|tree| tree.to_vec()).collect::<Vec<Vec<i32>>>();
+		assert_eq!(order, vec![vec![], vec![1, 5], vec![2]]);
+	}
+	
+	#[test]
+	fn tree_extract_if_lazy_test() {
+		let mut tree = BinaryTree::from_iter(vec![3, 3, 1, 2, 3, 5, 4]);
+		
+		{
+			let mut iter = tree.extract_if(|x| *x >= 3);
+			assert_eq!(iter.next(), Some(3));
+			assert_eq!(iter.next(), Some(3));
+		}
+		assert_eq!(tree.to_vec(), vec![1, 2, 3, 4, 5]);
+		
+		assert_eq!(tree.extract_if(|_| false).next(), None);
+		assert_eq!(tree.len(), 5);
+		
+		tree.retain(|_| false);
+		assert!(tree.is_empty());
+		assert_eq!(tree.extract_if(|_| true).next(), None);
+	}
+}
+
+/// Tests for sorted sequence
+
+#[allow(unused_imports)]
+mod seq_test {
+	use crate::seq::SortedSeq;
+	use crate::tree::{BinaryTree, DuplicatePolicy};
+	use std::iter::FromIterator;
+	use super::lcg;
+	
+	fn is_sorted(vec: &[i32]) -> bool {
+		vec.windows(2).all(|pair| pair[0] <= pair[1])
+	}
+	
+	#[test]
+	fn seq_edit_test() {
+		let mut seed = 17;
+		let mut seq = SortedSeq::new();
+		let mut check = Vec::new();
+		
+		for _ in 0..2000 {
+			let val = (lcg(&mut seed) % 100) as i32;
+			if lcg(&mut seed) % 3 < 1 {
+				assert_eq!(seq.remove(&val), check.contains(&val));
+				if let Some(pos) = check.iter().position(|x| *x == val) {
+					check.remove(pos);
+				}
+			} else {
+				let index = seq.insert(&val);
+				assert_eq!(seq.get(index), Some(&val));
+				check.push(val);
+				check.sort();
+			}
+		}
+		
+		assert!(is_sorted(&seq.to_vec()));
+		assert_eq!(seq.to_vec(), check);
+		
+		let other = SortedSeq::from_iter((0..500).map(|_| (lcg(&mut seed) % 100) as i32));
+		check.extend(other.iter());
+		check.sort();
+		seq.merge(other);
+		assert_eq!(seq.to_vec(), check);
+		
+		assert_eq!(seq.range(10..20).to_vec(), check.iter().copied().filter(|x| (10..20).contains(x)).collect::<Vec<i32>>());
+		let (low, high) = (20, 10);
+		assert_eq!(seq.range(low..high).to_vec(), vec![]);
+		
+		seq.dedup();
+		check.dedup();
+		assert_eq!(seq.clone().collect::<Vec<i32>>(), check);
+		assert_eq!(seq.rev().collect::<Vec<i32>>(), check.into_iter().rev().collect::<Vec<i32>>());
+	}
+	
+	#[test]
+	fn seq_set_ops_sorted_test() {
+		let mut seed = 19;
+		
+		for round in 0..20 {
+			let first = (0..200).map(|_| (lcg(&mut seed) % 80) as i32).collect::<Vec<i32>>();
+			let second = (0..150).map(|_| (lcg(&mut seed) % 80) as i32).collect::<Vec<i32>>();
+			
+			let mut tree1 = BinaryTree::from_iter(first.clone());
+			let tree2 = BinaryTree::from_iter(second.clone());
+			if round % 2 == 1 {
+				tree1 = BinaryTree::with_policy(DuplicatePolicy::Reject);
+				tree1.extend(first.clone());
+			}
+			
+			let mut sorted1 = tree1.to_vec();
+			sorted1.sort();
+			let in_first = |x: &i32| first.contains(x);
+			let in_second = |x: &i32| second.contains(x);
+			
+			let mut union = sorted1.clone();
+			union.extend(second.iter().copied().filter(|x| !in_first(x)));
+			union.sort();
+			let mut sym = sorted1.iter().copied().filter(|x| !in_second(x)).collect::<Vec<i32>>();
+			sym.extend(second.iter().copied().filter(|x| !in_first(x)));
+			sym.sort();
+			if round % 2 == 1 {
+				union.dedup();
+				sym.dedup();
+			}
+			
+			let difference = tree1.difference(&tree2).collect::<Vec<i32>>();
+			let intersection = tree1.intersection(&tree2).collect::<Vec<i32>>();
+			
+			assert_eq!(difference, sorted1.iter().copied().filter(|x| !in_second(x)).collect::<Vec<i32>>());
+			assert_eq!(intersection, sorted1.iter().copied().filter(|x| in_second(x)).collect::<Vec<i32>>());
+			assert_eq!(tree1.symmetric_difference(&tree2).collect::<Vec<i32>>(), sym);
+			assert_eq!(tree1.union(&tree2).collect::<Vec<i32>>(), union);
+			
+			for seq in [tree1.union(&tree2), tree2.union(&tree1), tree2.symmetric_difference(&tree1), tree2.difference(&tree1)] {
+				assert!(is_sorted(&seq.to_vec()));
+			}
+		}
+	}
+}
+
+/// Tests for bounded tree
+
+#[allow(unused_imports)]
+mod bounded_test {
+	use crate::bounded::{BoundedTree, Evict};
+	use super::lcg;
+	
+	#[test]
+	fn bounded_zero_capacity_test() {
+		let mut tree = BoundedTree::new(0, Evict::Min);
+		
+		assert_eq!(tree.insert(&1), Some(1));
+		assert!(tree.is_empty());
+	}
+	
+	#[test]
+	fn bounded_top_k_test() {
+		let mut seed = 7;
+		let mut largest = BoundedTree::new(100, Evict::Min);
+		let mut smallest = BoundedTree::new(100, Evict::Max);
+		let mut all = Vec::new();
+		
+		for _ in 0..10000 {
+			let val = lcg(&mut seed) % 5000;
+			all.push(val);
+			
+			if let Some(evicted) = largest.insert(&val) {
+				assert!(evicted <= *largest.first());
+			}
+			if let Some(evicted) = smallest.insert(&val) {
+				assert!(evicted >= *smallest.last());
+			}
+			assert!(largest.len() <= 100);
+		}
+		all.sort();
+		
+		assert_eq!(largest.to_vec(), all[all.len() - 100..].to_vec());
+		assert_eq!(smallest.to_vec(), all[..100].to_vec());
+		assert_eq!(largest.validate(), Ok(()));
+		assert!(largest.height() <= 2 * 7);
+	}
+	
+	#[test]
+	fn bounded_duplicates_test() {
+		let mut tree = BoundedTree::new(3, Evict::Min);
+		tree.extend(vec![5, 5, 5]);
+		
+		assert_eq!(tree.insert(&5), Some(5));
+		assert_eq!(tree.insert(&6), Some(5));
+		assert_eq!(tree.to_vec(), vec![5, 5, 6]);
+		
+		assert_eq!(tree.pop_last(), Some(6));
+		assert_eq!(tree.insert(&1), None);
+		assert_eq!(tree.to_vec(), vec![1, 5, 5]);
+	}
+}
+
+/// Tests for static tree
+
+#[allow(unused_imports)]
+mod fixed_test {
+	use crate::fixed::StaticTree;
+	use crate::error::Error;
+	use super::lcg;
+	
+	#[test]
+	fn fixed_zero_capacity_test() {
+		let mut tree = StaticTree::<i32, 0>::new();
+		
+		assert_eq!(tree.insert(&1), Err(Error::Full));
+		assert_eq!(tree.remove(&1), Err(Error::NotFound));
+		assert_eq!(tree.first(), Err(Error::Empty));
+		assert_eq!(tree.last(), Err(Error::Empty));
+		assert_eq!(tree.iter().next(), None);
+	}
+	
+	#[test]
+	fn fixed_full_test() {
+		let mut tree = StaticTree::<u8, 100>::new();
+		
+		for i in 0..100 {
+			assert_eq!(tree.insert(&((i as u32 * 7 % 100) as u8)), Ok(()));
+		}
+		assert_eq!(tree.insert(&0), Err(Error::Full));
+		assert_eq!(tree.len(), 100);
+		assert_eq!(tree.iter().collect::<Vec<u8>>(), (0..100).collect::<Vec<u8>>());
+		
+		tree.clear();
+		assert!(tree.is_empty());
+		assert_eq!(tree.insert(&0), Ok(()));
+	}
+	
+	#[test]
+	fn fixed_random_test() {
+		let mut seed = 11;
+		let mut tree = StaticTree::<u64, 64>::new();
+		let mut check = Vec::new();
+		
+		for _ in 0..20000 {
+			let val = lcg(&mut seed) % 50;
+			if lcg(&mut seed) & 1 == 0 {
+				match check.iter().position(|&x| x == val) {
+					Some(pos) => {
+						check.remove(pos);
+						assert_eq!(tree.remove(&val), Ok(()));
+					}
+					None => assert_eq!(tree.remove(&val), Err(Error::NotFound)),
+				}
+			} else if check.len() < 64 {
+				check.insert(check.partition_point(|&x| x <= val), val);
+				assert_eq!(tree.insert(&val), Ok(()));
+			} else {
+				assert_eq!(tree.insert(&val), Err(Error::Full));
+			}
+			
+			assert_eq!(tree.len(), check.len());
+			assert_eq!(tree.contains(&val), check.contains(&val));
+			assert_eq!(tree.first().ok(), check.first());
+			assert_eq!(tree.last().ok(), check.last());
+		}
+		
+		assert_eq!(tree.iter().collect::<Vec<u64>>(), check);
+		check.reverse();
+		assert_eq!(tree.iter().rev().collect::<Vec<u64>>(), check);
+	}
+}
+
+/// Tests for flat and adaptive trees
+
+#[allow(unused_imports)]
+mod flat_test {
+	use crate::error::Error;
+	use crate::flat::{AdaptiveTree, FlatTree};
+	use crate::tree::{BinaryTree, DuplicatePolicy};
+	use std::iter::FromIterator;
+	use std::ops::Bound;
+	use super::lcg;
+	
+	#[test]
+	fn flat_vs_tree_test() {
+		let mut seed = 37;
+		
+		for policy in [DuplicatePolicy::KeepAll, DuplicatePolicy::Reject, DuplicatePolicy::Replace] {
+			let mut flat = FlatTree::with_policy(policy);
+			let mut tree = BinaryTree::with_policy(policy);
+			
+			for _ in 0..3000 {
+				let val = (lcg(&mut seed) % 60) as i32;
+				match lcg(&mut seed) % 3 {
+					0 => assert_eq!(flat.try_remove(&val), tree.try_remove(&val)),
+					_ => assert_eq!(flat.insert(&val), tree.insert(&val)),
+				}
+				
+				assert_eq!(flat.len(), tree.len());
+				assert_eq!(flat.contains(&val), tree.contains(&val));
+				assert_eq!(flat.get(&val), tree.get(&val));
+				assert_eq!(flat.try_first(), tree.try_first());
+				assert_eq!(flat.try_last(), tree.try_last());
+				
+				for bound in [Bound::Included(&val), Bound::Excluded(&val), Bound::Unbounded] {
+					assert_eq!(flat.lower_bound(bound), tree.lower_bound(bound));
+					assert_eq!(flat.upper_bound(bound), tree.upper_bound(bound));
+				}
+				assert_eq!(flat.partition_point(|x| *x < val), tree.partition_point(|x| *x < val));
+				assert_eq!(flat.find_by(|x| x.cmp(&val)).is_some(), tree.find_by(|x| x.cmp(&val)).is_some());
+			}
+			
+			assert_eq!(flat.validate(), Ok(()));
+			assert_eq!(flat.to_vec(), tree.to_vec());
+			assert_eq!(flat.iter().collect::<Vec<i32>>(), tree.iter().collect::<Vec<i32>>());
+			assert_eq!(flat.range(10..40).collect::<Vec<i32>>(), tree.range(10..40).collect::<Vec<i32>>());
+			assert_eq!(flat.range(30..=30).collect::<Vec<i32>>(), tree.range(30..=30).collect::<Vec<i32>>());
+			assert_eq!(flat.range_by(|x| (x / 10).cmp(&2)).collect::<Vec<i32>>(), tree.range_by(|x| (x / 10).cmp(&2)).collect::<Vec<i32>>());
+			
+			let other_vals = (0..100).map(|_| (lcg(&mut seed) % 80) as i32).collect::<Vec<i32>>();
+			let other_flat = FlatTree::from(other_vals.clone());
+			let other_tree = BinaryTree::from_iter(other_vals);
+			
+			assert_eq!(flat.union(&other_flat), tree.union(&other_tree));
+			assert_eq!(flat.intersection(&other_flat), tree.intersection(&other_tree));
+			assert_eq!(flat.difference(&other_flat), tree.difference(&other_tree));
+			assert_eq!(flat.symmetric_difference(&other_flat), tree.symmetric_difference(&other_tree));
+			assert_eq!(flat.is_disjoint(&other_flat), tree.is_disjoint(&other_tree));
+			assert_eq!((&flat | &other_flat).to_vec(), (&tree | &other_tree).to_vec());
+			assert_eq!((&flat & &other_flat).to_vec(), (&tree & &other_tree).to_vec());
+			assert_eq!((&flat ^ &other_flat).to_vec(), (&tree ^ &other_tree).to_vec());
+			
+			flat.replace_val(&5, &70);
+			tree.replace_val(&5, &70);
+			flat.multi_remove(vec![1, 2, 2, 70, 100]);
+			tree.multi_remove(vec![1, 2, 2, 70, 100]);
+			assert_eq!(flat.to_vec(), tree.to_vec());
+			
+			assert_eq!(flat.drain_filter(|x| x % 7 == 3).collect::<Vec<i32>>(), tree.drain_filter(|x| x % 7 == 3).collect::<Vec<i32>>());
+			flat.retain(|x| x % 5 != 1);
+			tree.retain(|x| x % 5 != 1);
+			assert_eq!(flat.pop_first(), tree.pop_first());
+			assert_eq!(flat.pop_last(), tree.pop_last());
+			assert_eq!(flat.to_vec(), tree.to_vec());
+			
+			assert_eq!(BinaryTree::from(flat.clone()), tree);
+			assert_eq!(FlatTree::from(tree.clone()), flat);
+		}
+	}
+	
+	#[test]
+	fn flat_limit_test() {
+		let mut flat = FlatTree::with_limit(3);
+		assert_eq!(flat.try_append(&FlatTree::from(vec![1, 2])), Ok(()));
+		assert_eq!(flat.try_append(&FlatTree::from(vec![3, 4])), Err(Error::Full));
+		assert_eq!(flat.try_insert(&3), Ok(true));
+		assert_eq!(flat.try_insert(&0), Err(Error::Full));
+		assert_eq!(flat.to_vec(), vec![1, 2, 3]);
+		
+		assert_eq!(flat.try_remove(&5), Err(Error::NotFound));
+		assert_eq!(flat.try_replace_val(&5, &6), Err(Error::NotFound));
+		flat.clear();
+		assert_eq!(flat.try_first(), Err(Error::Empty));
+		assert_eq!(flat.try_pop_last(), Err(Error::Empty));
+	}
+	
+	#[test]
+	fn flat_limit_append_test() {
+		// duplicates, which policy rejects, don't count against limit
+		let mut tree = BinaryTree::with_policy(DuplicatePolicy::Reject);
+		tree.limit = Some(4);
+		tree.extend(vec![1, 2, 3]);
+		let mut flat = FlatTree::from(tree);
+		assert_eq!(flat.try_append(&FlatTree::from(vec![1, 2, 3, 3, 4, 4])), Ok(()));
+		assert_eq!(flat.to_vec(), vec![1, 2, 3, 4]);
+		assert_eq!(flat.try_append(&FlatTree::from(vec![2, 5])), Err(Error::Full));
+		
+		let mut flat = FlatTree::with_limit(3);
+		flat.insert(&1);
+		let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+			flat.append(&FlatTree::from(vec![2, 3, 4]));
+		}));
+		assert!(result.is_err());
+		assert_eq!(flat.to_vec(), vec![1]);
+	}
+	
+	#[test]
+	fn flat_extract_if_test() {
+		let mut flat = FlatTree::from(vec![5, 1, 4, 2, 3, 2]);
+		{
+			let mut evens = flat.extract_if(|x| x % 2 == 0);
+			assert_eq!(evens.next(), Some(2));
+			assert_eq!(evens.next(), Some(2));
+		}
+		assert_eq!(flat.to_vec(), vec![1, 3, 4, 5]);
+		assert_eq!(flat.extract_if(|x| *x > 2).collect::<Vec<i32>>(), vec![3, 4, 5]);
+		assert_eq!(flat.to_vec(), vec![1]);
+	}
+	
+	#[test]
+	fn adaptive_test() {
+		let mut seed = 41;
+		let mut adaptive = AdaptiveTree::with_threshold(32);
+		let mut tree = BinaryTree::new();
+		let (mut was_flat, mut switches) = (true, 0);
+		
+		for step in 0..4000 {
+			let val = (lcg(&mut seed) % 200) as i32;
+			// grow and shrink in waves, so tree crosses threshold many times
+			if step % 300 < 100 || lcg(&mut seed) % 4 < 1 {
+				adaptive.insert(&val);
+				tree.insert(&val);
+			} else {
+				let first = tree.try_first().copied();
+				if let Ok(first) = first {
+					assert_eq!(adaptive.try_remove(&first), Ok(()));
+					tree.remove(&first);
+				}
+			}
+			
+			assert_eq!(adaptive.len(), tree.len());
+			assert_eq!(adaptive.is_flat(), adaptive.len() <= 32 && (was_flat || adaptive.len() <= 16));
+			if adaptive.is_flat() != was_flat {
+				switches += 1;
+				was_flat = adaptive.is_flat();
+			}
+			assert_eq!(adaptive.contains(&val), tree.contains(&val));
+			assert_eq!(adaptive.try_first(), tree.try_first());
+			assert_eq!(adaptive.try_last(), tree.try_last());
+		}
+		
+		assert!(switches >= 4);
+		assert_eq!(adaptive.to_vec(), tree.to_vec());
+		assert_eq!(adaptive.range(50..150).collect::<Vec<i32>>(), tree.range(50..150).collect::<Vec<i32>>());
+		
+		adaptive.clear();
+		assert!(adaptive.is_flat() && adaptive.is_empty());
+	}
+}
+
+/// Tests for multiway tree
+
+#[allow(unused_imports)]
+mod btree_test {
+	use crate::btree::BTree;
+	use crate::error::{Error, InvariantError};
+	use crate::tree::{BinaryTree, DuplicatePolicy};
+	use std::iter::FromIterator;
+	use std::ops::Bound;
+	use super::lcg;
+	
+	#[test]
+	fn btree_vs_tree_test() {
+		let mut seed = 43;
+		
+		for fanout in [4, 6, 32] {
+			for policy in [DuplicatePolicy::KeepAll, DuplicatePolicy::Reject, DuplicatePolicy::Replace] {
+				let mut btree = BTree::with_policy(policy);
+				btree.set_fanout(fanout);
+				let mut tree = BinaryTree::with_policy(policy);
+				
+				for _ in 0..3000 {
+					let val = (lcg(&mut seed) % 150) as i32;
+					match lcg(&mut seed) % 3 {
+						0 => assert_eq!(btree.try_remove(&val), tree.try_remove(&val)),
+						_ => assert_eq!(btree.insert(&val), tree.insert(&val)),
+					}
+					
+					assert_eq!(btree.validate(), Ok(()));
+					assert_eq!(btree.len(), tree.len());
+					assert_eq!(btree.contains(&val), tree.contains(&val));
+					assert_eq!(btree.get(&val), tree.get(&val));
+					assert_eq!(btree.try_first(), tree.try_first());
+					assert_eq!(btree.try_last(), tree.try_last());
+					
+					for bound in [Bound::Included(&val), Bound::Excluded(&val), Bound::Unbounded] {
+						assert_eq!(btree.lower_bound(bound), tree.lower_bound(bound));
+						assert_eq!(btree.upper_bound(bound), tree.upper_bound(bound));
+					}
+					assert_eq!(btree.partition_point(|x| *x < val), tree.partition_point(|x| *x < val));
+					assert_eq!(btree.find_by(|x| x.cmp(&val)).is_some(), tree.find_by(|x| x.cmp(&val)).is_some());
+				}
+				
+				assert_eq!(btree.to_vec(), tree.to_vec());
+				assert_eq!(btree.iter().len(), tree.len());
+				assert_eq!(btree.range(10..40).collect::<Vec<i32>>(), tree.range(10..40).collect::<Vec<i32>>());
+				assert_eq!(btree.range(30..=30).collect::<Vec<i32>>(), tree.range(30..=30).collect::<Vec<i32>>());
+				assert_eq!(btree.range_by(|x| (x / 10).cmp(&2)).collect::<Vec<i32>>(), tree.range_by(|x| (x / 10).cmp(&2)).collect::<Vec<i32>>());
+				
+				let other_vals = (0..300).map(|_| (lcg(&mut seed) % 200) as i32).collect::<Vec<i32>>();
+				let other_btree = BTree::from(other_vals.clone());
+				let other_tree = BinaryTree::from_iter(other_vals);
+				
+				assert_eq!(btree.union(&other_btree), tree.union(&other_tree));
+				assert_eq!(btree.intersection(&other_btree), tree.intersection(&other_tree));
+				assert_eq!(btree.difference(&other_btree), tree.difference(&other_tree));
+				assert_eq!(btree.symmetric_difference(&other_btree), tree.symmetric_difference(&other_tree));
+				assert_eq!(btree.is_disjoint(&other_btree), tree.is_disjoint(&other_tree));
+				assert_eq!((&btree | &other_btree).to_vec(), (&tree | &other_tree).to_vec());
+				assert_eq!((&btree & &other_btree).to_vec(), (&tree & &other_tree).to_vec());
+				assert_eq!((&btree ^ &other_btree).to_vec(), (&tree ^ &other_tree).to_vec());
+				
+				btree.replace_val(&5, &70);
+				tree.replace_val(&5, &70);
+				btree.multi_remove(vec![1, 2, 2, 70, 100]);
+				tree.multi_remove(vec![1, 2, 2, 70, 100]);
+				assert_eq!(btree.to_vec(), tree.to_vec());
+				
+				assert_eq!(btree.drain_filter(|x| x % 7 == 3).collect::<Vec<i32>>(), tree.drain_filter(|x| x % 7 == 3).collect::<Vec<i32>>());
+				btree.retain(|x| x % 5 != 1);
+				tree.retain(|x| x % 5 != 1);
+				assert_eq!(btree.pop_first(), tree.pop_first());
+				assert_eq!(btree.pop_last(), tree.pop_last());
+				assert_eq!(btree.validate(), Ok(()));
+				assert_eq!(btree.to_vec(), tree.to_vec());
+			}
+		}
+	}
+	
+	#[test]
+	fn btree_shape_test() {
+		let mut seed = 47;
+		
+		for fanout in [4, 5, 8, 64] {
+			for len in [0, 1, 3, 4, 15, 16, 17, 100, 1000, 5000] {
+				let mut btree = BTree::from_iter(0..len);
+				btree.set_fanout(fanout);
+				assert_eq!(btree.validate(), Ok(()));
+				assert!(btree.iter().eq(0..len));
+				
+				// ascending inserts split only the rightmost nodes
+				let mut grown = BTree::with_fanout(fanout);
+				grown.extend(0..len);
+				assert_eq!(grown.validate(), Ok(()));
+				assert_eq!(grown, btree);
+				
+				let mut removed = 0;
+				while !btree.is_empty() {
+					let val = (lcg(&mut seed) % len as u64) as i32;
+					match lcg(&mut seed) % 3 {
+						0 => assert!(btree.pop_first().is_some()),
+						1 => assert!(btree.pop_last().is_some()),
+						_ => { let _ = btree.try_remove(&val); },
+					}
+					removed += 1;
+					if removed & 63 == 0 {
+						assert_eq!(btree.validate(), Ok(()));
+					}
+				}
+				assert_eq!(btree.height(), 0);
+				assert_eq!(btree.try_first(), Err(Error::Empty));
+			}
+		}
+	}
+	
+	#[test]
+	fn btree_limit_test() {
+		let mut btree = BTree::with_limit(3);
+		assert_eq!(btree.try_append(&BTree::from(vec![1, 2])), Ok(()));
+		assert_eq!(btree.try_append(&BTree::from(vec![3, 4])), Err(Error::Full));
+		assert_eq!(btree.try_insert(&3), Ok(true));
+		assert_eq!(btree.try_insert(&0), Err(Error::Full));
+		assert_eq!(btree.to_vec(), vec![1, 2, 3]);
+		
+		assert_eq!(btree.try_remove(&5), Err(Error::NotFound));
+		assert_eq!(btree.try_replace_val(&5, &6), Err(Error::NotFound));
+		btree.clear();
+		assert_eq!(btree.try_pop_last(), Err(Error::Empty));
+	}
+	
+	#[test]
+	fn btree_limit_append_test() {
+		let mut btree = BTree::with_limit(3);
+		btree.insert(&1);
+		let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+			btree.append(&BTree::from(vec![2, 3, 4]));
+		}));
+		assert!(result.is_err());
+		// nothing is added before panic
+		assert_eq!(btree.to_vec(), vec![1]);
+	}
+}
+
+/// Tests for ordered set trait
+
+#[allow(unused_imports)]
+mod set_test {
+	use crate::btree::BTree;
+	use crate::flat::FlatTree;
+	use crate::set::{conformance, OrderedSet};
+	use crate::tree::{BinaryTree, DuplicatePolicy};
+	use std::collections::BTreeSet;
+	
+	fn sum_range<S: OrderedSet<i32>>(set: &S, start: i32, end: i32) -> i32 {
+		set.range(start..end).sum()
+	}
+	
+	#[test]
+	fn conformance_test() {
+		for seed in 0..4 {
+			conformance(BTreeSet::new, seed);
+			conformance(|| BinaryTree::with_policy(DuplicatePolicy::Reject), seed);
+			conformance(|| BTree::with_policy(DuplicatePolicy::Reject), seed);
+			conformance(|| FlatTree::with_policy(DuplicatePolicy::Reject), seed);
+		}
+	}
+	
+	#[test]
+	fn generic_backend_test() {
+		let mut std_set = BTreeSet::new();
+		let mut tree = BinaryTree::new();
+		for val in [7, 3, 9, 1, 5] {
+			OrderedSet::insert(&mut std_set, &val);
+			OrderedSet::insert(&mut tree, &val);
+		}
+		
+		assert_eq!(sum_range(&std_set, 2, 8), 15);
+		assert_eq!(sum_range(&tree, 2, 8), 15);
+		assert_eq!(sum_range(&std_set, 8, 2), 0);
+		assert_eq!(sum_range(&tree, 8, 2), 0);
+		
+		// KeepAll tree keeps duplicates, std set rejects them
+		assert!(!OrderedSet::insert(&mut std_set, &5));
+		assert!(OrderedSet::insert(&mut tree, &5));
+		assert_eq!(OrderedSet::len(&tree), 6);
+		assert!(OrderedSet::remove(&mut tree, &5));
+		assert_eq!(OrderedSet::iter(&tree).collect::<Vec<i32>>(), OrderedSet::iter(&std_set).collect::<Vec<i32>>());
+	}
+}
+
+/// Tests for frozen tree
+
+#[allow(unused_imports)]
+mod frozen_test {
+	use crate::error::Error;
+	use crate::frozen::FrozenTree;
+	use crate::tree::BinaryTree;
+	use std::iter::FromIterator;
+	use std::ops::Bound;
+	use super::lcg;
+	
+	#[test]
+	fn frozen_vs_tree_test() {
+		let mut seed = 61;
+		
+		// every size up to a few full levels, so all shapes of the last level are covered
+		for len in 0..80 {
+			let vals = (0..len).map(|_| (lcg(&mut seed) % 60) as i32 * 2).collect::<Vec<i32>>();
+			let tree = BinaryTree::from_iter(vals.clone());
+			let frozen = tree.freeze();
+			let sorted = tree.to_vec();
+			
+			assert_eq!(frozen.len(), tree.len());
+			assert_eq!(frozen.to_vec(), sorted);
+			assert_eq!(frozen.iter().len(), sorted.len());
+			assert_eq!(frozen.try_first().ok(), tree.try_first().ok());
+			assert_eq!(frozen.try_last().ok(), tree.try_last().ok());
+			assert_eq!(FrozenTree::from(vals), frozen);
+			
+			for val in -1..122 {
+				assert_eq!(frozen.contains(&val), tree.contains(&val));
+				assert_eq!(frozen.rank(&val), sorted.iter().filter(|x| **x < val).count());
+				assert_eq!(frozen.partition_point(|x| *x < val), tree.partition_point(|x| *x < val));
+				for bound in [Bound::Included(&val), Bound::Excluded(&val), Bound::Unbounded] {
+					assert_eq!(frozen.lower_bound(bound), tree.lower_bound(bound), "lower {:?}", bound);
+					assert_eq!(frozen.upper_bound(bound), tree.upper_bound(bound), "upper {:?}", bound);
+				}
+				
+				let end = val + (lcg(&mut seed) % 30) as i32 - 5;
+				assert_eq!(frozen.range(val..end).collect::<Vec<i32>>(), tree.range(val..end).collect::<Vec<i32>>());
+				assert_eq!(frozen.range(val..=end).collect::<Vec<i32>>(), tree.range(val..=end).collect::<Vec<i32>>());
+				assert_eq!(frozen.range(..val).collect::<Vec<i32>>(), tree.range(..val).collect::<Vec<i32>>());
+			}
+			
+			let thawed = frozen.thaw();
+			assert_eq!(thawed, tree);
+			assert_eq!(thawed.validate(), Ok(()));
+		}
+		
+		assert_eq!(FrozenTree::<u8>::default().try_first(), Err(Error::Empty));
+	}
+	
+	#[cfg(feature = "std")]
+	#[test]
+	fn frozen_bytes_test() {
+		use crate::error::{FormatError, InvariantError};
+		
+		let frozen = FrozenTree::from_iter((0..300u32).map(|x| x * 7 % 1000));
+		let bytes = frozen.to_bytes();
+		assert_eq!(FrozenTree::<u32>::from_bytes(&bytes), Ok(frozen.clone()));
+		assert_eq!(FrozenTree::<i32>::from_bytes(&bytes), Err(FormatError::KeyType { tag: 0x03, width: 4 }));
+		
+		for len in 0..bytes.len() {
+			assert!(FrozenTree::<u32>::from_bytes(&bytes[..len]).is_err(), "truncated to {}", len);
+		}
+		for bit in 0..bytes.len() * 8 {
+			let mut damaged = bytes.clone();
+			damaged[bit / 8] ^= 1 << (bit % 8);
+			assert!(FrozenTree::<u32>::from_bytes(&damaged).is_err(), "flipped bit {}", bit);
+		}
+		
+		// keys out of Eytzinger order are found even with valid checksum
+		let mut unsorted = FrozenTree::from(vec![1u8, 2, 3]).to_bytes();
+		unsorted.swap(16, 17);
+		let end = unsorted.len() - 8;
+		let sum = crate::codec::checksum(&unsorted[..end]);
+		unsorted[end..].copy_from_slice(&sum.to_le_bytes());
+		assert_eq!(FrozenTree::<u8>::from_bytes(&unsorted), Err(FormatError::Invariant(InvariantError::Order { index: 1 })));
+		
+		let mut longer = bytes.clone();
+		longer.push(0);
+		assert_eq!(FrozenTree::<u32>::from_bytes(&longer), Err(FormatError::Layout));
+	}
+}
+
+/// Tests for transactions
+
+#[allow(unused_imports)]
+mod transaction_test {
+	use crate::error::Error;
+	use crate::tree::{BinaryTree, DuplicatePolicy};
+	use std::panic::{self, AssertUnwindSafe};
+	use super::lcg;
+	
+	/// Random change of both transaction and model
+	fn step(tx: &mut crate::transaction::Transaction<'_, i32>, model: &mut BinaryTree<i32>, seed: &mut u64) {
+		let val = (lcg(seed) % 40) as i32;
+		match lcg(seed) % 20 {
+			0 => { tx.clear(); model.clear(); },
+			1 => assert_eq!(tx.pop_first(), model.pop_first()),
+			2 => assert_eq!(tx.pop_last(), model.pop_last()),
+			3 | 4 => {
+				let new = (lcg(seed) % 40) as i32;
+				tx.replace_val(&val, &new);
+				model.replace_val(&val, &new);
+			},
+			5..=10 => assert_eq!(tx.try_remove(&val), model.try_remove(&val)),
+			_ => assert_eq!(tx.try_insert(&val), model.try_insert(&val)),
+		}
+		assert_eq!(tx.len(), model.len());
+	}
+	
+	#[test]
+	fn transaction_random_test() {
+		for &policy in [DuplicatePolicy::KeepAll, DuplicatePolicy::Reject, DuplicatePolicy::Replace].iter() {
+			let mut seed = 17;
+			let mut tree = BinaryTree::with_policy(policy);
+			for _ in 0..200 {
+				let before = tree.to_vec();
+				let mut model = tree.clone();
+				let commit = lcg(&mut seed) % 2 == 1;
+				
+				let result: Result<(), ()> = tree.transaction(|tx| {
+					for _ in 0..(lcg(&mut seed) % 20) {
+						step(tx, &mut model, &mut seed);
+					}
+					
+					// nested savepoint, which may be rolled back
+					let inner = model.clone();
+					let keep = lcg(&mut seed) % 3 < 2;
+					let _ = tx.savepoint(|tx| {
+						for _ in 0..(lcg(&mut seed) % 10) {
+							step(tx, &mut model, &mut seed);
+						}
+						if keep { Ok(()) } else { Err(()) }
+					});
+					if !keep {
+						model = inner;
+					}
+					assert_eq!(tx.to_vec(), model.to_vec());
+					
+					if commit { Ok(()) } else { Err(()) }
+				});
+				
+				assert_eq!(result.is_ok(), commit);
+				assert_eq!(tree.validate(), Ok(()));
+				if commit {
+					assert_eq!(tree.to_vec(), model.to_vec());
+				} else {
+					assert_eq!(tree.to_vec(), before);
+				}
+				assert_eq!(tree.len(), tree.to_vec().len());
+			}
+		}
+	}
+	
+	#[test]
+	fn transaction_panic_test() {
+		let mut tree = BinaryTree::from(vec![1, 2, 3, 4, 5]);
+		
+		let result = panic::catch_unwind(AssertUnwindSafe(|| {
+			let _: Result<(), ()> = tree.transaction(|tx| {
+				tx.insert(&10);
+				tx.remove(&1);
+				tx.clear();
+				tx.insert(&7);
+				panic!("validation failed");
+			});
+		}));
+		assert!(result.is_err());
+		assert_eq!(tree.to_vec(), vec![1, 2, 3, 4, 5]);
+		assert_eq!(tree.validate(), Ok(()));
+		
+		// panic in nested savepoint is caught inside transaction, outer changes stay
+		let result: Result<(), ()> = tree.transaction(|tx| {
+			tx.insert(&6);
+			let inner = panic::catch_unwind(AssertUnwindSafe(|| {
+				let _: Result<(), ()> = tx.savepoint(|tx| {
+					tx.pop_first();
+					tx.replace_val(&2, &20);
+					panic!("inner");
+				});
+			}));
+			assert!(inner.is_err());
+			assert_eq!(tx.to_vec(), vec![1, 2, 3, 4, 5, 6]);
+			Ok(())
+		});
+		assert_eq!(result, Ok(()));
+		assert_eq!(tree.to_vec(), vec![1, 2, 3, 4, 5, 6]);
+		
+		// panic of full bounded tree rolls back too
+		let mut tree = BinaryTree::with_limit(3);
+		tree.extend(vec![1, 2]);
+		let result = panic::catch_unwind(AssertUnwindSafe(|| {
+			let _: Result<(), ()> = tree.transaction(|tx| {
+				tx.remove(&1);
+				tx.insert(&3);
+				tx.insert(&4);
+				tx.insert(&5);
+				Ok(())
+			});
+		}));
+		assert!(result.is_err());
+		assert_eq!(tree.to_vec(), vec![1, 2]);
+		assert_eq!(tree.limit(), Some(3));
+	}
+	
+	#[test]
+	fn transaction_policy_test() {
+		let mut tree = BinaryTree::with_policy(DuplicatePolicy::Reject);
+		tree.extend(vec![1, 2, 3]);
+		let result: Result<(), Error> = tree.transaction(|tx| {
+			assert!(!tx.insert(&2));
+			tx.replace_val(&1, &3);
+			assert_eq!(tx.to_vec(), vec![2, 3]);
+			tx.try_remove(&1)
+		});
+		assert_eq!(result, Err(Error::NotFound));
+		assert_eq!(tree.to_vec(), vec![1, 2, 3]);
+		
+		let mut tree = BinaryTree::new();
+		tree.extend(vec![2, 2, 5]);
+		let result: Result<usize, ()> = tree.transaction(|tx| {
+			tx.replace_val(&2, &5);
+			tx.insert(&5);
+			Ok(tx.changes())
+		});
+		assert_eq!(result, Ok(2));
+		assert_eq!(tree.to_vec(), vec![5, 5, 5, 5]);
+	}
+}
+
+/// Tests for priority queue
+
+#[allow(unused_imports)]
+mod queue_test {
+	use crate::queue::TreePriorityQueue;
+	use std::cmp::Reverse;
+	use std::collections::BinaryHeap;
+	use std::iter::FromIterator;
+	use super::lcg;
+	
+	/// Simple LCG, so tests don't need any random crates
+	#[test]
+	fn queue_empty_test() {
+		let mut queue = TreePriorityQueue::<i32>::new();
+		
+		assert!(queue.is_empty());
+		assert_eq!(queue.peek_min(), None);
+		assert_eq!(queue.peek_max(), None);
+		assert_eq!(queue.pop_min(), None);
+		assert_eq!(queue.pop_max(), None);
+		assert_eq!(queue.drain_sorted().next(), None);
+		assert_eq!(queue.into_sorted_vec(), vec![]);
+	}
+	
+	#[test]
+	fn queue_max_heap_test() {
+		let mut seed = 1;
+		let mut queue = TreePriorityQueue::new();
+		let mut heap = BinaryHeap::new();
+		
+		for _ in 0..20000 {
+			match lcg(&mut seed) % 3 {
+				0 => assert_eq!(queue.pop_max(), heap.pop()),
+				_ => {
+					let val = lcg(&mut seed) % 500;
+					queue.push(&val);
+					heap.push(val);
+				}
+			}
+			assert_eq!(queue.peek_max(), heap.peek());
+			assert_eq!(queue.len(), heap.len());
+		}
+		
+		assert_eq!(queue.into_sorted_vec(), heap.into_sorted_vec());
+	}
+	
+	#[test]
+	fn queue_min_heap_test() {
+		let mut seed = 2;
+		let mut queue = TreePriorityQueue::new();
+		let mut heap = BinaryHeap::new();
+		
+		for _ in 0..20000 {
+			match lcg(&mut seed) % 3 {
+				0 => assert_eq!(queue.pop_min(), heap.pop().map(|Reverse(val)| val)),
+				_ => {
+					let val = lcg(&mut seed) % 500;
+					queue.push(&val);
+					heap.push(Reverse(val));
+				}
+			}
+			assert_eq!(queue.peek_min(), heap.peek().map(|Reverse(val)| val));
+		}
+		
+		let mut check = heap.into_sorted_vec().into_iter().map(|Reverse(val)| val).collect::<Vec<u64>>();
+		check.reverse();
+		assert_eq!(queue.into_sorted_vec(), check);
+	}
+	
+	#[test]
+	fn queue_double_ended_test() {
+		let mut seed = 3;
+		let mut queue = TreePriorityQueue::new();
+		let mut check = Vec::new();
+		
+		for _ in 0..5000 {
+			match lcg(&mut seed) % 4 {
+				0 => assert_eq!(queue.pop_min(), if check.is_empty() { None } else { Some(check.remove(0)) }),
+				1 => assert_eq!(queue.pop_max(), check.pop()),
+				_ => {
+					let val = lcg(&mut seed) % 100;
+					queue.push(&val);
+					let pos = check.partition_point(|&x| x <= val);
+					check.insert(pos, val);
+				}
+			}
+			assert_eq!(queue.peek_min(), check.first());
+			assert_eq!(queue.peek_max(), check.last());
+		}
+	}
+	
+	#[test]
+	fn queue_balanced_test() {
+		let mut queue = TreePriorityQueue::from_iter(0..10000);
+		
+		for i in 0..5000 {
+			assert_eq!(queue.pop_min(), Some(i));
+			queue.push(&(10000 + i));
+		}
+		assert_eq!(queue.len(), 10000);
+		assert!(queue.tree.height() <= 2 * 14);
+	}
+	
+	#[test]
+	fn queue_shrink_test() {
+		// ascending pushes make a high right side, which stays after pops from the left
+		let mut queue = TreePriorityQueue::from_iter(0..10000);
+		for i in 0..9000 {
+			assert_eq!(queue.pop_min(), Some(i));
+		}
+		
+		// rebuilt on the way down, so height follows the current size
+		assert_eq!(queue.len(), 1000);
+		assert!(queue.tree.height() <= crate::stats::min_height(queue.len()) + 1);
+		assert_eq!(queue.into_sorted_vec(), (9000..10000).collect::<Vec<i32>>());
+	}
+	
+	#[test]
+	fn queue_drain_sorted_test() {
+		let mut queue = TreePriorityQueue::from(vec![5, 1, 4, 1, 3]);
+		let drain = queue.drain_sorted();
+		
+		assert_eq!(drain.len(), 5);
+		assert_eq!(drain.collect::<Vec<i32>>(), vec![1, 1, 3, 4, 5]);
+		assert!(queue.is_empty());
+		
+		queue.extend(0..10);
+		assert_eq!(queue.drain_sorted().rev().take(3).collect::<Vec<i32>>(), vec![9, 8, 7]);
+		assert!(queue.is_empty());
+	}
+}
+
+/// Tests for parallel methods
+
+#[cfg(feature = "std")]
+#[allow(unused_imports)]
+mod par_test {
+	use crate::par::{join, PAR_THRESHOLD};
+	use crate::tree::{BinaryTree, DuplicatePolicy};
+	use std::sync::{mpsc, Mutex};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use std::time::Duration;
+	use super::{lcg, random_tree};
+	
+	#[test]
+	fn par_from_sorted_test() {
+		for len in [0, 1, 2, 3, 100, PAR_THRESHOLD - 1, PAR_THRESHOLD * 5 + 7] {
+			let src = (0..len as u64).map(|x| x / 3).collect::<Vec<u64>>();
+			let tree = BinaryTree::par_from_sorted(&src);
+			
+			assert_eq!(tree.len(), len);
+			assert_eq!(tree.validate(), Ok(()));
+			assert_eq!(tree.to_vec(), src);
+			assert_eq!(tree.par_to_vec(), src);
+			if len <= 100 {
+				assert_eq!(tree, BinaryTree::from(src.clone()));
+			}
+			assert_eq!(tree.height(), crate::stats::min_height(len));
+		}
+	}
+	
+	#[test]
+	fn par_set_ops_test() {
+		let mut seed = 23;
+		
+		for &(len, modulo) in &[(50, 30), (PAR_THRESHOLD * 3, 5000), (PAR_THRESHOLD * 4, 1 << 40)] {
+			let tree1 = random_tree::<u64>(&mut seed, len, modulo, DuplicatePolicy::KeepAll);
+			let tree2 = random_tree::<u64>(&mut seed, len / 2 + 1, modulo, DuplicatePolicy::KeepAll);
+			
+			assert_eq!(tree1.par_union(&tree2), tree1.union(&tree2));
+			assert_eq!(tree2.par_union(&tree1), tree2.union(&tree1));
+			assert_eq!(tree1.par_intersection(&tree2), tree1.intersection(&tree2));
+			assert_eq!(tree2.par_intersection(&tree1), tree2.intersection(&tree1));
+			
+			let mut set = BinaryTree::with_policy(DuplicatePolicy::Reject);
+			let mut shuffled = tree1.to_vec();
+			for i in (1..shuffled.len()).rev() {
+				shuffled.swap(i, lcg(&mut seed) as usize % (i + 1));
+			}
+			set.extend(shuffled);
+			assert_eq!(set.par_union(&tree2), set.union(&tree2));
+			assert_eq!(set.par_intersection(&tree2), set.intersection(&tree2));
+			
+			let empty = BinaryTree::new();
+			assert_eq!(tree1.par_union(&empty), tree1.union(&empty));
+			assert_eq!(empty.par_union(&tree1), empty.union(&tree1));
+			assert_eq!(empty.par_intersection(&tree1).len(), 0);
+		}
+	}
+	
+	#[test]
+	fn par_join_test() {
+		// each side waits for the message of the other, so it passes only if they run at once
+		let (to_second, from_first) = mpsc::channel();
+		let (to_first, from_second) = mpsc::channel();
+		let wait = Duration::from_secs(10);
+		
+		let (first, second) = join(
+			move || { to_second.send(()).unwrap(); from_second.recv_timeout(wait).is_ok() },
+			move || { to_first.send(()).unwrap(); from_first.recv_timeout(wait).is_ok() },
+		);
+		assert!(first && second);
+	}
+	
+	#[test]
+	fn par_fold_test() {
+		let mut seed = 29;
+		let tree = random_tree::<u64>(&mut seed, PAR_THRESHOLD * 6, 1000, DuplicatePolicy::KeepAll);
+		
+		let sum = tree.par_fold(|| 0, |acc, x| acc + x, |a, b| a + b);
+		assert_eq!(sum, tree.iter().sum::<u64>());
+		
+		let max = tree.par_fold(|| None, |acc: Option<u64>, x| acc.max(Some(*x)), |a, b| a.max(b));
+		assert_eq!(max, Some(*tree.last()));
+		
+		// Not commutative: order of elements must be kept
+		let hash = tree.par_fold(
+			|| (0u64, 1u64),
+			|(h, p), x| (h.wrapping_mul(31).wrapping_add(*x), p.wrapping_mul(31)),
+			|(h1, p1), (h2, p2)| (h1.wrapping_mul(p2).wrapping_add(h2), p1.wrapping_mul(p2)),
+		);
+		let check = tree.iter().fold(0u64, |h, x| h.wrapping_mul(31).wrapping_add(x));
+		assert_eq!(hash.0, check);
+		
+		let small = BinaryTree::from(vec![3, 1, 2]);
+		let concat = small.par_fold(Vec::new, |mut acc, x| { acc.push(*x); acc }, |mut a, b| { a.extend(b); a });
+		assert_eq!(concat, vec![1, 2, 3]);
+	}
+	
+	#[test]
+	fn par_for_each_test() {
+		let mut seed = 31;
+		let tree = random_tree::<u64>(&mut seed, PAR_THRESHOLD * 6, 1 << 20, DuplicatePolicy::KeepAll);
+		
+		let count = AtomicUsize::new(0);
+		let seen = Mutex::new(Vec::new());
+		tree.par_for_each(|x| {
+			count.fetch_add(1, Ordering::Relaxed);
+			seen.lock().unwrap().push(*x);
+		});
+		
+		let mut seen = seen.into_inner().unwrap();
+		seen.sort();
+		assert_eq!(count.into_inner(), tree.len());
+		assert_eq!(seen, tree.to_vec());
+	}
+}
+
+/// Tests for binary format
+
+#[cfg(feature = "std")]
+#[allow(unused_imports)]
+mod codec_test {
+	use crate::codec::{checksum, FixedKey, Layout};
+	use crate::error::{FormatError, InvariantError};
+	use crate::tree::{BinaryTree, DuplicatePolicy};
+	use std::iter::FromIterator;
+	use super::{lcg, random_tree};
+	
+	fn encode<T: FixedKey>(tree: &BinaryTree<T>, layout: Layout) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		tree.write_layout_to(&mut bytes, layout).unwrap();
+		bytes
+	}
+	
+	/// Replaces checksum after test changed the data
+	fn reseal(bytes: &mut [u8]) {
+		let body = bytes.len() - 8;
+		let sum = checksum(&bytes[..body]);
+		bytes[body..].copy_from_slice(&sum.to_le_bytes());
+	}
+	
+	#[test]
+	fn codec_roundtrip_test() {
+		let mut seed = 53;
+		
+		for policy in [DuplicatePolicy::KeepAll, DuplicatePolicy::Reject, DuplicatePolicy::Replace] {
+			for len in [0, 1, 2, 10, 300] {
+				let tree = random_tree::<u32>(&mut seed, len, 500, policy);
+				
+				let sorted = BinaryTree::<u32>::read_from(encode(&tree, Layout::Sorted).as_slice()).unwrap();
+				assert_eq!(sorted, tree);
+				assert_eq!(sorted.policy(), policy);
+				assert_eq!(sorted.validate(), Ok(()));
+				assert!(sorted.height() <= tree.height());
+				
+				let shaped = BinaryTree::<u32>::read_from(encode(&tree, Layout::Shape).as_slice()).unwrap();
+				assert!(shaped.top == tree.top);
+				assert_eq!(shaped.len(), tree.len());
+				assert_eq!(shaped.policy(), policy);
+			}
+		}
+		
+		let ints = BinaryTree::from_iter(vec![i128::MIN, -1, 0, i128::MAX]);
+		assert_eq!(BinaryTree::<i128>::read_from(encode(&ints, Layout::Shape).as_slice()), Ok(ints));
+		
+		let names = BinaryTree::from_iter(vec![*b"bob\0\0", *b"alice", *b"eve\0\0"]);
+		assert_eq!(BinaryTree::<[u8; 5]>::read_from(encode(&names, Layout::Sorted).as_slice()), Ok(names));
+		
+		// degenerate tree is written and read without recursion
+		let mut list = BinaryTree::new();
+		for key in 0..2000u16 {
+			list.insert(&key);
+		}
+		let loaded = BinaryTree::<u16>::read_from(encode(&list, Layout::Shape).as_slice()).unwrap();
+		assert_eq!(loaded.height(), 2000);
+		assert_eq!(loaded.to_vec(), list.to_vec());
+	}
+	
+	#[test]
+	fn codec_compat_test() {
+		let tree = BinaryTree::from_iter(vec![5u64, 3, 8, 1]);
+		let bytes = encode(&tree, Layout::Shape);
+		
+		// newer minor version with longer header and optional flag is still readable
+		let mut newer = bytes.clone();
+		newer[5] = 7;
+		newer[6] += 8;
+		newer[9] |= 0x80;
+		newer.splice(24..24, [0xAB; 8].iter().copied());
+		reseal(&mut newer);
+		let loaded = BinaryTree::<u64>::read_from(newer.as_slice()).unwrap();
+		assert!(loaded.top == tree.top);
+		
+		let mut major = bytes.clone();
+		major[4] = 2;
+		reseal(&mut major);
+		assert_eq!(BinaryTree::<u64>::read_from(major.as_slice()), Err(FormatError::Version { major: 2 }));
+		
+		let mut required = bytes.clone();
+		required[8] |= 0x04;
+		reseal(&mut required);
+		assert_eq!(BinaryTree::<u64>::read_from(required.as_slice()), Err(FormatError::Flags { flags: 0x05 }));
+		
+		assert_eq!(BinaryTree::<u32>::read_from(bytes.as_slice()), Err(FormatError::KeyType { tag: 0x04, width: 8 }));
+		assert_eq!(BinaryTree::<i64>::read_from(bytes.as_slice()), Err(FormatError::KeyType { tag: 0x04, width: 8 }));
+		assert_eq!(BinaryTree::<u64>::read_from(&b"BNTX"[..]), Err(FormatError::Magic));
+		
+		// extra bytes after tree belong to somebody else
+		let mut stream = bytes.clone();
+		stream.extend_from_slice(b"tail");
+		assert_eq!(BinaryTree::<u64>::read_from(stream.as_slice()), Ok(tree));
+	}
+	
+	#[test]
+	fn codec_consistency_test() {
+		let tree = BinaryTree::from_iter(vec![2u8, 1, 3]);
+		
+		// unsorted keys with valid checksum
+		let mut unsorted = encode(&tree, Layout::Sorted);
+		unsorted.swap(24, 25);
+		reseal(&mut unsorted);
+		assert_eq!(BinaryTree::<u8>::read_from(unsorted.as_slice()), Err(FormatError::Invariant(InvariantError::Order { index: 1 })));
+		
+		// duplicates are forbidden by stored policy
+		let mut duplicate = encode(&BinaryTree::from_iter(vec![1u8, 2]), Layout::Sorted);
+		duplicate[11] = 1;
+		duplicate[25] = 1;
+		reseal(&mut duplicate);
+		assert_eq!(BinaryTree::<u8>::read_from(duplicate.as_slice()), Err(FormatError::Invariant(InvariantError::Duplicate { index: 1 })));
+		
+		// links promise a child, which doesn't exist
+		let mut links = encode(&tree, Layout::Shape);
+		links[24] |= 0x04;
+		reseal(&mut links);
+		assert_eq!(BinaryTree::<u8>::read_from(links.as_slice()), Err(FormatError::Layout));
+		let mut orphan = encode(&tree, Layout::Shape);
+		orphan[26] = 1;
+		reseal(&mut orphan);
+		assert_eq!(BinaryTree::<u8>::read_from(orphan.as_slice()), Err(FormatError::Layout));
+		
+		// huge count doesn't allocate memory for it
+		let mut huge = encode(&tree, Layout::Sorted);
+		huge[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+		assert!(BinaryTree::<u8>::read_from(huge.as_slice()).is_err());
+	}
+	
+	#[test]
+	fn codec_damage_test() {
+		let mut seed = 59;
+		
+		for layout in [Layout::Sorted, Layout::Shape] {
+			let tree = random_tree::<u32>(&mut seed, 40, 500, DuplicatePolicy::KeepAll);
+			let bytes = encode(&tree, layout);
+			
+			for len in 0..bytes.len() {
+				assert!(BinaryTree::<u32>::read_from(&bytes[..len]).is_err(), "truncated to {}", len);
+			}
+			
+			for bit in 0..bytes.len() * 8 {
+				let mut damaged = bytes.clone();
+				damaged[bit / 8] ^= 1 << (bit % 8);
+				assert!(BinaryTree::<u32>::read_from(damaged.as_slice()).is_err(), "flipped bit {}", bit);
+			}
+			
+			for _ in 0..500 {
+				let mut damaged = bytes.clone();
+				let index = (lcg(&mut seed) as usize) % bytes.len();
+				damaged[index] ^= (lcg(&mut seed) % 255 + 1) as u8;
+				assert!(BinaryTree::<u32>::read_from(damaged.as_slice()).is_err(), "damaged byte {}", index);
+			}
+		}
+	}
+}
+
+/// Tests for durable tree
+
+#[cfg(feature = "std")]
+#[allow(unused_imports)]
+mod durable_test {
+	use crate::durable::DurableTree;
+	use crate::error::FormatError;
+	use crate::tree::{BinaryTree, DuplicatePolicy};
+	use std::fs;
+	use std::path::PathBuf;
+	use super::lcg;
+	
+	/// Empty directory, which is unique for test and process
+	fn temp_dir(name: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("binartree-{}-{}", name, std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		dir
+	}
+	
+	#[test]
+	fn durable_reopen_test() {
+		let dir = temp_dir("durable-reopen");
+		let mut seed = 67;
+		let mut model = BinaryTree::new();
+		{
+			let mut tree = DurableTree::<u32>::open(&dir).unwrap();
+			tree.set_sync(false);
+			tree.set_auto_checkpoint(Some(100));
+			
+			for step in 0..1000 {
+				let val = (lcg(&mut seed) % 50) as u32;
+				match lcg(&mut seed) % 50 {
+					0 => { tree.clear().unwrap(); model.clear(); },
+					1..=19 => assert_eq!(tree.remove(&val).unwrap(), model.try_remove(&val).is_ok()),
+					_ => assert_eq!(tree.insert(&val).unwrap(), model.insert(&val)),
+				}
+				assert!(tree.log_records() < 100, "step {}", step);
+			}
+			assert_eq!(tree.to_vec(), model.to_vec());
+			tree.sync().unwrap();
+		}
+		
+		let tree = DurableTree::<u32>::open(&dir).unwrap();
+		assert_eq!(tree.to_vec(), model.to_vec());
+		assert_eq!(DurableTree::<u64>::open(&dir).err(), Some(FormatError::KeyType { tag: 0x03, width: 4 }));
+		fs::remove_dir_all(&dir).unwrap();
+	}
+	
+	#[test]
+	fn durable_policy_test() {
+		let dir = temp_dir("durable-policy");
+		{
+			let mut tree = DurableTree::<i16>::open_with_policy(&dir, DuplicatePolicy::Reject).unwrap();
+			assert!(tree.insert(&-5).unwrap());
+			assert!(!tree.insert(&-5).unwrap());
+			assert!(!tree.remove(&7).unwrap());
+			assert_eq!(tree.log_records(), 1);
+			tree.checkpoint().unwrap();
+		}
+		
+		// policy of existing tree comes from snapshot
+		let mut tree = DurableTree::<i16>::open(&dir).unwrap();
+		assert_eq!(tree.policy(), DuplicatePolicy::Reject);
+		assert!(!tree.insert(&-5).unwrap());
+		assert_eq!(tree.to_vec(), vec![-5]);
+		drop(tree);
+		
+		// other requested policy is an error, the same one is fine
+		assert_eq!(DurableTree::<i16>::open_with_policy(&dir, DuplicatePolicy::Replace).err(),
+			Some(FormatError::Policy { stored: DuplicatePolicy::Reject }));
+		assert_eq!(DurableTree::<i16>::open_with_policy(&dir, DuplicatePolicy::Reject).unwrap().to_vec(), vec![-5]);
+		fs::remove_dir_all(&dir).unwrap();
+		
+		// policy of new tree is kept without checkpoint
+		{
+			let mut tree = DurableTree::<i16>::open_with_policy(&dir, DuplicatePolicy::Replace).unwrap();
+			assert!(tree.insert(&3).unwrap());
+		}
+		let tree = DurableTree::<i16>::open(&dir).unwrap();
+		assert_eq!(tree.policy(), DuplicatePolicy::Replace);
+		assert_eq!(tree.to_vec(), vec![3]);
+		assert_eq!(DurableTree::<i16>::open_with_policy(&dir, DuplicatePolicy::KeepAll).err(),
+			Some(FormatError::Policy { stored: DuplicatePolicy::Replace }));
+		fs::remove_dir_all(&dir).unwrap();
+	}
+	
+	#[test]
+	fn durable_torn_log_test() {
+		let dir = temp_dir("durable-torn");
+		let mut seed = 71;
+		let mut states = Vec::new();
+		{
+			let mut tree = DurableTree::<u16>::open(&dir).unwrap();
+			tree.set_sync(false);
+			for _ in 0..20 {
+				tree.insert(&((lcg(&mut seed) % 30) as u16)).unwrap();
+			}
+			tree.checkpoint().unwrap();
+			states.push(tree.to_vec());
+			
+			for step in 0..40 {
+				let val = (lcg(&mut seed) % 30) as u16;
+				if step == 25 {
+					tree.clear().unwrap();
+				} else if lcg(&mut seed) % 3 < 1 || !tree.remove(&val).unwrap() {
+					tree.insert(&val).unwrap();
+				}
+				states.push(tree.to_vec());
+			}
+			assert_eq!(tree.log_records(), 40);
+		}
+		
+		let snapshot = fs::read(dir.join("snapshot")).unwrap();
+		let log = fs::read(dir.join("wal")).unwrap();
+		let (header, record) = (16, 1 + 2 + 4);
+		assert_eq!(log.len(), header + 40 * record);
+		
+		let crash = temp_dir("durable-torn-crash");
+		for len in 0..=log.len() {
+			fs::create_dir_all(&crash).unwrap();
+			fs::write(crash.join("snapshot"), &snapshot).unwrap();
+			fs::write(crash.join("wal"), &log[..len]).unwrap();
+			
+			let complete = len.saturating_sub(header) / record;
+			{
+				let mut tree = DurableTree::<u16>::open(&crash).unwrap();
+				assert_eq!(tree.to_vec(), states[complete], "log cut at {}", len);
+				
+				// torn tail is cut off, so new records follow the complete ones
+				tree.insert(&1000).unwrap();
+			}
+			let tree = DurableTree::<u16>::open(&crash).unwrap();
+			let mut expected = states[complete].clone();
+			expected.push(1000);
+			assert_eq!(tree.to_vec(), expected, "log cut at {} and appended", len);
+			fs::remove_dir_all(&crash).unwrap();
+		}
+		
+		// damaged record stops replay there
+		let mut damaged = log.clone();
+		damaged[header + 10 * record + 1] ^= 0x40;
+		fs::create_dir_all(&crash).unwrap();
+		fs::write(crash.join("snapshot"), &snapshot).unwrap();
+		fs::write(crash.join("wal"), &damaged).unwrap();
+		assert_eq!(DurableTree::<u16>::open(&crash).unwrap().to_vec(), states[10]);
+		fs::remove_dir_all(&crash).unwrap();
+		fs::remove_dir_all(&dir).unwrap();
+	}
+	
+	#[test]
+	fn durable_checkpoint_crash_test() {
+		let dir = temp_dir("durable-checkpoint");
+		{
+			let mut tree = DurableTree::<u8>::open(&dir).unwrap();
+			tree.insert(&1).unwrap();
+			tree.checkpoint().unwrap();
+			tree.insert(&2).unwrap();
+			tree.insert(&2).unwrap();
+		}
+		let old_log = fs::read(dir.join("wal")).unwrap();
+		{
+			let mut tree = DurableTree::<u8>::open(&dir).unwrap();
+			tree.checkpoint().unwrap();
+		}
+		
+		// crash after new snapshot, but before new log: old log must not be applied twice
+		fs::write(dir.join("wal"), &old_log).unwrap();
+		let tree = DurableTree::<u8>::open(&dir).unwrap();
+		assert_eq!(tree.to_vec(), vec![1, 2, 2]);
+		assert_eq!(tree.log_records(), 0);
+		
+		// crash before snapshot rename leaves only temporary file
+		fs::write(dir.join("snapshot.tmp"), b"garbage").unwrap();
+		assert_eq!(DurableTree::<u8>::open(&dir).unwrap().to_vec(), vec![1, 2, 2]);
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
+
+/// Tests for paged tree
+
+#[cfg(feature = "std")]
+#[allow(unused_imports)]
+mod paged_test {
+	use crate::error::FormatError;
+	use crate::paged::PagedTree;
+	use crate::tree::{BinaryTree, DuplicatePolicy};
+	use std::fs;
+	use std::ops::Bound;
+	use std::path::PathBuf;
+	use super::lcg;
+	
+	/// File path, which is unique for test and process
+	fn temp_file(name: &str) -> PathBuf {
+		let path = std::env::temp_dir().join(format!("binartree-{}-{}", name, std::process::id()));
+		let _ = fs::remove_file(&path);
+		path
+	}
+	
+	#[test]
+	fn paged_vs_tree_test() {
+		for &(page_size, cache) in [(64, 1), (128, 3), (512, 8)].iter() {
+			let path = temp_file(&format!("paged-random-{}", page_size));
+			let mut seed = page_size as u64;
+			let mut tree = PagedTree::<u32>::create(&path, page_size, cache).unwrap();
+			let mut model = BinaryTree::new();
+			
+			for step in 0..3000 {
+				let val = (lcg(&mut seed) % 300) as u32;
+				match lcg(&mut seed) % 10 {
+					0..=3 => assert_eq!(tree.remove(&val).unwrap(), model.try_remove(&val).is_ok(), "step {}", step),
+					4 => assert_eq!(tree.contains(&val).unwrap(), model.contains(&val)),
+					_ => assert_eq!(tree.insert(&val).unwrap(), model.insert(&val)),
+				}
+				assert_eq!(tree.len(), model.len());
+				assert!(tree.cached_pages() <= cache);
+				
+				if lcg(&mut seed) % 100 == 7 {
+					let low = (lcg(&mut seed) % 300) as u32;
+					let high = (lcg(&mut seed) % 300) as u32;
+					let got = tree.range(low..=high).collect::<Result<Vec<u32>, _>>().unwrap();
+					assert_eq!(got, model.range(low..=high).collect::<Vec<u32>>(), "step {}", step);
+					let got = tree.range((Bound::Excluded(low), Bound::Excluded(high))).collect::<Result<Vec<u32>, _>>().unwrap();
+					assert_eq!(got, model.range((Bound::Excluded(low), Bound::Excluded(high))).collect::<Vec<u32>>());
+				}
+			}
+			
+			assert_eq!(tree.to_vec().unwrap(), model.to_vec());
+			assert_eq!(tree.first().unwrap(), model.to_vec().first().copied());
+			assert_eq!(tree.last().unwrap(), model.to_vec().last().copied());
+			drop(tree);
+			
+			let tree = PagedTree::<u32>::open(&path, cache).unwrap();
+			assert_eq!(tree.len(), model.len());
+			assert_eq!(tree.to_vec().unwrap(), model.to_vec());
+			drop(tree);
+			fs::remove_file(&path).unwrap();
+		}
+	}
+	
+	#[test]
+	fn paged_min_page_test() {
+		// 51 bytes hold 3 keys of u8, but not the tree header
+		let path = temp_file("paged-min-page");
+		let mut tree = PagedTree::<u8>::create(&path, 56, 4).unwrap();
+		for val in (0..200).rev() {
+			assert!(tree.insert(&val).unwrap());
+		}
+		drop(tree);
+		
+		let tree = PagedTree::<u8>::open(&path, 4).unwrap();
+		assert_eq!(tree.to_vec().unwrap(), (0..200).collect::<Vec<u8>>());
+		drop(tree);
+		fs::remove_file(&path).unwrap();
+	}
+	
+	#[test]
+	fn paged_bad_page_test() {
+		let path = temp_file("paged-bad-page");
+		assert_eq!(PagedTree::<u8>::create(&path, 55, 4).err(), Some(FormatError::Layout));
+		assert_eq!(PagedTree::<u64>::create(&path, 64, 4).err(), Some(FormatError::Layout));
+		assert_eq!(PagedTree::<u8>::create(&path, u32::MAX as usize + 1, 4).err(), Some(FormatError::Layout));
+		assert!(!path.exists());
+	}
+	
+	#[test]
+	fn paged_policy_test() {
+		let path = temp_file("paged-policy");
+		for &policy in [DuplicatePolicy::KeepAll, DuplicatePolicy::Reject, DuplicatePolicy::Replace].iter() {
+			let mut seed = 5;
+			let mut tree = PagedTree::<i64>::create_with_policy(&path, 96, 4, policy).unwrap();
+			let mut model = BinaryTree::with_policy(policy);
+			for _ in 0..500 {
+				let val = (lcg(&mut seed) % 40) as i64 - 20;
+				assert_eq!(tree.insert(&val).unwrap(), model.insert(&val));
+			}
+			assert_eq!(tree.to_vec().unwrap(), model.to_vec());
+			drop(tree);
+			
+			let mut tree = PagedTree::<i64>::open(&path, 4).unwrap();
+			assert_eq!(tree.policy(), policy);
+			tree.clear().unwrap();
+			assert!(tree.is_empty());
+			assert_eq!(tree.page_count(), 2);
+			assert!(tree.insert(&1).unwrap());
+			assert_eq!(tree.to_vec().unwrap(), vec![1]);
+		}
+		fs::remove_file(&path).unwrap();
+	}
+	
+	#[test]
+	fn paged_reuse_test() {
+		let path = temp_file("paged-reuse");
+		let mut tree = PagedTree::<u64>::create(&path, 128, 4).unwrap();
+		for round in 0..5 {
+			for val in 0..2000 {
+				tree.insert(&val).unwrap();
+			}
+			let pages = tree.page_count();
+			for val in 0..2000 {
+				assert!(tree.remove(&val).unwrap());
+			}
+			assert!(tree.is_empty());
+			if round > 0 {
+				// freed pages are taken again, file doesn't grow
+				assert!(tree.page_count() <= pages, "round {}", round);
+			}
+		}
+		drop(tree);
+		fs::remove_file(&path).unwrap();
+	}
+	
+	#[test]
+	fn paged_damage_test() {
+		let path = temp_file("paged-damage");
+		{
+			let mut tree = PagedTree::<u32>::create(&path, 64, 2).unwrap();
+			for val in 0..200 {
+				tree.insert(&val).unwrap();
+			}
+		}
+		
+		assert!(matches!(PagedTree::<u64>::open(&path, 2), Err(FormatError::KeyType { .. })));
+		
+		let mut bytes = fs::read(&path).unwrap();
+		bytes[64 * 3 + 10] ^= 1;
+		fs::write(&path, &bytes).unwrap();
+		let tree = PagedTree::<u32>::open(&path, 2).unwrap();
+		let result = tree.iter().collect::<Result<Vec<u32>, _>>();
+		assert_eq!(result, Err(FormatError::Checksum));
+		drop(tree);
+		
+		bytes[0] = b'X';
+		fs::write(&path, &bytes).unwrap();
+		assert!(matches!(PagedTree::<u32>::open(&path, 2), Err(FormatError::Magic)));
+		fs::remove_file(&path).unwrap();
+	}
+}
+
+/// Tests for bulk loader
+
+#[cfg(feature = "std")]
+#[allow(unused_imports)]
+mod bulk_test {
+	use crate::bulk::BulkLoader;
+	use crate::codec::FixedKey;
+	use crate::error::FormatError;
+	use crate::tree::{BinaryTree, DuplicatePolicy};
+	use std::fs;
+	use std::path::PathBuf;
+	use super::lcg;
+	
+	/// Empty directory, which is unique for test and process
+	fn temp_dir(name: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("binartree-{}-{}", name, std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		fs::create_dir_all(&dir).unwrap();
+		dir
+	}
+	
+	#[test]
+	fn bulk_sort_test() {
+		let dir = temp_dir("bulk-sort");
+		for &(count, memory, fan_in) in [(0, 64, 2), (10, 64, 2), (1000, 64, 2), (5000, 256, 3), (5000, 1 << 20, 64)].iter() {
+			let mut seed = count as u64;
+			let keys = (0..count).map(|_| (lcg(&mut seed) % 1000) as u32).collect::<Vec<u32>>();
+			let mut model = keys.clone();
+			model.sort_unstable();
+			
+			let loader = BulkLoader::new().with_memory(memory).with_fan_in(fan_in).with_temp_dir(&dir);
+			let sorted = loader.sort(keys.iter().copied()).unwrap();
+			assert_eq!(sorted.collect::<Result<Vec<u32>, _>>().unwrap(), model);
+			
+			let tree = loader.sort(keys.iter().copied()).unwrap().into_tree().unwrap();
+			assert_eq!(tree.validate(), Ok(()));
+			assert_eq!(tree.to_vec(), model);
+			assert_eq!(tree.len(), count);
+			assert!(tree.height() <= 14);
+			
+			let frozen = loader.sort(keys.iter().copied()).unwrap().into_frozen().unwrap();
+			assert_eq!(frozen.to_vec(), model);
+			
+			let path = dir.join("paged");
+			let paged = loader.sort(keys.iter().copied()).unwrap().into_paged(&path, 256, 4).unwrap();
+			assert_eq!(paged.to_vec().unwrap(), model);
+			drop(paged);
+			fs::remove_file(&path).unwrap();
+			
+			// spill files are removed
+			assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+		}
+		fs::remove_dir_all(&dir).unwrap();
+	}
+	
+	#[test]
+	fn bulk_policy_test() {
+		let dir = temp_dir("bulk-policy");
+		let mut seed = 3;
+		let keys = (0..3000).map(|_| (lcg(&mut seed) % 500) as i64 - 250).collect::<Vec<i64>>();
+		
+		for &policy in [DuplicatePolicy::KeepAll, DuplicatePolicy::Reject, DuplicatePolicy::Replace].iter() {
+			let loader = BulkLoader::new().with_memory(400).with_fan_in(4).with_temp_dir(&dir).with_policy(policy);
+			let tree = loader.sort(keys.iter().copied()).unwrap().into_tree().unwrap();
+			let mut model = BinaryTree::with_policy(policy);
+			for key in keys.iter() {
+				model.insert(key);
+			}
+			assert_eq!(tree.policy(), policy);
+			assert_eq!(tree.validate(), Ok(()));
+			assert_eq!(tree.to_vec(), model.to_vec());
+		}
+		fs::remove_dir_all(&dir).unwrap();
+	}
+	
+	#[test]
+	fn bulk_reader_test() {
+		let dir = temp_dir("bulk-reader");
+		let mut seed = 11;
+		let keys = (0..2000).map(|_| lcg(&mut seed)).collect::<Vec<u64>>();
+		let mut bytes = vec![0; keys.len() * u64::WIDTH];
+		for (key, chunk) in keys.iter().zip(bytes.chunks_mut(u64::WIDTH)) {
+			key.encode(chunk);
+		}
+		
+		let loader = BulkLoader::new().with_memory(512).with_fan_in(2).with_temp_dir(&dir);
+		let mut model = keys.clone();
+		model.sort_unstable();
+		let sorted = loader.sort_reader::<u64, _>(&bytes[..]).unwrap();
+		assert_eq!(sorted.collect::<Result<Vec<u64>, _>>().unwrap(), model);
+		
+		bytes.pop();
+		assert!(matches!(loader.sort_reader::<u64, _>(&bytes[..]), Err(FormatError::Truncated)));
+		assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
+
+/// Tests for no_std build
+
+#[allow(unused_imports)]
+mod no_std_test {
+	use std::env;
+	use std::fs;
+	use std::path::PathBuf;
+	use std::process::Command;
+	
+	/// Builds a `#![no_std]` crate, which uses tree without `std` feature
+	#[test]
+	fn no_std_build_test() {
+		let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+		let crate_dir = manifest_dir.join("target").join("no_std_test");
+		fs::create_dir_all(crate_dir.join("src")).unwrap();
+		
+		fs::write(crate_dir.join("Cargo.toml"), format!(
+			"[package]\n\
+			name = \"binartree_no_std_test\"\n\
+			version = \"0.1.0\"\n\
+			edition = \"2018\"\n\
+			\n\
+			[dependencies]\n\
+			binartree = {{ path = {:?}, default-features = false }}\n\
+			\n\
+			[workspace]\n",
+			manifest_dir
+		)).unwrap();
+		
+		fs::write(crate_dir.join("src").join("lib.rs"), "\
+			#![no_std]\n\
+			extern crate alloc;\n\
+			use alloc::vec::Vec;\n\
+			use binartree::tree::BinaryTree;\n\
+			use binartree::queue::TreePriorityQueue;\n\
+			\n\
+			pub fn sorted(src: &[i32]) -> Vec<i32> {\n\
+				let mut tree = BinaryTree::with_auto_rebalance(2.0);\n\
+				src.iter().for_each(|x| { tree.insert(x); });\n\
+				tree.to_vec()\n\
+			}\n\
+			\n\
+			pub fn max(src: &[i32]) -> Option<i32> {\n\
+				let mut queue = TreePriorityQueue::new();\n\
+				src.iter().for_each(|x| queue.push(x));\n\
+				queue.pop_max()\n\
+			}\n"
+		).unwrap();
+		
+		let status = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+			.arg("check")
+			.arg("--offline")
+			.arg("--manifest-path")
+			.arg(crate_dir.join("Cargo.toml"))
+			.env("CARGO_TARGET_DIR", crate_dir.join("target"))
+			.status()
+			.unwrap();
+		
+		assert!(status.success());
+	}
+}
+
+/// Tests for rendering
+
+#[allow(unused_imports)]
+mod render_test {
+	use crate::node::Node;
+	use crate::tree::BinaryTree;
+	use crate::render::Layout;
+	use std::iter::FromIterator;
+	
+	/// Counts lines and the longest line without keeping the picture
+	#[derive(Default)]
+	struct Counter {
+		lines: usize,
+		line: usize,
+		longest: usize,
+	}
+	
+	impl std::fmt::Write for Counter {
+		fn write_str(&mut self, s: &str) -> std::fmt::Result {
+			match s.rfind('\n') {
+				Some(end) => {
+					self.lines += s.matches('\n').count();
+					self.longest = self.longest.max(self.line + s.find('\n').unwrap_or(0));
+					self.line = s.len() - end - 1;
+				},
+				None => self.line += s.len(),
+			}
+			Ok(())
+		}
+	}
+	
+	#[test]
+	fn render_degenerate_test() {
+		use std::fmt::Write;
+		
+		// chain of 20k nodes must be drawn without recursion
+		let keys = (0..20_000).collect::<Vec<i32>>();
+		let mut tree = BinaryTree::new();
+		tree.top = Node::from_sorted(&keys);
+		tree.top.make_vine();
+		tree.size = keys.len();
+		
+		let mut counter = Counter::default();
+		write!(counter, "{}", tree.pretty(Layout::Sideways)).unwrap();
+		assert_eq!(counter.lines, keys.len());
+		assert_eq!(counter.longest, 4 * 19_998 + "/-- 19999".len());
+		
+		tree.rebalance();
+	}
+	
+	#[test]
+	fn render_empty_test() {
+		let tree = BinaryTree::<i32>::new();
+		assert_eq!(tree.to_dot(true), "digraph BinaryTree {\n}\n");
+		assert_eq!(tree.pretty(Layout::Sideways).to_string(), "");
+		assert_eq!(tree.pretty(Layout::TopDown).to_string(), "");
+	}
+	
+	#[test]
+	fn render_dot_nil_test() {
+		let tree = BinaryTree::from_iter(vec![2, 3]);
+		assert_eq!(
+			tree.to_dot(true),
+			"digraph BinaryTree {
+    n0 [label=\"2\"];
+    nil0 [shape=point];
+    n0 -> nil0;
+    n0 -> n1;
+    n1 [label=\"3\"];
+    nil1 [shape=point];
+    n1 -> nil1;
+    nil2 [shape=point];
+    n1 -> nil2;
+}
+"
+		);
+	}
+	
+	#[test]
+	fn render_sideways_test() {
+		let tree = BinaryTree::from_iter(vec![40, 20, 60, 10, 30, 70, 65, 100, 5]);
+		assert_eq!(
+			tree.pretty(Layout::Sideways).to_string(),
+			"        /-- 100
+    /-- 70
+    |   \\-- 65
+/-- 60
+40
+|   /-- 30
+\\-- 20
+    \\-- 10
+        \\-- 5
+"
+		);
+	}
+	
+	#[test]
+	fn render_top_down_test() {
+		let tree = BinaryTree::from_iter(vec![40, 20, 60, 10, 30, 70, 65, 100, 5, 5]);
+		assert_eq!(
+			tree.pretty(Layout::TopDown).to_string(),
+			"        _____40_
+       /        \\
+     __20_      60____
+    /     \\           \\
+ ___10    30        __70__
+/                  /      \\
+5_                 65    100
+  \\
+  5
+"
+		);
+	}
 }
@@ -0,0 +1,352 @@
+use crate::error::Error;
+use crate::node::Node;
+use crate::tree::{BinaryTree, DuplicatePolicy};
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::mem;
+use core::ops::Deref;
+
+/// *English*: One record of undo log: how to revert one change
+///
+/// *Russian*: Одна запись журнала отмены: как откатить одно изменение
+
+#[derive(Debug)]
+enum Undo<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	/// *English*: Value was added, it must be removed
+	///
+	/// *Russian*: Значение было добавлено, его нужно удалить
+	Inserted(T),
+	/// *English*: Equal key was overwritten by *Replace* policy, old key must be written back
+	///
+	/// *Russian*: Равный ключ был перезаписан политикой *Replace*, старый ключ нужно вернуть
+	Overwritten(T),
+	/// *English*: Value was removed, it must be added
+	///
+	/// *Russian*: Значение было удалено, его нужно добавить
+	Removed(T),
+	/// *English*: *count* keys *old* were removed and *added* keys *new* appeared
+	///
+	/// *Russian*: *count* ключей *old* были удалены и появились *added* ключей *new*
+	Replaced { old: T, new: T, count: usize, added: usize },
+	/// *English*: Tree was cleared, its nodes are kept here
+	///
+	/// *Russian*: Дерево было очищено, его узлы хранятся здесь
+	Cleared { top: Node<T>, size: usize },
+}
+
+/// **Transaction**
+/// ---------------
+///
+/// *English*: **Transaction** is given to closure of **BinaryTree::transaction()**.
+/// Every change made through it is written to *undo log*, so it can be reverted
+/// without copying the tree. If closure returns *Ok*, changes are committed,
+/// if it returns *Err* or panics, they are rolled back. **savepoint()** works
+/// the same way inside transaction and can be nested. Log takes memory
+/// for one key per change (O(1) for **clear()**), reading methods
+/// of **BinaryTree** are available through **Deref**.
+///
+/// *Russian*: **Transaction** передаётся в замыкание **BinaryTree::transaction()**.
+/// Каждое изменение через неё записывается в *журнал отмены*, так что его можно
+/// откатить без копирования дерева. Если замыкание возвращает *Ok*, изменения
+/// сохраняются, если возвращает *Err* или паникует, они откатываются.
+/// **savepoint()** работает так же внутри транзакции и может быть вложенным.
+/// Журнал занимает память на один ключ на изменение (O(1) для **clear()**),
+/// читающие методы **BinaryTree** доступны через **Deref**.
+///
+/// # Example
+///
+/// ```
+/// use binartree::tree::BinaryTree;
+/// use std::iter::FromIterator;
+///
+/// let mut tree = BinaryTree::from_iter(1..4);
+///
+/// let result: Result<(), &str> = tree.transaction(|tx| {
+///     tx.insert(&10);
+///     tx.remove(&1);
+///     if tx.contains(&2) { Err("2 is not allowed") } else { Ok(()) }
+/// });
+/// assert_eq!(result, Err("2 is not allowed"));
+/// assert_eq!(tree.to_vec(), vec![1, 2, 3]);
+///
+/// let result: Result<usize, ()> = tree.transaction(|tx| {
+///     tx.insert(&4);
+///     let _ = tx.savepoint(|tx| {
+///         tx.clear();
+///         Err::<(), ()>(())
+///     });
+///     Ok(tx.len())
+/// });
+/// assert_eq!(result, Ok(4));
+/// assert_eq!(tree.to_vec(), vec![1, 2, 3, 4]);
+/// ```
+
+#[derive(Debug)]
+pub struct Transaction<'a, T>
+	where T: Copy + Clone + Ord + Eq
+{
+	tree: &'a mut BinaryTree<T>,
+	log: Vec<Undo<T>>,
+}
+
+/// *English*: Rolls back changes after *mark*, unless it's disarmed.
+/// It's dropped on panic too, so closure can't leave half of changes.
+///
+/// *Russian*: Откатывает изменения после *mark*, если его не разоружили.
+/// Он удаляется и при панике, так что замыкание не может оставить половину изменений.
+
+struct Guard<'t, 'a, T>
+	where T: Copy + Clone + Ord + Eq
+{
+	tx: &'t mut Transaction<'a, T>,
+	mark: usize,
+	armed: bool,
+}
+
+impl<T> Drop for Guard<'_, '_, T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn drop(&mut self) {
+		if self.armed {
+			self.tx.rollback_to(self.mark);
+		}
+	}
+}
+
+impl<T> BinaryTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	/// *English*: Method **transaction()** runs closure with **Transaction**.
+	/// All changes are kept, if it returns *Ok*, and rolled back by undo log,
+	/// if it returns *Err* or panics (panic goes on after rollback).
+	///
+	/// *Russian*: Метод **transaction()** выполняет замыкание с **Transaction**.
+	/// Все изменения сохраняются, если оно возвращает *Ok*, и откатываются по
+	/// журналу отмены, если оно возвращает *Err* или паникует (паника продолжается
+	/// после отката).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = BinaryTree::from_iter(vec![1, 2, 2]);
+	///
+	/// let result: Result<(), ()> = tree.transaction(|tx| {
+	///     tx.replace_val(&2, &5);
+	///     tx.pop_first();
+	///     Ok(())
+	/// });
+	/// assert_eq!(result, Ok(()));
+	/// assert_eq!(tree.to_vec(), vec![5, 5]);
+	/// ```
+	
+	pub fn transaction<R, E, F>(&mut self, f: F) -> Result<R, E>
+		where F: FnOnce(&mut Transaction<'_, T>) -> Result<R, E>
+	{
+		let mut tx = Transaction { tree: self, log: Vec::new() };
+		tx.savepoint(f)
+	}
+}
+
+impl<'a, T> Transaction<'a, T>
+	where T: Copy + Clone + Ord + Eq
+{
+	/// *English*: Method **savepoint()** runs closure like **BinaryTree::transaction()**,
+	/// but rolls back only changes made inside it. Outer transaction goes on
+	/// and may still roll back everything.
+	///
+	/// *Russian*: Метод **savepoint()** выполняет замыкание как **BinaryTree::transaction()**,
+	/// но откатывает только изменения внутри него. Внешняя транзакция продолжается
+	/// и всё ещё может откатить всё.
+	
+	pub fn savepoint<R, E, F>(&mut self, f: F) -> Result<R, E>
+		where F: FnOnce(&mut Transaction<'a, T>) -> Result<R, E>
+	{
+		let mark = self.log.len();
+		let mut guard = Guard { tx: self, mark, armed: true };
+		let result = f(guard.tx);
+		guard.armed = result.is_err();
+		result
+	}
+	
+	/// *English*: Method **changes()** returns number of records in undo log
+	///
+	/// *Russian*: Метод **changes()** возвращает количество записей в журнале отмены
+	
+	#[inline]
+	pub fn changes(&self) -> usize {
+		self.log.len()
+	}
+	
+	/// *English*: Reverts records after *mark* from the last one to the first one
+	///
+	/// *Russian*: Откатывает записи после *mark* от последней к первой
+	
+	fn rollback_to(&mut self, mark: usize) {
+		while self.log.len() > mark {
+			match self.log.pop() {
+				Some(Undo::Inserted(val)) => {
+					let _ = self.tree.try_remove(&val);
+				},
+				Some(Undo::Overwritten(old)) => {
+					if let Some(branch) = self.tree.top.find_mut(&old).branch_mut() {
+						branch.key = old;
+					}
+				},
+				Some(Undo::Removed(val)) => self.restore(&val),
+				Some(Undo::Replaced { old, new, count, added }) => {
+					for _ in 0..added {
+						let _ = self.tree.try_remove(&new);
+					}
+					for _ in 0..count {
+						self.restore(&old);
+					}
+				},
+				Some(Undo::Cleared { top, size }) => {
+					self.tree.top = top;
+					self.tree.size = size;
+				},
+				None => break,
+			}
+		}
+		self.tree.check();
+	}
+	
+	/// *English*: Adds removed value back. Tree had it before, so limit is ignored.
+	///
+	/// *Russian*: Возвращает удалённое значение. Оно уже было в дереве, так что ограничение
+	/// не учитывается.
+	
+	fn restore(&mut self, val: &T) {
+		let limit = self.tree.limit.take();
+		let _ = self.tree.try_insert(val);
+		self.tree.limit = limit;
+	}
+	
+	/// *English*: Method **insert()** works like **BinaryTree::insert()** and logs the change
+	///
+	/// *Russian*: Метод **insert()** работает как **BinaryTree::insert()** и записывает изменение
+	
+	#[inline]
+	pub fn insert(&mut self, val: &T) -> bool {
+		match self.try_insert(val) {
+			Ok(changed) => changed,
+			Err(error) => panic!("{}", error),
+		}
+	}
+	
+	/// *English*: Method **try_insert()** works like **BinaryTree::try_insert()** and logs the change
+	///
+	/// *Russian*: Метод **try_insert()** работает как **BinaryTree::try_insert()** и записывает изменение
+	
+	pub fn try_insert(&mut self, val: &T) -> Result<bool, Error> {
+		let old = match self.tree.policy {
+			DuplicatePolicy::Replace => self.tree.get(val).copied(),
+			_ => None,
+		};
+		
+		let changed = self.tree.try_insert(val)?;
+		match old {
+			Some(old) => self.log.push(Undo::Overwritten(old)),
+			None if changed => self.log.push(Undo::Inserted(*val)),
+			None => (),
+		}
+		Ok(changed)
+	}
+	
+	/// *English*: Method **remove()** works like **BinaryTree::remove()** and logs the change
+	///
+	/// *Russian*: Метод **remove()** работает как **BinaryTree::remove()** и записывает изменение
+	
+	#[inline]
+	pub fn remove<Q>(&mut self, val: &Q)
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		let _ = self.try_remove(val);
+	}
+	
+	/// *English*: Method **try_remove()** works like **BinaryTree::try_remove()** and logs the change
+	///
+	/// *Russian*: Метод **try_remove()** работает как **BinaryTree::try_remove()** и записывает изменение
+	
+	pub fn try_remove<Q>(&mut self, val: &Q) -> Result<(), Error>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		let key = *self.tree.get(val).ok_or(Error::NotFound)?;
+		self.tree.try_remove(val)?;
+		self.log.push(Undo::Removed(key));
+		Ok(())
+	}
+	
+	/// *English*: Method **pop_first()** works like **BinaryTree::pop_first()** and logs the change
+	///
+	/// *Russian*: Метод **pop_first()** работает как **BinaryTree::pop_first()** и записывает изменение
+	
+	pub fn pop_first(&mut self) -> Option<T> {
+		let key = self.tree.pop_first()?;
+		self.log.push(Undo::Removed(key));
+		Some(key)
+	}
+	
+	/// *English*: Method **pop_last()** works like **BinaryTree::pop_last()** and logs the change
+	///
+	/// *Russian*: Метод **pop_last()** работает как **BinaryTree::pop_last()** и записывает изменение
+	
+	pub fn pop_last(&mut self) -> Option<T> {
+		let key = self.tree.pop_last()?;
+		self.log.push(Undo::Removed(key));
+		Some(key)
+	}
+	
+	/// *English*: Method **replace_val()** works like **BinaryTree::replace_val()**
+	/// and logs the change. Log keeps only values and count of keys.
+	///
+	/// *Russian*: Метод **replace_val()** работает как **BinaryTree::replace_val()**
+	/// и записывает изменение. Журнал хранит лишь значения и количество ключей.
+	
+	pub fn replace_val(&mut self, old_val: &T, new_val: &T) {
+		if old_val == new_val {
+			return;
+		}
+		
+		let count = self.tree.range(old_val..=old_val).count();
+		if count == 0 {
+			return;
+		}
+		let before = self.tree.range(new_val..=new_val).count();
+		self.tree.replace_val(old_val, new_val);
+		let added = self.tree.range(new_val..=new_val).count() - before;
+		self.log.push(Undo::Replaced { old: *old_val, new: *new_val, count, added });
+	}
+	
+	/// *English*: Method **clear()** works like **BinaryTree::clear()** and logs the change.
+	/// Nodes are moved to log, so it takes O(1) until commit.
+	///
+	/// *Russian*: Метод **clear()** работает как **BinaryTree::clear()** и записывает изменение.
+	/// Узлы перемещаются в журнал, так что до сохранения это занимает O(1).
+	
+	pub fn clear(&mut self) {
+		let top = mem::take(&mut self.tree.top);
+		let size = mem::replace(&mut self.tree.size, 0);
+		self.log.push(Undo::Cleared { top, size });
+	}
+}
+
+/// *English*: **Deref** gives access to all *immutable* methods of **BinaryTree**
+///
+/// *Russian*: **Deref** даёт доступ ко всем *неизменяющим* методам **BinaryTree**
+
+impl<T> Deref for Transaction<'_, T>
+	where T: Copy + Clone + Ord + Eq
+{
+	type Target = BinaryTree<T>;
+	
+	#[inline]
+	fn deref(&self) -> &BinaryTree<T> {
+		self.tree
+	}
+}
@@ -1,9 +1,53 @@
 use crate::node::Node;
-use crate::iter::TreeIter;
+use crate::iter::{ExtractIf, TreeIter};
+use crate::seq::SortedSeq;
 use crate::branch::Branch;
-use std::iter::FromIterator;
-use std::collections::VecDeque;
-use std::ops::{BitAnd, BitOr, BitXor};
+use crate::stats::{TreeStats, min_height};
+use crate::error::{Error, InvariantError};
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::{BitAnd, BitOr, BitXor, Bound, RangeBounds};
+
+/// *English*: What tree does, when it gets a value, which is *already in the tree*.
+/// Policy is chosen at construction (check **BinaryTree::with_policy()**).
+///
+/// *Russian*: Что делает дерево, когда получает значение, *которое уже есть в дереве*.
+/// Политика выбирается при создании (см. **BinaryTree::with_policy()**).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DuplicatePolicy {
+	/// *English*: Equal values are stored together (*multiset*, default)
+	///
+	/// *Russian*: Равные значения хранятся вместе (*мультисэт*, по-умолчанию)
+	KeepAll,
+	
+	/// *English*: New value is ignored (*set*)
+	///
+	/// *Russian*: Новое значение игнорируется (*множество*)
+	Reject,
+	
+	/// *English*: New value overwrites the old one (*set*)
+	///
+	/// *Russian*: Новое значение заменяет старое (*множество*)
+	Replace,
+}
+
+/// *English*: **Default** policy is *KeepAll*, like it always was.
+///
+/// *Russian*: Политика по-умолчанию - *KeepAll*, как было всегда.
+
+impl Default for DuplicatePolicy {
+	#[inline]
+	fn default() -> Self {
+		DuplicatePolicy::KeepAll
+	}
+}
 
 /// **Realisation of Binary Search Tree in Rust lang**
 /// ---------------------------------------------------------
@@ -17,7 +61,9 @@ use std::ops::{BitAnd, BitOr, BitXor};
 /// The *left branch* stores nodes whose keys (values) are *less* than the key of the *current node*.
 /// In the classic implementation, the binary tree does not store the same keys.
 /// In my implementation, this is possible, nodes whose keys are *greater
-/// or equal to the current one* are stored in the *left branch*.
+/// or equal to the current one* are stored in the *right branch*.
+/// **rebalance()** may move some equal keys to the left branch, so the rule,
+/// which is always true (and checked by **validate()**), is *left ≤ key ≤ right*.
 ///
 /// You can add all values, if they implement Clone, Copy, Eq, Ord traits.
 /// It was made to not broke tree's logic (NAN value).
@@ -45,7 +91,9 @@ use std::ops::{BitAnd, BitOr, BitXor};
 /// В левой ветви хранятся узлы, ключи (значения) которых меньше ключа текущего
 /// узла. В классической реализации бинарное дерево не хранит одинаковые ключи.
 /// В моей реализации это возможно, узлы, ключи которых больше или равны текущему
-/// хранятся в левой ветви.
+/// хранятся в правой ветви. **rebalance()** может перенести часть равных ключей
+/// в левую ветвь, так что всегда верное правило (его проверяет **validate()**) -
+/// *левый ≤ ключ ≤ правый*.
 ///
 /// Дерево может хранить только те типы, которые реализуют
 /// трейты Clone, Copy, Ord, Eq. Это сделанно с целью не нарушать логику дерева (NAN)
@@ -62,16 +110,25 @@ use std::ops::{BitAnd, BitOr, BitXor};
 /// есть весь код. Особенно стоит изучить **tests.rs** файл. Там много примеров
 /// того, как всё это можно использовать.
 ///
-/// *English*: The tree itself. Contains the *top node* (start of tree) and it's *size*.
+/// *English*: The tree itself. Contains the *top node* (start of tree), it's *size*,
+/// the *balance factor* for automatic rebalancing (check **with_auto_rebalance()**),
+/// the *limit* of elements for bounded trees (check **with_limit()**)
+/// and the *policy* for duplicates (check **with_policy()**).
 ///
-/// *Russian*: Само дерево. Храним *головной узел - начало дерева*, и *размер*.
+/// *Russian*: Само дерево. Храним *головной узел - начало дерева*, *размер*,
+/// *коэффициент баланса* для автоматической балансировки (см. **with_auto_rebalance()**),
+/// *предел* количества элементов для ограниченных деревьев (см. **with_limit()**)
+/// и *политику* для повторов (см. **with_policy()**).
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct BinaryTree<T>
 	where T: Copy + Clone + Ord + Eq
 {
 	pub(crate) top: Node<T>,
 	pub(crate) size: usize,
+	pub(crate) balance_factor: Option<f64>,
+	pub(crate) limit: Option<usize>,
+	pub(crate) policy: DuplicatePolicy,
 }
 
 /// *English*: **Default trait** for tree. Bu default tree is *empty*.
@@ -96,10 +153,109 @@ impl<T> Default for BinaryTree<T>
 		BinaryTree {
 			top: Node::Empty,
 			size: 0,
+			balance_factor: None,
+			limit: None,
+			policy: DuplicatePolicy::KeepAll,
+		}
+	}
+}
+
+/// *English*: Trees are *equal*, if they have the same sorted sequences of elements.
+/// Shape of trees, balance factor, limit and policy aren't compared.
+///
+/// *Russian*: Деревья *равны*, если у них одинаковые отсортированные последовательности
+/// элементов. Форма деревьев, коэффициент баланса, предел и политика не сравниваются.
+///
+/// # Example
+///
+/// ```
+/// use binartree::tree::BinaryTree;
+/// use std::iter::FromIterator;
+///
+/// let tree1 = BinaryTree::from_iter(vec![1, 2, 3]);
+/// let tree2 = BinaryTree::from_iter(vec![3, 1, 2]);
+///
+/// assert_eq!(tree1, tree2);
+/// assert_ne!(tree1, BinaryTree::from_iter(vec![1, 2, 3, 3]));
+/// ```
+
+impl<T> PartialEq for BinaryTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn eq(&self, other: &Self) -> bool {
+		self.size == other.size && self.iter().eq(other.iter())
+	}
+}
+
+impl<T> Eq for BinaryTree<T>
+	where T: Copy + Clone + Ord + Eq
+{}
+
+/// *English*: Hash of tree is built from it's size and sorted elements,
+/// so equal trees have equal hashes.
+///
+/// *Russian*: Хэш дерева строится из его размера и отсортированных элементов,
+/// так что у равных деревьев равные хэши.
+///
+/// # Example
+///
+/// ```
+/// use binartree::tree::BinaryTree;
+/// use std::collections::HashSet;
+/// use std::iter::FromIterator;
+///
+/// let mut set = HashSet::new();
+/// set.insert(BinaryTree::from_iter(vec![1, 2]));
+///
+/// assert!(set.contains(&BinaryTree::from_iter(vec![2, 1])));
+/// ```
+
+impl<T> Hash for BinaryTree<T>
+	where T: Copy + Clone + Ord + Eq + Hash
+{
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.size.hash(state);
+		for elem in self.iter() {
+			elem.hash(state);
 		}
 	}
 }
 
+/// *English*: Trees are compared *lexicographically* by their sorted elements
+///
+/// *Russian*: Деревья сравниваются *лексикографически* по их отсортированным элементам
+///
+/// # Example
+///
+/// ```
+/// use binartree::tree::BinaryTree;
+/// use std::iter::FromIterator;
+///
+/// let tree1 = BinaryTree::from_iter(vec![1, 2, 3]);
+/// let tree2 = BinaryTree::from_iter(vec![1, 3]);
+/// let tree3 = BinaryTree::from_iter(vec![1, 2]);
+///
+/// assert!(tree1 < tree2);
+/// assert!(tree3 < tree1);
+/// ```
+
+impl<T> PartialOrd for BinaryTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	#[inline]
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<T> Ord for BinaryTree<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.iter().cmp(other.iter())
+	}
+}
+
 /// Realisation of tree's methods.
 /// ------------------------------------------
 
@@ -129,9 +285,179 @@ impl<T> BinaryTree<T>
 		BinaryTree {
 			top: Node::Empty,
 			size: 0,
+			balance_factor: None,
+			limit: None,
+			policy: DuplicatePolicy::KeepAll,
 		}
 	}
 	
+	/// *English*: Method **with_auto_rebalance()** creates *empty* tree,
+	/// which rebalances itself when it's height becomes greater than
+	/// *factor × (⌊log2(len)⌋ + 1)*. Like in *scapegoat tree*, only the lowest
+	/// too high subtree is rebuilt, so insertion is amortized O(log n).
+	/// Factor 1.0 keeps tree perfectly balanced, but rebuilds it too often,
+	/// so 2.0 is a good choice. Panics if factor < 1.0
+	///
+	/// *Russian*: Метод **with_auto_rebalance()** создаёт *пустое* дерево,
+	/// которое само себя балансирует, когда его высота становится больше
+	/// *factor × (⌊log2(len)⌋ + 1)*. Как в *scapegoat дереве*, перестраивается
+	/// только самое нижнее слишком высокое поддерево, так что вставка - амортизированное O(log n).
+	/// Коэффициент 1.0 держит дерево идеально сбалансированным, но перестраивает
+	/// его слишком часто, так что 2.0 - хороший выбор. Паникует, если коэффициент < 1.0
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	///
+	/// let mut tree = BinaryTree::with_auto_rebalance(2.0);
+	/// tree.extend(0..1000);
+	///
+	/// assert_eq!(tree.len(), 1000);
+	/// assert!(tree.height() <= 20);
+	/// ```
+	
+	#[inline]
+	pub fn with_auto_rebalance(factor: f64) -> Self {
+		let mut tree = BinaryTree::new();
+		tree.set_auto_rebalance(Some(factor));
+		tree
+	}
+	
+	/// *English*: Method **set_auto_rebalance()** turns on (*Some(factor)*) or
+	/// turns off (*None*) automatic rebalancing. Check **with_auto_rebalance()**.
+	/// If tree is already higher than the new factor allows, it's rebuilt
+	/// immediately in O(n), otherwise only insertions rebuild it. Panics if factor < 1.0
+	///
+	/// *Russian*: Метод **set_auto_rebalance()** включает (*Some(factor)*) или
+	/// выключает (*None*) автоматическую балансировку. См. **with_auto_rebalance()**.
+	/// Если дерево уже выше, чем позволяет новый коэффициент, оно сразу перестраивается
+	/// за O(n), иначе его перестраивают только вставки. Паникует, если коэффициент < 1.0
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	///
+	/// let mut tree = BinaryTree::new();
+	/// tree.set_auto_rebalance(Some(1.0));
+	/// tree.extend(1..8);
+	/// assert_eq!(tree.height(), 3);
+	///
+	/// tree.set_auto_rebalance(None);
+	/// tree.extend(8..11);
+	/// assert_eq!(tree.height(), 6);
+	/// ```
+	
+	pub fn set_auto_rebalance(&mut self, factor: Option<f64>) {
+		if let Some(factor) = factor {
+			assert!(factor >= 1.0, "Balance factor must be at least 1.0");
+			if self.height() as f64 > factor * min_height(self.size) as f64 {
+				self.rebalance();
+			}
+		}
+		self.balance_factor = factor;
+	}
+	
+	/// *English*: Method **with_limit()** creates *empty bounded* tree,
+	/// which can't contain more than *limit* elements.
+	/// **try_insert()** returns *Error::Full* when tree is full,
+	/// **insert()** panics in this case.
+	///
+	/// *Russian*: Метод **with_limit()** создаёт *пустое ограниченное* дерево,
+	/// которое не может хранить больше *limit* элементов.
+	/// **try_insert()** возвращает *Error::Full*, когда дерево заполнено,
+	/// а **insert()** в этом случае паникует.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use binartree::error::Error;
+	///
+	/// let mut tree = BinaryTree::with_limit(2);
+	///
+	/// assert_eq!(tree.try_insert(&1), Ok(true));
+	/// assert_eq!(tree.try_insert(&2), Ok(true));
+	/// assert_eq!(tree.try_insert(&3), Err(Error::Full));
+	/// assert_eq!(tree.to_vec(), vec![1, 2]);
+	/// assert_eq!(tree.limit(), Some(2));
+	/// ```
+	
+	#[inline]
+	pub fn with_limit(limit: usize) -> Self {
+		let mut tree = BinaryTree::new();
+		tree.limit = Some(limit);
+		tree
+	}
+	
+	/// *English*: Method **with_policy()** creates *empty* tree,
+	/// which handles equal values with chosen **DuplicatePolicy**.
+	/// *Reject* and *Replace* make tree a *set* (like **BTreeSet**),
+	/// and set operations of such tree return no duplicates.
+	///
+	/// *Russian*: Метод **with_policy()** создаёт *пустое* дерево,
+	/// которое обрабатывает равные значения по выбранной **DuplicatePolicy**.
+	/// *Reject* и *Replace* превращают дерево в *множество* (как **BTreeSet**),
+	/// и операции над множествами такого дерева не возвращают повторов.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::{BinaryTree, DuplicatePolicy};
+	///
+	/// let mut set = BinaryTree::with_policy(DuplicatePolicy::Reject);
+	///
+	/// assert_eq!(set.insert(&1), true);
+	/// assert_eq!(set.insert(&1), false);
+	/// assert_eq!(set.to_vec(), vec![1]);
+	/// assert_eq!(set.policy(), DuplicatePolicy::Reject);
+	/// ```
+	
+	#[inline]
+	pub fn with_policy(policy: DuplicatePolicy) -> Self {
+		let mut tree = BinaryTree::new();
+		tree.policy = policy;
+		tree
+	}
+	
+	/// *English*: Method **policy()** returns tree's **DuplicatePolicy**
+	///
+	/// *Russian*: Метод **policy()** возвращает **DuplicatePolicy** дерева
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::{BinaryTree, DuplicatePolicy};
+	///
+	/// assert_eq!(BinaryTree::<i32>::new().policy(), DuplicatePolicy::KeepAll);
+	/// ```
+	
+	#[inline]
+	pub fn policy(&self) -> DuplicatePolicy {
+		self.policy
+	}
+	
+	/// *English*: Method **limit()** returns *maximum number of elements*
+	/// for bounded tree or *None* if tree is unbounded.
+	///
+	/// *Russian*: Метод **limit()** возвращает *максимальное количество элементов*
+	/// ограниченного дерева или *None*, если дерево не ограничено.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	///
+	/// assert_eq!(BinaryTree::<i32>::new().limit(), None);
+	/// assert_eq!(BinaryTree::<i32>::with_limit(10).limit(), Some(10));
+	/// ```
+	
+	#[inline]
+	pub fn limit(&self) -> Option<usize> {
+		self.limit
+	}
+	
 	/// *English*: Method **len()** returns *tree's length*.
 	///
 	/// *Russian*: Метод **len()** возвращает *длину дерева*.
@@ -225,10 +551,18 @@ impl<T> BinaryTree<T>
 	
 	/// *English*: Method **insert()** adds value to tree.
 	/// Value takes by *immutable reference* (&T), so there is no ownership.
+	/// Returns *false* if tree wasn't changed, because it's policy is *Reject*
+	/// and value is already in the tree. With *Replace* policy equal value is
+	/// overwritten, so *true* is returned, but length isn't changed.
+	/// If tree is bounded and full, it'll panic (use **try_insert()**).
 	///
 	/// *Russian*: Метод **insert()** добавляет значение в дерево.
 	/// Значение берётся по *неизменяемой ссылке* (&T), так что
-	/// владение исключено.
+	/// владение исключено. Возвращает *false*, если дерево не изменилось,
+	/// потому что его политика - *Reject*, а значение уже есть в дереве.
+	/// С политикой *Replace* равное значение перезаписывается, так что
+	/// возвращается *true*, но длина не меняется. Если дерево ограничено
+	/// и заполнено, то будет вызвана *паника* (используйте **try_insert()**).
 	///
 	/// # Example
 	///
@@ -249,7 +583,51 @@ impl<T> BinaryTree<T>
 	/// assert_eq!(tree.to_vec(), vec![1, 2, 3]);
 	/// ```
 	
-	pub fn insert(&mut self, val: &T) {
+	#[inline]
+	pub fn insert(&mut self, val: &T) -> bool {
+		match self.try_insert(val) {
+			Ok(changed) => changed,
+			Err(error) => panic!("{}", error),
+		}
+	}
+	
+	/// *English*: Method **try_insert()** adds value to tree like **insert()**,
+	/// but returns *Error::Full* instead of panic, if bounded tree is full.
+	///
+	/// *Russian*: Метод **try_insert()** добавляет значение в дерево как **insert()**,
+	/// но вместо паники возвращает *Error::Full*, если ограниченное дерево заполнено.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use binartree::error::Error;
+	///
+	/// let mut tree = BinaryTree::with_limit(1);
+	///
+	/// assert_eq!(tree.try_insert(&1), Ok(true));
+	/// assert_eq!(tree.try_insert(&1), Err(Error::Full));
+	/// assert_eq!(tree.len(), 1);
+	/// ```
+	
+	pub fn try_insert(&mut self, val: &T) -> Result<bool, Error> {
+		match self.policy {
+			DuplicatePolicy::KeepAll => (),
+			
+			DuplicatePolicy::Reject => if self.contains(val) {
+				return Ok(false);
+			},
+			
+			DuplicatePolicy::Replace => if let Some(branch) = self.top.find_mut(val).branch_mut() {
+				branch.key = *val;
+				return Ok(true);
+			},
+		}
+		
+		if matches!(self.limit, Some(limit) if self.size >= limit) {
+			return Err(Error::Full);
+		}
+		
 		if let Node::Empty = self.top {
 			self.top = Node::NonEmpty(Box::new(Branch {
 				key: (*val).clone(),
@@ -258,16 +636,28 @@ impl<T> BinaryTree<T>
 			}));
 			self.size = 1;
 		} else {
-			self.top.insert(val);
+			let depth = self.top.insert(val);
 			self.size += 1;
+			
+			if let Some(factor) = self.balance_factor {
+				if (depth + 1) as f64 > factor * min_height(self.size) as f64 {
+					self.top.rebalance_path(val, factor);
+				}
+			}
 		}
+		self.check();
+		Ok(true)
 	}
 	
 	/// *English*: Method **contains()** checks that value is in the tree.
-	/// If it's true, returns *true*, else *false*
+	/// If it's true, returns *true*, else *false*.
+	/// Value may be any borrowed form of key (like in std collections),
+	/// so there is no need to build a key just to search.
 	///
 	/// *Russian*: Метод **contains()** проверяет наличие значения в дереве
-	/// Если оно есть, то возвращает *true*, иначе *false*
+	/// Если оно есть, то возвращает *true*, иначе *false*.
+	/// Значение может быть любой заимствованной формой ключа (как в коллекциях std),
+	/// так что не нужно строить ключ только для поиска.
 	///
 	/// # Example
 	///
@@ -283,11 +673,252 @@ impl<T> BinaryTree<T>
 	/// assert_eq!(tree.contains(&2), false);
 	/// assert_eq!(tree.contains(&3), true);
 	/// assert_eq!(tree.to_vec(), vec![1, 3]);
+	///
+	/// let names = BinaryTree::from(vec!["Alice", "Bob"]);
+	/// let name = String::from("Bob");
+	/// assert!(names.contains(name.as_str()));
+	/// ```
+	
+	#[inline]
+	pub fn contains<Q>(&self, val: &Q) -> bool
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		*self.top.find(val) != Node::Empty
+	}
+	
+	/// *English*: Method **get()** returns key from tree, which is equal to value,
+	/// or *None*, if there is no such key. Value may be any borrowed form of key.
+	///
+	/// *Russian*: Метод **get()** возвращает ключ из дерева, равный значению,
+	/// или *None*, если такого ключа нет. Значение может быть любой заимствованной формой ключа.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	///
+	/// let tree = BinaryTree::from(vec!["one", "two"]);
+	/// let key = String::from("two");
+	///
+	/// assert_eq!(tree.get(key.as_str()), Some(&"two"));
+	/// assert_eq!(tree.get("three"), None);
+	/// ```
+	
+	#[inline]
+	pub fn get<Q>(&self, val: &Q) -> Option<&T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.top.find(val).branch().map(|branch| &branch.key)
+	}
+	
+	/// *English*: Method **range()** returns *sorted* iterator over keys from range.
+	/// It visits only subtrees that may contain such keys, so it takes
+	/// O(height + number of found keys).
+	///
+	/// *Russian*: Метод **range()** возвращает *отсортированный* итератор по ключам из диапазона.
+	/// Он посещает только поддеревья, в которых могут быть такие ключи,
+	/// так что занимает O(высоты + количества найденных ключей).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	/// use std::ops::Bound;
+	///
+	/// let tree = BinaryTree::from_iter(vec![5, 1, 4, 2, 3, 3]);
+	///
+	/// assert_eq!(tree.range(2..4).collect::<Vec<i32>>(), vec![2, 3, 3]);
+	/// assert_eq!(tree.range(4..).collect::<Vec<i32>>(), vec![4, 5]);
+	/// assert_eq!(tree.range(..=1).collect::<Vec<i32>>(), vec![1]);
+	///
+	/// let words = BinaryTree::from(vec!["apple", "banana", "cherry"]);
+	/// let range = (Bound::Included("b"), Bound::Excluded("c"));
+	/// assert_eq!(words.range::<str, _>(range).collect::<Vec<&str>>(), vec!["banana"]);
+	/// ```
+	
+	#[inline]
+	pub fn range<Q, R>(&self, range: R) -> TreeIter<T>
+		where T: Borrow<Q>, Q: Ord + ?Sized, R: RangeBounds<Q>
+	{
+		self.range_by(|key| {
+			let key = key.borrow();
+			let before = match range.start_bound() {
+				Bound::Included(start) => key < start,
+				Bound::Excluded(start) => key <= start,
+				Bound::Unbounded => false,
+			};
+			let after = match range.end_bound() {
+				Bound::Included(end) => key > end,
+				Bound::Excluded(end) => key >= end,
+				Bound::Unbounded => false,
+			};
+			
+			match (before, after) {
+				(true, _) => Ordering::Less,
+				(_, true) => Ordering::Greater,
+				_ => Ordering::Equal,
+			}
+		})
+	}
+	
+	/// *English*: Method **range_by()** returns *sorted* iterator over keys,
+	/// for which comparator returns *Equal*. Comparator must return *Less* for keys
+	/// before the range and *Greater* for keys after it, so it works with projections,
+	/// which are not representable as *T*. Takes O(height + number of found keys).
+	///
+	/// *Russian*: Метод **range_by()** возвращает *отсортированный* итератор по ключам,
+	/// для которых компаратор возвращает *Equal*. Компаратор должен возвращать *Less*
+	/// для ключей перед диапазоном и *Greater* для ключей после него, так что он работает
+	/// с проекциями, которые нельзя представить как *T*. Занимает O(высоты + количества найденных ключей).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// // (timestamp, id)
+	/// let events = BinaryTree::from_iter(vec![(10, 1), (20, 2), (20, 3), (30, 4)]);
+	///
+	/// let at_20 = events.range_by(|&(time, _)| time.cmp(&20));
+	/// assert_eq!(at_20.collect::<Vec<(i32, i32)>>(), vec![(20, 2), (20, 3)]);
+	/// ```
+	
+	#[inline]
+	pub fn range_by<F>(&self, cmp: F) -> TreeIter<T>
+		where F: FnMut(&T) -> Ordering
+	{
+		TreeIter {
+			iter: self.top.range_by(cmp)
+		}
+	}
+	
+	/// *English*: Method **find_by()** searches key with comparator, which returns
+	/// ordering of key relative to the target (like **slice::binary_search_by()**).
+	/// If there are several matching keys, any of them may be returned.
+	/// Returns *None*, if there is no such key. Takes O(height).
+	///
+	/// *Russian*: Метод **find_by()** ищет ключ с компаратором, который возвращает
+	/// порядок ключа относительно цели (как **slice::binary_search_by()**).
+	/// Если подходящих ключей несколько, может вернуться любой из них.
+	/// Возвращает *None*, если такого ключа нет. Занимает O(высоты).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// // (timestamp, id)
+	/// let events = BinaryTree::from_iter(vec![(10, 1), (20, 2), (30, 3)]);
+	///
+	/// assert_eq!(events.find_by(|&(time, _)| time.cmp(&20)), Some(&(20, 2)));
+	/// assert_eq!(events.find_by(|&(time, _)| time.cmp(&25)), None);
+	/// ```
+	
+	#[inline]
+	pub fn find_by<F>(&self, cmp: F) -> Option<&T>
+		where F: FnMut(&T) -> Ordering
+	{
+		self.top.find_by(cmp).branch().map(|branch| &branch.key)
+	}
+	
+	/// *English*: Method **partition_point()** returns the smallest key, for which
+	/// predicate is *false*, or *None*, if it's *true* for all keys.
+	/// Predicate must be *true* for the beginning of the sorted keys and *false* for the rest
+	/// (like in **slice::partition_point()**). Unlike slice, key itself is returned,
+	/// because tree doesn't know positions of nodes. Takes O(height).
+	///
+	/// *Russian*: Метод **partition_point()** возвращает наименьший ключ, для которого
+	/// предикат *ложен*, или *None*, если он *истинен* для всех ключей.
+	/// Предикат должен быть *истинен* для начала отсортированных ключей и *ложен* для
+	/// остальных (как в **slice::partition_point()**). В отличие от среза возвращается
+	/// сам ключ, потому что дерево не знает позиции узлов. Занимает O(высоты).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// // (timestamp, id)
+	/// let events = BinaryTree::from_iter(vec![(10, 1), (20, 2), (30, 3)]);
+	///
+	/// // the first event after 15
+	/// assert_eq!(events.partition_point(|&(time, _)| time <= 15), Some(&(20, 2)));
+	/// assert_eq!(events.partition_point(|&(time, _)| time <= 30), None);
 	/// ```
 	
 	#[inline]
-	pub fn contains(&self, val: &T) -> bool {
-		*self.top.find(&val) != Node::Empty
+	pub fn partition_point<P>(&self, pred: P) -> Option<&T>
+		where P: FnMut(&T) -> bool
+	{
+		self.top.partition_point(pred)
+	}
+	
+	/// *English*: Method **lower_bound()** returns the smallest key *above* the bound
+	/// (`>= val` for *Included*, `> val` for *Excluded*, min key for *Unbounded*)
+	/// or *None*, if there is no such key. Takes O(height).
+	///
+	/// *Russian*: Метод **lower_bound()** возвращает наименьший ключ *выше* границы
+	/// (`>= val` для *Included*, `> val` для *Excluded*, наименьший ключ для *Unbounded*)
+	/// или *None*, если такого ключа нет. Занимает O(высоты).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	/// use std::ops::Bound;
+	///
+	/// let tree = BinaryTree::from_iter(vec![10, 20, 30]);
+	///
+	/// assert_eq!(tree.lower_bound(Bound::Included(&20)), Some(&20));
+	/// assert_eq!(tree.lower_bound(Bound::Excluded(&20)), Some(&30));
+	/// assert_eq!(tree.lower_bound(Bound::Excluded(&30)), None);
+	/// assert_eq!(tree.lower_bound::<i32>(Bound::Unbounded), Some(&10));
+	/// ```
+	
+	#[inline]
+	pub fn lower_bound<Q>(&self, bound: Bound<&Q>) -> Option<&T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.top.partition_point(|key| match bound {
+			Bound::Included(val) => key.borrow() < val,
+			Bound::Excluded(val) => key.borrow() <= val,
+			Bound::Unbounded => false,
+		})
+	}
+	
+	/// *English*: Method **upper_bound()** returns the biggest key *below* the bound
+	/// (`<= val` for *Included*, `< val` for *Excluded*, max key for *Unbounded*)
+	/// or *None*, if there is no such key. Takes O(height).
+	///
+	/// *Russian*: Метод **upper_bound()** возвращает наибольший ключ *ниже* границы
+	/// (`<= val` для *Included*, `< val` для *Excluded*, наибольший ключ для *Unbounded*)
+	/// или *None*, если такого ключа нет. Занимает O(высоты).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	/// use std::ops::Bound;
+	///
+	/// let tree = BinaryTree::from_iter(vec![10, 20, 30]);
+	///
+	/// assert_eq!(tree.upper_bound(Bound::Included(&20)), Some(&20));
+	/// assert_eq!(tree.upper_bound(Bound::Excluded(&20)), Some(&10));
+	/// assert_eq!(tree.upper_bound(Bound::Excluded(&10)), None);
+	/// assert_eq!(tree.upper_bound::<i32>(Bound::Unbounded), Some(&30));
+	/// ```
+	
+	#[inline]
+	pub fn upper_bound<Q>(&self, bound: Bound<&Q>) -> Option<&T>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		self.top.upper_key(bound)
 	}
 	
 	/// *English*: Method **first()** returns *minimum value in the tree*.
@@ -315,7 +946,29 @@ impl<T> BinaryTree<T>
 	
 	#[inline]
 	pub fn first(&self) -> &T {
-		self.top.min().get_key()
+		self.try_first().expect("Empty tree")
+	}
+	
+	/// *English*: Method **try_first()** returns *minimum value in the tree*
+	/// or *Error::Empty*, if tree is empty.
+	///
+	/// *Russian*: Метод **try_first()** возвращает *минимальное значение
+	/// в дереве* или *Error::Empty*, если дерево пустое.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use binartree::error::Error;
+	/// use std::iter::FromIterator;
+	///
+	/// assert_eq!(BinaryTree::<i32>::new().try_first(), Err(Error::Empty));
+	/// assert_eq!(BinaryTree::from_iter(vec![3, 1, 2]).try_first(), Ok(&1));
+	/// ```
+	
+	#[inline]
+	pub fn try_first(&self) -> Result<&T, Error> {
+		self.top.min().branch().map(|branch| &branch.key).ok_or(Error::Empty)
 	}
 	
 	/// *English*: Method **last()** returns *maximum value in the tree*.
@@ -342,8 +995,30 @@ impl<T> BinaryTree<T>
 	/// ```
 	
 	#[inline]
-	pub fn last(&self) -> &T {
-		self.top.max().get_key()
+	pub fn last(&self) -> &T {
+		self.try_last().expect("Empty tree")
+	}
+	
+	/// *English*: Method **try_last()** returns *maximum value in the tree*
+	/// or *Error::Empty*, if tree is empty.
+	///
+	/// *Russian*: Метод **try_last()** возвращает *максимальное значение
+	/// в дереве* или *Error::Empty*, если дерево пустое.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use binartree::error::Error;
+	/// use std::iter::FromIterator;
+	///
+	/// assert_eq!(BinaryTree::<i32>::new().try_last(), Err(Error::Empty));
+	/// assert_eq!(BinaryTree::from_iter(vec![3, 1, 2]).try_last(), Ok(&3));
+	/// ```
+	
+	#[inline]
+	pub fn try_last(&self) -> Result<&T, Error> {
+		self.top.max().branch().map(|branch| &branch.key).ok_or(Error::Empty)
 	}
 	
 	/// *English*: Method **iter()** converts tree to *iterator*,
@@ -378,11 +1053,14 @@ impl<T> BinaryTree<T>
 	
 	/// *English*: Method **apend()** translates all elements
 	/// *from 2-nd tree to 1-st*. All trees are taking by *immutable reference*,
-	/// no ownership.
+	/// no ownership. If bounded tree can't take all of them, it'll *panic*
+	/// before adding anything (use **try_append()**).
 	///
 	/// *Russian*: Метод **append()** передаёт элементы *из 2 дерева
 	/// в 1*. Используемое дерево остаётся *неизменным*,
-	/// так что его *можно использовать повторно*.
+	/// так что его *можно использовать повторно*. Если ограниченное дерево
+	/// не может принять их все, то до добавления будет вызвана *паника*
+	/// (используйте **try_append()**).
 	///
 	/// # Example
 	///
@@ -401,11 +1079,64 @@ impl<T> BinaryTree<T>
 	/// assert_eq!(tree1.to_vec(), vec![1, 2, 3]);
 	/// ```
 	
+	#[inline]
 	pub fn append(&mut self, src: &Self) {
+		if let Err(error) = self.try_append(src) {
+			panic!("{}", error);
+		}
+	}
+	
+	/// *English*: Method **try_append()** translates all elements
+	/// *from 2-nd tree to 1-st* like **append()**, but if bounded tree
+	/// can't take all of them, it returns *Error::Full* and *adds nothing*.
+	/// Duplicates, which policy rejects or replaces, don't take place.
+	///
+	/// *Russian*: Метод **try_append()** передаёт элементы *из 2 дерева
+	/// в 1* как **append()**, но если ограниченное дерево не может принять
+	/// их все, то возвращает *Error::Full* и *ничего не добавляет*.
+	/// Повторы, которые политика отклоняет или заменяет, места не занимают.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use binartree::error::Error;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = BinaryTree::with_limit(3);
+	///
+	/// assert_eq!(tree.try_append(&BinaryTree::from_iter(1..3)), Ok(()));
+	/// assert_eq!(tree.try_append(&BinaryTree::from_iter(3..5)), Err(Error::Full));
+	/// assert_eq!(tree.to_vec(), vec![1, 2]);
+	/// ```
+	
+	pub fn try_append(&mut self, src: &Self) -> Result<(), Error> {
+		if matches!(self.limit, Some(limit) if self.size + self.append_growth(src) > limit) {
+			return Err(Error::Full);
+		}
+		
 		let collect = src.iter().iter;
 		for elem in collect.iter() {
 			self.insert(elem);
 		}
+		Ok(())
+	}
+	
+	/// *English*: Number of elements, which append of *src* adds under tree's policy.
+	/// Takes O(1) for *KeepAll* and O(n + m) otherwise.
+	///
+	/// *Russian*: Количество элементов, которое добавит присоединение *src* при политике дерева.
+	/// Занимает O(1) для *KeepAll* и O(n + m) иначе.
+	
+	fn append_growth(&self, src: &Self) -> usize {
+		match self.policy {
+			DuplicatePolicy::KeepAll => src.size,
+			DuplicatePolicy::Reject | DuplicatePolicy::Replace => {
+				let mut keys = src.to_vec();
+				keys.dedup();
+				filter_present(&keys, &self.to_vec(), false).len()
+			}
+		}
 	}
 	
 	/// *English*: Method **celan()** makes tree empty.
@@ -428,10 +1159,12 @@ impl<T> BinaryTree<T>
 	pub fn clear(&mut self) {
 		self.top.rec_drop();
 		self.size = 0;
+		self.check();
 	}
 	
 	/// *English*: *Removing element from tree*.
 	/// Keys, that are in subnodes are *still in tree*.
+	/// Value may be any borrowed form of key.
 	/// **USE THIS METHOD ONLY IF YOU WANT TO REMOVE
 	/// ONE ELEMENT FROM TREE. USE *MULTI_REMOVE()* IF
 	/// YOU WISH TO REMOVE MORE THAN 1 ELEM. IT'S A LOT MORE FASTER**
@@ -439,6 +1172,7 @@ impl<T> BinaryTree<T>
 	/// *Russian*: *Удаление элемента из дерева*.
 	/// Ключи, которые есть в подузлах искомого узла
 	/// *остаются в нашем дереве*.
+	/// Значение может быть любой заимствованной формой ключа.
 	/// **ИСПОЛЬЗУЙТЕ ЭТОД МЕТОД ТОЛЬК ЕСЛИ ХОТИТЕ
 	/// УДАЛИТЬ 1 ЭЛЕМЕНТ ИЗ ДЕРЕВА. ЛУЧШЕ ИСПОЛЬЗОВАТЬ
 	/// *MULTI_ERMOVE()*, КОТОРЫЙ РАБОТАЕТ В РАЗЫ БЫСТРЕЕ**
@@ -457,19 +1191,51 @@ impl<T> BinaryTree<T>
 	/// assert_eq!(tree.to_vec(), vec![2, 3, 4, 6, 7, 8, 9, 10]);
 	/// ```
 	
-	pub fn remove(&mut self, val: &T) {
+	pub fn remove<Q>(&mut self, val: &Q)
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
+		let _ = self.try_remove(val);
+	}
+	
+	/// *English*: Method **try_remove()** removes value like **remove()**,
+	/// but returns *Error::NotFound*, if there is no such value.
+	///
+	/// *Russian*: Метод **try_remove()** удаляет значение как **remove()**,
+	/// но возвращает *Error::NotFound*, если такого значения нет.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use binartree::error::Error;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = BinaryTree::from_iter(1..4);
+	///
+	/// assert_eq!(tree.try_remove(&2), Ok(()));
+	/// assert_eq!(tree.try_remove(&2), Err(Error::NotFound));
+	/// assert_eq!(tree.to_vec(), vec![1, 3]);
+	/// ```
+	
+	pub fn try_remove<Q>(&mut self, val: &Q) -> Result<(), Error>
+		where T: Borrow<Q>, Q: Ord + ?Sized
+	{
 		let keys = self.top.remove(val);
-		if keys.0 {
-			self.size -= keys.1.len() + 1;
-			self.extend(keys.1);
+		if !keys.0 {
+			return Err(Error::NotFound);
 		}
+		
+		self.size -= keys.1.len() + 1;
+		self.extend(keys.1);
+		self.check();
+		Ok(())
 	}
 	
-	/// *English*: Method **difference()** returns TreeIter<T>, which contains all elements,
-	/// that are *in 1-st tree, but not in 2-nd*,
+	/// *English*: Method **difference()** returns *sorted* SortedSeq<T>, which contains all elements,
+	/// that are *in 1-st tree, but not in 2-nd*. Takes O(n + m).
 	///
-	/// *Russian*: Метод **difference()** возвращает *"деревянный итератор"*, который
-	/// хранит все те элементы, *которые есть в 1 дереве, но которых нет во 2*.
+	/// *Russian*: Метод **difference()** возвращает *отсортированную* SortedSeq<T>, которая
+	/// хранит все те элементы, *которые есть в 1 дереве, но которых нет во 2*. Занимает O(n + m).
 	///
 	///  # Example
 	///
@@ -483,20 +1249,10 @@ impl<T> BinaryTree<T>
 	/// assert_eq!(tree_1.difference(&tree_2).collect::<Vec<i32>>(), vec![1, 2]);
 	/// ```
 	
-	pub fn difference(&self, other: &Self) -> TreeIter<T> {
-		let iter_1 = self.to_vec();
-		let iter_2 = other.to_vec();
-		
-		let mut iter = vec![];
-		for elem in iter_1 {
-			if let Err(_) = iter_2.binary_search(&elem) {
-				iter.push(elem);
-			}
-		}
-		
-		let mut it = TreeIter::new();
-		it.extend(iter);
-		it
+	pub fn difference(&self, other: &Self) -> SortedSeq<T> {
+		let first = self.to_vec();
+		let second = other.to_vec();
+		self.set_result(filter_present(&first, &second, false))
 	}
 	
 	/// *English*: Method **drain_filter()** *stoles all values* from tree,
@@ -520,35 +1276,91 @@ impl<T> BinaryTree<T>
 	/// assert_eq!(tree2.collect::<Vec<i32>>(), vec![2, 4, 6, 8, 10]);
 	/// ```
 	
-	pub fn drain_filter<F: FnMut(&T) -> bool>(&mut self, mut fun: F) -> TreeIter<T> {
-		let mut new_tree_iter = TreeIter::new();
-		let iter = self.iter();
-		
-		for elem in iter {
-			if fun(&elem) {
-				new_tree_iter.iter.push_back(elem.clone());
-			}
-		}
-		
-		let new_vec = Vec::from(new_tree_iter.clone().iter);
-		let mut old_new_vec = vec![];
-		
-		for elem in self.to_vec() {
-			if let Err(_) = new_vec.binary_search(&elem) {
-				old_new_vec.push(elem);
-			}
+	pub fn drain_filter<F: FnMut(&T) -> bool>(&mut self, fun: F) -> TreeIter<T> {
+		self.extract_if(fun).collect()
+	}
+	
+	/// *English*: Method **extract_if()** returns *lazy* iterator, which visits keys
+	/// in *ascending* order and removes those, for which predicate returns *true*.
+	/// Nodes are removed in place, survivors aren't rebuilt, and every occurrence
+	/// of equal keys is checked on it's own. If iterator is dropped before the end,
+	/// unvisited keys stay in tree. Every step descends from the top again and walks
+	/// over kept occurrences of the current key, so it takes O(height + kept equal keys).
+	///
+	/// *Russian*: Метод **extract_if()** возвращает *ленивый* итератор, который обходит
+	/// ключи *по возрастанию* и удаляет те, для которых предикат возвращает *true*.
+	/// Узлы удаляются на месте, оставшиеся не перестраиваются, а каждое вхождение
+	/// равных ключей проверяется отдельно. Если итератор уничтожен до конца,
+	/// непосещённые ключи остаются в дереве. Каждый шаг заново спускается от вершины
+	/// и проходит оставленные вхождения текущего ключа, так что занимает
+	/// O(высоты + оставленных равных ключей).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = BinaryTree::from_iter(vec![5, 1, 4, 2, 3, 2]);
+	/// let mut evens = tree.extract_if(|x| x % 2 == 0);
+	///
+	/// assert_eq!(evens.next(), Some(2));
+	/// assert_eq!(evens.next(), Some(2));
+	/// drop(evens);
+	///
+	/// assert_eq!(tree.to_vec(), vec![1, 3, 4, 5]);
+	/// ```
+	
+	#[inline]
+	pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, F>
+		where F: FnMut(&T) -> bool
+	{
+		ExtractIf {
+			tree: self,
+			pred,
+			last: None,
+			kept: 0,
 		}
-		self.clear();
-		self.extend(old_new_vec);
-		
-		new_tree_iter
 	}
 	
-	/// *English*: Method **intersection()** returns TreeIter<T>, which contains
-	/// all elements, that *are in 1-st and 2-nd tree*
+	/// *English*: Method **retain()** keeps only keys, for which predicate returns *true*.
+	/// Keys are visited in *ascending* order and removed in place,
+	/// every occurrence of equal keys is checked on it's own.
+	/// It's built on **extract_if()**, so it takes O(n × height) in total
+	/// (more, if many equal keys are kept).
+	///
+	/// *Russian*: Метод **retain()** оставляет только ключи, для которых предикат
+	/// возвращает *true*. Ключи обходятся *по возрастанию* и удаляются на месте,
+	/// каждое вхождение равных ключей проверяется отдельно.
+	/// Он построен на **extract_if()**, так что занимает O(n × высоты) всего
+	/// (больше, если оставлено много равных ключей).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = BinaryTree::from_iter(vec![1, 1, 1, 2, 2]);
+	///
+	/// // keep only the first occurrence of every key
+	/// let mut last = None;
+	/// tree.retain(|x| last.replace(*x) != Some(*x));
+	///
+	/// assert_eq!(tree.to_vec(), vec![1, 2]);
+	/// ```
+	
+	pub fn retain<F>(&mut self, mut pred: F)
+		where F: FnMut(&T) -> bool
+	{
+		self.extract_if(|key| !pred(key)).for_each(drop);
+	}
+	
+	/// *English*: Method **intersection()** returns *sorted* SortedSeq<T>, which contains
+	/// all elements, that *are in 1-st and 2-nd tree*. Takes O(n + m).
 	///
-	/// *Russian*: Метод **intersection()** возвращает *"деревянный итератор"*, который
-	/// хранит все те элементы, которые *есть и в 1, и во 2 дереве*
+	/// *Russian*: Метод **intersection()** возвращает *отсортированную* SortedSeq<T>, которая
+	/// хранит все те элементы, которые *есть и в 1, и во 2 дереве*. Занимает O(n + m).
 	///
 	///  # Example
 	///
@@ -562,20 +1374,10 @@ impl<T> BinaryTree<T>
 	/// assert_eq!(tree_1.intersection(&tree_2).collect::<Vec<i32>>(), vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
 	/// ```
 	
-	pub fn intersection(&self, other: &Self) -> TreeIter<T> {
-		let iter_1 = self.to_vec();
-		let iter_2 = other.to_vec();
-		
-		let mut iter = vec![];
-		for elem in iter_1 {
-			if let Ok(_) = iter_2.binary_search(&elem) {
-				iter.push(elem);
-			}
-		}
-		
-		let mut it = TreeIter::new();
-		it.extend(iter);
-		it
+	pub fn intersection(&self, other: &Self) -> SortedSeq<T> {
+		let first = self.to_vec();
+		let second = other.to_vec();
+		self.set_result(filter_present(&first, &second, true))
 	}
 	
 	/// *English*: Method **is_disjoint()** answers the question
@@ -605,53 +1407,113 @@ impl<T> BinaryTree<T>
 		self.intersection(other).collect::<Vec<T>>().is_empty()
 	}
 	
-	/// *English*: Method **pop_frist** *removes min value from tree*.
-	/// If tree is empty, it'll *panic*
+	/// *English*: Method **pop_frist** *removes min value from tree* and returns it.
+	/// Only the min node is removed (it's replaced by it's right child), so it takes O(height).
+	/// If tree is empty, returns *None*
 	///
-	/// *Russian*: Метод **pop_first()** *удаляет наименьший элемент дерева*.
-	/// Т.к. дерево может быть пустым, то будет вызвана *паника*
+	/// *Russian*: Метод **pop_first()** *удаляет наименьший элемент дерева* и возвращает его.
+	/// Удаляется только минимальный узел (его место занимает правый потомок),
+	/// так что это O(высоты). Если дерево пустое, возвращает *None*
 	///
 	/// # Example
 	///
 	/// ```
 	/// use binartree::tree::BinaryTree;
 	/// use std::iter::FromIterator;
-	/// use std::convert::TryInto;
 	///
 	/// let mut tree = BinaryTree::from_iter((1..6));
-	/// tree.pop_first();
-	/// tree.pop_first();
+	/// assert_eq!(tree.pop_first(), Some(1));
+	/// assert_eq!(tree.pop_first(), Some(2));
 	/// assert_eq!(tree.to_vec(), vec![3, 4, 5]);
+	///
+	/// assert_eq!(BinaryTree::<i32>::new().pop_first(), None);
+	/// ```
+	
+	pub fn pop_first(&mut self) -> Option<T> {
+		let key = self.top.pop_min()?;
+		self.size -= 1;
+		self.check();
+		Some(key)
+	}
+	
+	/// *English*: Method **try_pop_first()** *removes and returns min value*
+	/// or returns *Error::Empty*, if tree is empty.
+	///
+	/// *Russian*: Метод **try_pop_first()** *удаляет и возвращает наименьший
+	/// элемент* или возвращает *Error::Empty*, если дерево пустое.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use binartree::error::Error;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = BinaryTree::from_iter(1..3);
+	///
+	/// assert_eq!(tree.try_pop_first(), Ok(1));
+	/// assert_eq!(tree.try_pop_first(), Ok(2));
+	/// assert_eq!(tree.try_pop_first(), Err(Error::Empty));
 	/// ```
 	
-	pub fn pop_first(&mut self) {
-		let key = self.first().clone();
-		self.remove(&key);
+	#[inline]
+	pub fn try_pop_first(&mut self) -> Result<T, Error> {
+		self.pop_first().ok_or(Error::Empty)
 	}
 	
-	/// *English*: Method *pop_last()* removes *min element from tree*.
-	/// If tree is empty, it'll *panic*.
+	/// *English*: Method *pop_last()* removes *max element from tree* and returns it.
+	/// Only the max node is removed (it's replaced by it's left child), so it takes O(height).
+	/// If tree is empty, returns *None*.
 	///
-	/// *Russian*: Метод *pop_last()* удаляет *наибольший элемент дерева*.
-	/// Т.к. дерево может быть пустым, то вызывается *паника*
+	/// *Russian*: Метод *pop_last()* удаляет *наибольший элемент дерева* и возвращает его.
+	/// Удаляется только максимальный узел (его место занимает левый потомок),
+	/// так что это O(высоты). Если дерево пустое, возвращает *None*
 	///
 	/// # Example
 	///
 	/// ```
 	/// use binartree::tree::BinaryTree;
 	/// use std::iter::FromIterator;
-	/// use std::convert::TryInto;
 	///
 	/// let mut tree = BinaryTree::from_iter((1..6));
 	///
-	/// tree.pop_last();
-	/// tree.pop_last();
+	/// assert_eq!(tree.pop_last(), Some(5));
+	/// assert_eq!(tree.pop_last(), Some(4));
 	/// assert_eq!(tree.to_vec(), vec![1, 2, 3]);
+	///
+	/// assert_eq!(BinaryTree::<i32>::new().pop_last(), None);
+	/// ```
+	
+	pub fn pop_last(&mut self) -> Option<T> {
+		let key = self.top.pop_max()?;
+		self.size -= 1;
+		self.check();
+		Some(key)
+	}
+	
+	/// *English*: Method **try_pop_last()** *removes and returns max value*
+	/// or returns *Error::Empty*, if tree is empty.
+	///
+	/// *Russian*: Метод **try_pop_last()** *удаляет и возвращает наибольший
+	/// элемент* или возвращает *Error::Empty*, если дерево пустое.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use binartree::error::Error;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = BinaryTree::from_iter(1..3);
+	///
+	/// assert_eq!(tree.try_pop_last(), Ok(2));
+	/// assert_eq!(tree.try_pop_last(), Ok(1));
+	/// assert_eq!(tree.try_pop_last(), Err(Error::Empty));
 	/// ```
 	
-	pub fn pop_last(&mut self) {
-		let key = self.last().clone();
-		self.remove(&key);
+	#[inline]
+	pub fn try_pop_last(&mut self) -> Result<T, Error> {
+		self.pop_last().ok_or(Error::Empty)
 	}
 	
 	/// *English*: Method **replace_val()** changes all keys with
@@ -697,13 +1559,41 @@ impl<T> BinaryTree<T>
 		self.extend(new_tree);
 	}
 	
-	/// *English*: Method **symmetric_difference()** returns *TreeIter<T>* with keys,
-	/// that are *only in 1-st or 2-nd tree*
-	/// Elements in iterator are **not in sorted order**.
+	/// *English*: Method **try_replace_val()** changes all keys with
+	/// *some* value to *another* value like **replace_val()**,
+	/// but returns *Error::NotFound*, if there are no keys with old value.
+	///
+	/// *Russian*: Метод **try_replace_val()** заменяет все ключи
+	/// с *одним* значением на ключи с *другим* значением как **replace_val()**,
+	/// но возвращает *Error::NotFound*, если ключей со старым значением нет.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use binartree::error::Error;
+	///
+	/// let mut tree = BinaryTree::new();
+	/// tree.extend(vec![1, 1, 2]);
+	///
+	/// assert_eq!(tree.try_replace_val(&3, &4), Err(Error::NotFound));
+	/// assert_eq!(tree.try_replace_val(&1, &3), Ok(()));
+	/// assert_eq!(tree.to_vec(), vec![2, 3, 3]);
+	/// ```
+	
+	pub fn try_replace_val(&mut self, old_val: &T, new_val: &T) -> Result<(), Error> {
+		if !self.contains(old_val) {
+			return Err(Error::NotFound);
+		}
+		self.replace_val(old_val, new_val);
+		Ok(())
+	}
+	
+	/// *English*: Method **symmetric_difference()** returns *sorted* SortedSeq<T> with keys,
+	/// that are *only in 1-st or 2-nd tree*. Takes O(n + m).
 	///
-	/// *Russian*: Метод **symmetric_difference()** возвращает *"деревянный" итератор*
-	/// с ключами, которые есть *либо только в 1 дереве, либо только во 2*.
-	/// Метод возвращает **неотсортированный итератор**.
+	/// *Russian*: Метод **symmetric_difference()** возвращает *отсортированную* SortedSeq<T>
+	/// с ключами, которые есть *либо только в 1 дереве, либо только во 2*. Занимает O(n + m).
 	///
 	/// # Example
 	/// ```
@@ -713,39 +1603,25 @@ impl<T> BinaryTree<T>
 	/// let tree1 = BinaryTree::from_iter((5..16));
 	/// let tree2 = BinaryTree::from_iter((1..11));
 	///
-	/// assert_eq!(tree1.symmetric_difference(&tree2).collect::<Vec<i32>>(), vec![11, 12, 13, 14, 15, 1, 2, 3, 4]);
+	/// assert_eq!(tree1.symmetric_difference(&tree2).collect::<Vec<i32>>(), vec![1, 2, 3, 4, 11, 12, 13, 14, 15]);
 	/// ```
 	
-	pub fn symmetric_difference(&self, other: &Self) -> TreeIter<T> {
-		let iter_1 = self.to_vec();
-		let iter_2 = other.to_vec();
-		
-		let mut iter = vec![];
+	pub fn symmetric_difference(&self, other: &Self) -> SortedSeq<T> {
+		let first = self.to_vec();
+		let second = other.to_vec();
 		
-		for elem in &iter_1 {
-			if let Err(_) = iter_2.binary_search(&elem) {
-				iter.push(elem.clone());
-			}
-		}
-		
-		for elem in iter_2 {
-			if let Err(_) = iter_1.binary_search(&elem) {
-				iter.push(elem);
-			}
-		}
-		
-		let mut it = TreeIter::new();
-		it.extend(iter);
-		it
+		let mut seq = SortedSeq::from_sorted(filter_present(&first, &second, false));
+		seq.merge(SortedSeq::from_sorted(filter_present(&second, &first, false)));
+		self.set_result(seq.to_deque())
 	}
 	
-	/// *English*: Method **union()** creates iterator which contains
-	/// *elements from 1-st and 2-nd trees*.
-	/// Elements in iterator are **not in sorted order**.
+	/// *English*: Method **union()** creates *sorted* SortedSeq<T> which contains
+	/// *all elements from 1-st tree and elements from 2-nd tree, which are not in 1-st*.
+	/// Takes O(n + m).
 	///
-	/// *Russian*: Метод **гтшщт()** создаёт итереатор, содержащий
-	/// *элементы из 1 и 2 деревьев*.
-	/// Метод возвращает **неотсортированный итератор**.
+	/// *Russian*: Метод **union()** создаёт *отсортированную* SortedSeq<T>, содержащую
+	/// *все элементы 1 дерева и элементы 2 дерева, которых нет в 1*.
+	/// Занимает O(n + m).
 	///
 	/// # Example
 	///
@@ -759,11 +1635,41 @@ impl<T> BinaryTree<T>
 	/// assert_eq!(tree1.union(&tree2).collect::<BinaryTree<i32>>().to_vec(), (1..20).collect::<Vec<i32>>());
 	/// ```
 	
-	pub fn union(&self, other: &Self) -> TreeIter<T> {
-		let mut iter = TreeIter::new();
-		iter.extend(self.symmetric_difference(other));
-		iter.extend(self.intersection(other));
-		iter
+	pub fn union(&self, other: &Self) -> SortedSeq<T> {
+		let first = self.to_vec();
+		let second = other.to_vec();
+		
+		let mut seq = SortedSeq::from_sorted(VecDeque::from(first.clone()));
+		seq.merge(SortedSeq::from_sorted(filter_present(&second, &first, false)));
+		self.set_result(seq.to_deque())
+	}
+	
+	/// *English*: Creates empty unbounded tree with the same
+	/// policy and balance factor.
+	///
+	/// *Russian*: Создаёт пустое неограниченное дерево с такими же
+	/// политикой и коэффициентом баланса.
+	
+	#[inline]
+	pub(crate) fn empty_like(&self) -> Self {
+		BinaryTree {
+			top: Node::Empty,
+			size: 0,
+			balance_factor: self.balance_factor,
+			limit: None,
+			policy: self.policy,
+		}
+	}
+	
+	/// *English*: Result of set operation. If tree has no duplicates
+	/// (policy isn't *KeepAll*), result is sorted and has no duplicates too.
+	///
+	/// *Russian*: Результат операции над множествами. Если в дереве нет повторов
+	/// (политика не *KeepAll*), результат отсортирован и тоже не содержит повторов.
+	
+	#[inline]
+	pub(crate) fn set_result(&self, elems: VecDeque<T>) -> SortedSeq<T> {
+		set_result(self.policy, elems)
 	}
 	
 	/// *English*: You should use method **multi_remove()**
@@ -804,6 +1710,222 @@ impl<T> BinaryTree<T>
 		self.clear();
 		self.extend(new_tree);
 	}
+	
+	/// *English*: Method **height()** returns *number of levels* in the tree.
+	/// Empty tree has height 0, tree with one element has height 1.
+	///
+	/// *Russian*: Метод **height()** возвращает *количество уровней* дерева.
+	/// Высота пустого дерева - 0, дерева из одного элемента - 1.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let balanced = BinaryTree::from_iter(vec![2, 1, 3]);
+	/// assert_eq!(balanced.height(), 2);
+	///
+	/// let list = BinaryTree::from_iter(1..4);
+	/// assert_eq!(list.height(), 3);
+	/// ```
+	
+	#[inline]
+	pub fn height(&self) -> usize {
+		self.top.height()
+	}
+	
+	/// *English*: Method **depth_of()** returns *depth of the value* in the tree
+	/// (top node has depth 0). If there are equal values, returns depth of the closest one.
+	/// If value isn't in the tree, returns *None*.
+	///
+	/// *Russian*: Метод **depth_of()** возвращает *глубину значения* в дереве
+	/// (глубина головного узла - 0). Если одинаковых значений несколько,
+	/// возвращает глубину ближайшего. Если значения нет, возвращает *None*.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let tree = BinaryTree::from_iter(vec![2, 1, 3, 4]);
+	///
+	/// assert_eq!(tree.depth_of(&2), Some(0));
+	/// assert_eq!(tree.depth_of(&1), Some(1));
+	/// assert_eq!(tree.depth_of(&4), Some(2));
+	/// assert_eq!(tree.depth_of(&5), None);
+	/// ```
+	
+	#[inline]
+	pub fn depth_of(&self, val: &T) -> Option<usize> {
+		self.top.depth_of(val)
+	}
+	
+	/// *English*: Method **leaf_count()** returns *number of nodes without children*.
+	///
+	/// *Russian*: Метод **leaf_count()** возвращает *количество узлов без потомков*.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let tree = BinaryTree::from_iter(vec![4, 2, 6, 1, 3]);
+	/// assert_eq!(tree.leaf_count(), 3);
+	/// ```
+	
+	#[inline]
+	pub fn leaf_count(&self) -> usize {
+		self.top.leaf_count()
+	}
+	
+	/// *English*: Method **rebalance()** rebuilds the tree *in place* to the
+	/// perfectly balanced one with *Day-Stout-Warren* algorithm:
+	/// O(n) time and O(1) extra memory (no keys are copied).
+	/// Use it when **stats()** shows that tree became degenerate.
+	///
+	/// *Russian*: Метод **rebalance()** перестраивает дерево *на месте*
+	/// в идеально сбалансированное алгоритмом *Дэя-Стаута-Уоррена*:
+	/// O(n) времени и O(1) дополнительной памяти (ключи не копируются).
+	/// Используйте его, когда **stats()** показывает, что дерево выродилось.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = BinaryTree::from_iter(1..16);
+	/// assert_eq!(tree.height(), 15);
+	///
+	/// tree.rebalance();
+	/// assert_eq!(tree.height(), 4);
+	/// assert_eq!(tree.to_vec(), (1..16).collect::<Vec<i32>>());
+	/// ```
+	
+	#[inline]
+	pub fn rebalance(&mut self) {
+		self.top.rebalance();
+		self.check();
+	}
+	
+	/// *English*: Method **validate()** checks the *structure of the tree*:
+	/// keys are in the right order (*left ≤ key ≤ right* in every node),
+	/// there are no duplicates if policy forbids them and
+	/// length is equal to the real number of nodes. It takes O(n) time.
+	/// Useful in tests and fuzzing. If crate is built with *validate* feature,
+	/// tree calls it after every mutation in debug builds.
+	///
+	/// *Russian*: Метод **validate()** проверяет *структуру дерева*:
+	/// ключи стоят в правильном порядке (*левый ≤ ключ ≤ правый* в каждом узле),
+	/// нет повторов, если политика их запрещает, и
+	/// длина равна реальному количеству узлов. Работает за O(n).
+	/// Полезен в тестах и фаззинге. Если крейт собран с фичей *validate*,
+	/// дерево вызывает его после каждого изменения в отладочных сборках.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let mut tree = BinaryTree::from_iter(vec![5, 3, 8, 3, 1]);
+	/// assert_eq!(tree.validate(), Ok(()));
+	///
+	/// tree.rebalance();
+	/// tree.remove(&3);
+	/// assert_eq!(tree.validate(), Ok(()));
+	/// ```
+	
+	pub fn validate(&self) -> Result<(), InvariantError> {
+		let count = self.top.validate(self.policy != DuplicatePolicy::KeepAll)?;
+		if count != self.size {
+			return Err(InvariantError::Size { expected: self.size, actual: count });
+		}
+		Ok(())
+	}
+	
+	/// *English*: Panics if tree is broken. Works only in debug builds
+	/// with *validate* feature, otherwise does nothing.
+	///
+	/// *Russian*: Паникует, если дерево сломано. Работает только в отладочных
+	/// сборках с фичей *validate*, иначе ничего не делает.
+	
+	#[inline]
+	pub(crate) fn check(&self) {
+		if cfg!(feature = "validate") {
+			debug_assert_eq!(self.validate(), Ok(()));
+		}
+	}
+	
+	/// *English*: Method **stats()** returns *TreeStats* with full report
+	/// about tree's shape: leaf depths, node count per level and imbalance ratio.
+	/// Check **stats.rs** for more information.
+	///
+	/// *Russian*: Метод **stats()** возвращает *TreeStats* - полный отчёт
+	/// о форме дерева: глубины листьев, количество узлов на уровнях и коэффициент
+	/// несбалансированности. Изучите **stats.rs** для полного понимания.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use binartree::tree::BinaryTree;
+	/// use std::iter::FromIterator;
+	///
+	/// let tree = BinaryTree::from_iter(1..5);
+	/// let stats = tree.stats();
+	///
+	/// assert_eq!(stats.height, 4);
+	/// assert_eq!(stats.level_sizes, vec![1, 1, 1, 1]);
+	/// assert_eq!(stats.min_leaf_depth, 3);
+	/// assert_eq!(stats.max_leaf_depth, 3);
+	/// assert_eq!(stats.imbalance_ratio, 4.0 / 3.0);
+	/// ```
+	
+	#[inline]
+	pub fn stats(&self) -> TreeStats {
+		TreeStats::from_node(&self.top)
+	}
+}
+
+/// *English*: Sorted result of set operation for tree with given policy.
+/// If policy isn't *KeepAll*, duplicates are removed.
+///
+/// *Russian*: Отсортированный результат операции над множествами для дерева с данной политикой.
+/// Если политика не *KeepAll*, повторы удаляются.
+
+pub(crate) fn set_result<T>(policy: DuplicatePolicy, elems: VecDeque<T>) -> SortedSeq<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	let mut seq = SortedSeq::from_sorted(elems);
+	if policy != DuplicatePolicy::KeepAll {
+		seq.dedup();
+	}
+	seq
+}
+
+/// *English*: Elements of sorted *src*, which are (or aren't, if *present* is *false*)
+/// in sorted *other*. Takes O(n + m).
+///
+/// *Russian*: Элементы отсортированного *src*, которые есть (или которых нет,
+/// если *present* - *false*) в отсортированном *other*. Занимает O(n + m).
+
+pub(crate) fn filter_present<T>(src: &[T], other: &[T], present: bool) -> VecDeque<T>
+	where T: Copy + Clone + Ord + Eq
+{
+	let mut result = VecDeque::new();
+	let mut i = 0;
+	for elem in src {
+		while i < other.len() && other[i] < *elem {
+			i += 1;
+		}
+		if (i < other.len() && other[i] == *elem) == present {
+			result.push_back(*elem);
+		}
+	}
+	result
 }
 
 /// *English*: Search function in vector.
@@ -964,9 +2086,11 @@ impl<T> BitAnd for &BinaryTree<T>
 	
 	type Output = BinaryTree<T>;
 	
-	/// *English*: Creates new binary tree from 2 trees
+	/// *English*: Creates new binary tree from 2 trees.
+	/// New tree has the same policy and balance factor as the 1-st one.
 	///
-	/// *Russian*: Создаёт дерево из 2-х других
+	/// *Russian*: Создаёт дерево из 2-х других.
+	/// У нового дерева такие же политика и коэффициент баланса, как у 1-го.
 	///
 	/// # Example
 	///
@@ -981,7 +2105,9 @@ impl<T> BitAnd for &BinaryTree<T>
 	/// ```
 	
 	fn bitand(self, rhs: Self) -> BinaryTree<T> {
-		BinaryTree::from_iter(self.intersection(rhs))
+		let mut tree = self.empty_like();
+		tree.extend(self.intersection(rhs));
+		tree
 	}
 }
 
@@ -1003,9 +2129,11 @@ impl<T> BitOr for &BinaryTree<T>
 	
 	type Output = BinaryTree<T>;
 	
-	/// *English*: Creates new binary tree from 2 trees
+	/// *English*: Creates new binary tree from 2 trees.
+	/// New tree has the same policy and balance factor as the 1-st one.
 	///
-	/// *Russian*: Создаёт дерево из 2-х других
+	/// *Russian*: Создаёт дерево из 2-х других.
+	/// У нового дерева такие же политика и коэффициент баланса, как у 1-го.
 	///
 	/// # Example
 	///
@@ -1020,7 +2148,9 @@ impl<T> BitOr for &BinaryTree<T>
 	/// ```
 	
 	fn bitor(self, rhs: Self) -> BinaryTree<T> {
-		BinaryTree::from_iter(self.union(&rhs))
+		let mut tree = self.empty_like();
+		tree.extend(self.union(&rhs));
+		tree
 	}
 }
 
@@ -1041,9 +2171,11 @@ impl<T> BitXor for &BinaryTree<T>
 	
 	type Output = BinaryTree<T>;
 	
-	/// *English*: Creates new binary tree from 2 trees
+	/// *English*: Creates new binary tree from 2 trees.
+	/// New tree has the same policy and balance factor as the 1-st one.
 	///
-	/// *Russian*: Создаёт дерево из 2-х других
+	/// *Russian*: Создаёт дерево из 2-х других.
+	/// У нового дерева такие же политика и коэффициент баланса, как у 1-го.
 	///
 	/// # Example
 	///
@@ -1058,7 +2190,9 @@ impl<T> BitXor for &BinaryTree<T>
 	/// ```
 	
 	fn bitxor(self, rhs: Self) -> BinaryTree<T> {
-		BinaryTree::from_iter(self.symmetric_difference(&rhs))
+		let mut tree = self.empty_like();
+		tree.extend(self.symmetric_difference(&rhs));
+		tree
 	}
 }
 